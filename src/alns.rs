@@ -0,0 +1,322 @@
+//! # Adaptive Large Neighborhood Search
+//!
+//! This module contains an adaptive large neighborhood search (ALNS) heuristic for QUBO problems. Each iteration
+//! destroys part of the current solution by freeing a subset of the variables, and repairs it by solving the QUBO
+//! over the freed variables exactly, with every other variable fixed at its current value. Repairs that make the
+//! solution worse are accepted by a simulated annealing criterion, and the destroy operator and the number of freed
+//! variables adapt to how successful they have been.
+
+use crate::exact::brute_force;
+use crate::qubo::Qubo;
+use crate::simulated_annealing::{metropolis, AnnealingSchedule};
+use ndarray::Array1;
+use smolprng::{Algorithm, PRNG};
+use std::collections::HashMap;
+
+/// The smallest number of variables that are freed in an iteration
+const MIN_DESTROY_SIZE: usize = 2;
+
+/// The largest number of variables that are freed in an iteration, this bounds the cost of the exact repair
+const MAX_DESTROY_SIZE: usize = 16;
+
+/// How quickly the weights of the destroy operators follow their recent scores
+const REACTION_FACTOR: f64 = 0.2;
+
+/// The smallest weight of a destroy operator, so that no operator is ever ruled out
+const MIN_WEIGHT: f64 = 0.1;
+
+/// The ways to pick the variables that are freed in an iteration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Destroy {
+    /// Free uniformly random variables
+    Random,
+    /// Free a random variable and the variables around it in the interaction graph
+    Neighborhood,
+}
+
+const DESTROY_OPERATORS: [Destroy; 2] = [Destroy::Random, Destroy::Neighborhood];
+
+/// Runs adaptive large neighborhood search starting from x_0, and returns the best solution seen over all iterations.
+///
+/// Each iteration frees a subset of the variables and sets them to the best values given the rest of the solution,
+/// by enumerating the reduced QUBO over the freed variables. An improvement is always accepted. If the current values
+/// are already the best for the freed variables, then the first of them is flipped and the rest are solved again,
+/// which gives a different and possibly worse solution. That is accepted with probability $\exp(-\Delta / T)$, where
+/// the temperature $T$ follows the cooling schedule, so that the search can leave local minima. The
+/// number of freed variables shrinks by one after each improvement and grows by one otherwise, up to
+/// `MAX_DESTROY_SIZE`. The destroy operator is picked at random, in proportion to weights that follow how often each
+/// operator has improved the solution.
+///
+/// If `history` is given, then the objective of the current solution is appended to it after every iteration, so the
+/// accepted moves that made the solution worse can be seen.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::alns::alns;
+/// use hercules::simulated_annealing::AnnealingSchedule;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(100, &mut prng, 0.1);
+///
+/// // run ALNS from the zero vector for 200 iterations, cooling from a temperature of 1 down to 0.001
+/// let x_0 = ndarray::Array1::zeros(p.num_x());
+/// let schedule = AnnealingSchedule::geometric(1.0, 0.001, 200);
+/// let x_sol = alns(&p, x_0, schedule, &mut prng, None);
+/// ```
+pub fn alns<T: Algorithm>(
+    qubo: &Qubo,
+    x_0: Array1<usize>,
+    schedule: AnnealingSchedule,
+    prng: &mut PRNG<T>,
+    mut history: Option<&mut Vec<f64>>,
+) -> Array1<usize> {
+    let num_x = qubo.num_x();
+    if num_x == 0 {
+        return x_0;
+    }
+
+    let q_sym = qubo.make_symmetric();
+    let max_size = MAX_DESTROY_SIZE.min(num_x);
+    let min_size = MIN_DESTROY_SIZE.min(max_size);
+
+    let mut x = x_0;
+    let mut obj = qubo.eval_usize(&x);
+    let mut best_x = x.clone();
+    let mut best_obj = obj;
+    let mut destroy_size = min_size;
+    let mut weights = [1.0; DESTROY_OPERATORS.len()];
+
+    // the current solution, as the background of the sub-QUBO over the freed variables
+    let mut background = (0..num_x)
+        .map(|i| (i, x[i] as f64))
+        .collect::<HashMap<usize, f64>>();
+
+    for iter in 0..schedule.num_iters {
+        let temp = schedule.temperature(iter);
+        let operator = select_operator(&weights, prng);
+        let free = match DESTROY_OPERATORS[operator] {
+            Destroy::Random => random_subset(num_x, destroy_size, prng),
+            Destroy::Neighborhood => neighborhood_subset(&q_sym, destroy_size, prng),
+        };
+
+        // hold every other variable at its current value, and solve over the freed variables
+        let (sub_qubo, members) = qubo.induced_subproblem(&free, &background);
+        let (x_sub, sub_obj) = brute_force(&sub_qubo);
+
+        let improved = sub_obj < obj - 1E-10;
+        let moves = if improved {
+            Some(
+                members
+                    .into_iter()
+                    .zip(x_sub)
+                    .collect::<Vec<(usize, usize)>>(),
+            )
+        } else if let Some((&pivot, rest)) = free.split_first() {
+            // the current values are the best for the freed variables, so flip one and solve over the rest instead
+            let flipped = 1 - x[pivot];
+
+            background.insert(pivot, flipped as f64);
+            let (sub_qubo, members) = qubo.induced_subproblem(rest, &background);
+            background.insert(pivot, x[pivot] as f64);
+            let (x_sub, sub_obj) = brute_force(&sub_qubo);
+
+            metropolis(sub_obj - obj, temp, prng).then(|| {
+                let mut moves = members
+                    .into_iter()
+                    .zip(x_sub)
+                    .collect::<Vec<(usize, usize)>>();
+                moves.push((pivot, flipped));
+                moves
+            })
+        } else {
+            // the destroy size is at least one, so this is never reached
+            None
+        };
+
+        if let Some(moves) = moves {
+            for (i, value) in moves {
+                x[i] = value;
+                background.insert(i, value as f64);
+            }
+            obj = qubo.eval_usize(&x);
+
+            if obj < best_obj {
+                best_obj = obj;
+                best_x.clone_from(&x);
+            }
+        }
+
+        if improved {
+            destroy_size = (destroy_size - 1).max(min_size);
+        } else {
+            destroy_size = (destroy_size + 1).min(max_size);
+        }
+
+        // move the weight of the operator toward its score in this iteration
+        let score = if improved { 1.0 } else { 0.0 };
+        weights[operator] =
+            ((1.0 - REACTION_FACTOR) * weights[operator] + REACTION_FACTOR * score).max(MIN_WEIGHT);
+
+        if let Some(history) = history.as_mut() {
+            history.push(obj);
+        }
+    }
+
+    best_x
+}
+
+/// Picks a destroy operator at random, in proportion to the weights
+fn select_operator<T: Algorithm>(weights: &[f64], prng: &mut PRNG<T>) -> usize {
+    let total = weights.iter().sum::<f64>();
+    let mut target = prng.gen_f64() * total;
+
+    for (operator, &weight) in weights.iter().enumerate() {
+        if target < weight {
+            return operator;
+        }
+        target -= weight;
+    }
+
+    weights.len() - 1
+}
+
+/// Picks `size` distinct variables uniformly at random
+fn random_subset<T: Algorithm>(num_x: usize, size: usize, prng: &mut PRNG<T>) -> Vec<usize> {
+    // a partial Fisher-Yates shuffle of the variables
+    let mut variables = (0..num_x).collect::<Vec<usize>>();
+    for k in 0..size {
+        #[allow(clippy::cast_possible_truncation)]
+        // the number of variables is bounded by usize::MAX
+        let swap = k + prng.gen_u64() as usize % (num_x - k);
+        variables.swap(k, swap);
+    }
+
+    variables.truncate(size);
+    variables
+}
+
+/// Picks a random variable and then the variables closest to it in the interaction graph of the symmetric QUBO, in
+/// breadth first order, until there are `size` variables. If its component is too small, then the remaining variables
+/// are added at random.
+fn neighborhood_subset<T: Algorithm>(q_sym: &Qubo, size: usize, prng: &mut PRNG<T>) -> Vec<usize> {
+    let num_x = q_sym.num_x();
+    let mut is_picked = vec![false; num_x];
+    let mut picked = Vec::with_capacity(size);
+
+    #[allow(clippy::cast_possible_truncation)]
+    // the number of variables is bounded by usize::MAX
+    let root = prng.gen_u64() as usize % num_x;
+    is_picked[root] = true;
+    picked.push(root);
+
+    let mut next = 0;
+    while picked.len() < size && next < picked.len() {
+        let i = picked[next];
+        next += 1;
+
+        if let Some(row) = q_sym.q.outer_view(i) {
+            for (j, &value) in row.iter() {
+                if picked.len() < size && value != 0.0 && !is_picked[j] {
+                    is_picked[j] = true;
+                    picked.push(j);
+                }
+            }
+        }
+    }
+
+    // fill up with random variables that were not reached
+    if picked.len() < size {
+        let rest = (0..num_x)
+            .filter(|&i| !is_picked[i])
+            .collect::<Vec<usize>>();
+        for k in random_subset(rest.len(), size - picked.len(), prng) {
+            picked.push(rest[k]);
+        }
+    }
+
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alns::{alns, neighborhood_subset, random_subset};
+    use crate::exact::brute_force;
+    use crate::qubo::Qubo;
+    use crate::simulated_annealing::AnnealingSchedule;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_subsets() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(30, &mut prng, 0.1).make_symmetric();
+
+        for size in [1, 5, 30] {
+            for mut subset in [
+                random_subset(30, size, &mut prng),
+                neighborhood_subset(&p, size, &mut prng),
+            ] {
+                // the variables are distinct and in range
+                subset.sort_unstable();
+                subset.dedup();
+                assert_eq!(subset.len(), size);
+                assert!(subset.iter().all(|&i| i < 30));
+            }
+        }
+    }
+
+    #[test]
+    fn test_alns_optimal() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(14, &mut prng, 0.5);
+        let (_, opt) = brute_force(&p);
+
+        // the destroy size grows while there is no improvement, and freeing every variable enumerates the QUBO
+        let schedule = AnnealingSchedule::geometric(1.0, 0.001, 50);
+        let x_alns = alns(&p, Array1::zeros(14), schedule, &mut prng, None);
+        assert!((p.eval_usize(&x_alns) - opt).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_alns_improves() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(200, &mut prng, 0.05);
+
+        let x_0 = Array1::ones(p.num_x());
+        let schedule = AnnealingSchedule::geometric(1.0, 0.001, 100);
+        let x_alns = alns(&p, x_0.clone(), schedule, &mut prng, None);
+
+        assert!(p.eval_usize(&x_alns) < p.eval_usize(&x_0));
+    }
+
+    #[test]
+    fn test_alns_accepts_worse() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(30, &mut prng, 0.2);
+        let x_0 = Array1::zeros(p.num_x());
+        let obj_0 = p.eval_usize(&x_0);
+
+        // at a high temperature, moves that make the current solution worse are accepted
+        let mut history = Vec::new();
+        let schedule = AnnealingSchedule::geometric(1E6, 1E6, 20);
+        let x_alns = alns(&p, x_0.clone(), schedule, &mut prng, Some(&mut history));
+        assert_eq!(history.len(), 20);
+        assert!(history.windows(2).any(|w| w[1] > w[0] + 1e-10));
+
+        // the best solution seen is returned, which is never worse than the start or any current solution
+        let best = p.eval_usize(&x_alns);
+        assert!(best <= obj_0);
+        assert!(history.iter().all(|&obj| best <= obj + 1e-10));
+
+        // at a temperature of zero, only improvements are accepted
+        let mut history = Vec::new();
+        let schedule = AnnealingSchedule::linear(0.0, 0.0, 20);
+        alns(&p, x_0, schedule, &mut prng, Some(&mut history));
+        assert!(history.windows(2).all(|w| w[1] <= w[0]));
+    }
+}
@@ -0,0 +1,100 @@
+//! This module contains bound tightening for the branch and bound algorithm, which fixes more variables at a node from
+//! what is already known about it
+//!
+//! Currently the following methods are implemented:
+//! - Reduced cost fixing, from the duals of the relaxation of the node and the objective of the incumbent
+
+use crate::branch_node::QuboBBNode;
+use ndarray::Array1;
+use std::collections::HashMap;
+
+/// Finds the free variables of a node that can be fixed from the reduced costs of its relaxation
+///
+/// A variable is fixed if moving it away from its bound in the relaxed solution gives a lower bound that is worse than
+/// the incumbent. The lower bound and solution of the node must be those of its relaxation, and the duals are the
+/// reduced costs of that relaxation, as given by `solve_relaxed_qp_with_duals`. As the relaxation is convex, any point
+/// of the node with a variable on the other side of a reduced cost $r_i$ has an objective of at least the lower bound
+/// plus $|r_i|$. So if that is strictly above the incumbent, the variable is fixed to the bound it is held at.
+///
+/// The fixings are returned on their own, and do not include the fixed variables of the node. The B&B solver applies
+/// them at every node it solves when `reduced_cost_fixing` is set in its options.
+pub fn reduced_cost_fixing(
+    node: &QuboBBNode,
+    relaxation_duals: &Array1<f64>,
+    incumbent: f64,
+) -> HashMap<usize, usize> {
+    relaxation_duals
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !node.fixed_variables.contains_key(i))
+        .filter_map(|(i, &reduced_cost)| {
+            // a positive reduced cost holds the variable at zero, and a negative one holds it at one
+            let value = usize::from(reduced_cost < 0.0);
+            (node.lower_bound + reduced_cost.abs() > incumbent).then_some((i, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bounds::reduced_cost_fixing;
+    use crate::branch_node::QuboBBNode;
+    use crate::exact::brute_force;
+    use crate::qubo::Qubo;
+    use crate::subproblemsolvers::clarabel_qp::solve_relaxed_qp_with_duals;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+    use sprs::CsMat;
+    use std::collections::HashMap;
+
+    fn make_relaxed_node(
+        p: &Qubo,
+        fixed_variables: HashMap<usize, usize>,
+    ) -> (QuboBBNode, Array1<f64>) {
+        let (lower_bound, solution, duals) = solve_relaxed_qp_with_duals(p, &fixed_variables);
+        let node = QuboBBNode {
+            lower_bound,
+            solution,
+            ..QuboBBNode::root(p.num_x(), fixed_variables)
+        };
+        (node, duals)
+    }
+
+    #[test]
+    fn test_reduced_cost_fixing() {
+        // x_0 is held at zero, x_1 at one, and x_2 is in the interior, so it has no reduced cost
+        let c = Array1::from_vec(vec![2.0, -3.0, -0.5]);
+        let p = Qubo::new_with_c(CsMat::eye(3), c);
+        let (node, duals) = make_relaxed_node(&p, HashMap::new());
+        let (_, opt) = brute_force(&p);
+
+        // moving either variable costs 2, so with the optimal incumbent both are fixed, and with a poor one neither is
+        let fixings = reduced_cost_fixing(&node, &duals, opt);
+        assert_eq!(fixings, HashMap::from([(0, 0), (1, 1)]));
+        assert!(reduced_cost_fixing(&node, &duals, 0.0).is_empty());
+
+        // the fixed variables of the node are not fixed again
+        let (node, duals) = make_relaxed_node(&p, HashMap::from([(0, 0)]));
+        let fixings = reduced_cost_fixing(&node, &duals, opt);
+        assert_eq!(fixings, HashMap::from([(1, 1)]));
+    }
+
+    #[test]
+    fn test_reduced_cost_fixing_keeps_optimum() {
+        let mut prng = make_test_prng();
+        let mut num_fixings = 0;
+
+        for _ in 0..10 {
+            let p = Qubo::make_random_qubo(12, &mut prng, 0.3).convex_symmetric_form();
+            let (x_opt, opt) = brute_force(&p);
+            let (node, duals) = make_relaxed_node(&p, HashMap::new());
+
+            // every fixing agrees with the optimum, as the other value can not beat an incumbent just above it
+            let fixings = reduced_cost_fixing(&node, &duals, opt + 1E-6);
+            assert!(fixings.iter().all(|(&i, &value)| x_opt[i] == value));
+            num_fixings += fixings.len();
+        }
+
+        assert!(num_fixings > 0);
+    }
+}
@@ -8,6 +8,73 @@ pub struct QuboBBNode {
     pub lower_bound: f64,
     pub solution: Array1<f64>,
     pub fixed_variables: HashMap<usize, usize>,
+    pub branch_variable: Option<usize>,
+    /// The seed of the random decisions made at this node, drawn from the solver's generator when it is processed
+    pub seed: u64,
+    /// The number of branching decisions from the root node to this node
+    pub depth: usize,
+    /// The index of this node in the recorded tree, if the solver records the tree
+    pub id: usize,
+}
+
+/// A node of the recorded branch and bound tree, with the branching decision that created it
+#[derive(Clone, Debug)]
+pub struct TreeRecord {
+    /// The index of the parent node, or None for the root node
+    pub parent: Option<usize>,
+    /// The variable that was fixed to create this node and its value, or None for the root node
+    pub branch: Option<(usize, usize)>,
+    /// The lower bound of the node, which is the bound of its parent until it is branched on
+    pub lower_bound: f64,
+    /// The variable this node was branched on, or None if it was not branched on
+    pub branch_variable: Option<usize>,
+}
+
+impl QuboBBNode {
+    /// Creates a root node of a QUBO with `num_x` variables, with the given fixed variables and no lower bound yet
+    pub(crate) fn root(num_x: usize, fixed_variables: HashMap<usize, usize>) -> Self {
+        Self {
+            lower_bound: f64::NEG_INFINITY,
+            solution: Array1::zeros(num_x),
+            fixed_variables,
+            branch_variable: None,
+            seed: 0,
+            depth: 0,
+            id: 0,
+        }
+    }
+
+    /// The number of variables that are fixed at this node
+    pub fn num_fixed(&self) -> usize {
+        self.fixed_variables.len()
+    }
+
+    /// Checks if every one of the `n` variables of the QUBO is fixed, so the node is a single binary point and there is
+    /// nothing left to branch on
+    ///
+    /// Example:
+    /// ``` rust
+    /// use hercules::branch_node::QuboBBNode;
+    /// use ndarray::Array1;
+    /// use std::collections::HashMap;
+    ///
+    /// let node = QuboBBNode {
+    ///     lower_bound: 0.0,
+    ///     solution: Array1::zeros(2),
+    ///     fixed_variables: HashMap::from([(0, 1)]),
+    ///     branch_variable: None,
+    ///     seed: 0,
+    ///     depth: 1,
+    ///     id: 0,
+    /// };
+    ///
+    /// // one of the two variables is still free
+    /// assert_eq!(node.num_fixed(), 1);
+    /// assert!(!node.is_leaf(2));
+    /// ```
+    pub fn is_leaf(&self, n: usize) -> bool {
+        self.num_fixed() == n
+    }
 }
 
 impl Eq for QuboBBNode {}
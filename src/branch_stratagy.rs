@@ -1,6 +1,6 @@
 use crate::branch_node::QuboBBNode;
 use crate::branch_subproblem::SubProblemSolver;
-use crate::branchbound::BBSolver;
+use crate::branchbound::{BBSolver, Event};
 use crate::preprocess::preprocess_qubo;
 use ndarray::Array1;
 use smolprng::{JsfLarge, PRNG};
@@ -15,13 +15,20 @@ pub enum BranchStrategy {
     MostEdges,
     LargestEdges,
     MostFixed,
-    FullStrongBranching,
+    FullStrongBranch,
     PartialStrongBranching,
     RoundRobin,
+    PseudoCost,
+    Reliability,
 }
 
 impl BranchStrategy {
-    pub fn make_branch(self, bb_solver: &BBSolver, node: &QuboBBNode) -> usize {
+    /// Selects the variable of the node to branch on with this strategy, or None if every variable is fixed
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the strategy selects a fixed variable.
+    pub fn make_branch(self, bb_solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
         let branch_variable = match self {
             Self::FirstNotFixed => first_not_fixed(bb_solver, node),
             Self::MostViolated => most_violated(bb_solver, node),
@@ -31,20 +38,262 @@ impl BranchStrategy {
             Self::MostEdges => most_edges(bb_solver, node),
             Self::LargestEdges => largest_edges(bb_solver, node),
             Self::MostFixed => most_fixed(bb_solver, node),
-            Self::FullStrongBranching => full_strong_branching(bb_solver, node),
+            Self::FullStrongBranch => full_strong_branching(bb_solver, node),
             Self::PartialStrongBranching => partial_strong_branching(bb_solver, node),
             Self::RoundRobin => round_robin(bb_solver, node),
+            Self::PseudoCost => pseudo_cost(bb_solver, node),
+            Self::Reliability => reliability(bb_solver, node),
         };
 
         // hard assert that the variable is not fixed
-        assert!(!node.fixed_variables.contains_key(&branch_variable), "Branching on a fixed variable");
+        if let Some(branch_variable) = branch_variable {
+            assert!(!node.fixed_variables.contains_key(&branch_variable), "Branching on a fixed variable");
+        }
 
         branch_variable
     }
 }
 
+/// A rule for selecting the variable to branch on, so that custom selection logic can be plugged into the solver in
+/// place of the built-in branch strategies
+///
+/// Example:
+/// ``` rust
+/// use hercules::branch_node::QuboBBNode;
+/// use hercules::branch_stratagy::BranchSelector;
+/// use hercules::branchbound::BBSolver;
+/// use hercules::qubo::Qubo;
+/// use hercules::solver_options::SolverOptions;
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// // branches on the last variable that is not fixed
+/// struct LastNotFixed;
+///
+/// impl BranchSelector for LastNotFixed {
+///     fn select(&mut self, solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+///         (0..solver.qubo.num_x())
+///             .rev()
+///             .find(|i| !node.fixed_variables.contains_key(i))
+///     }
+/// }
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5).convex_symmetric_form();
+///
+/// let mut options = SolverOptions::new();
+/// options.branch_selector = Some(Box::new(LastNotFixed));
+/// let mut solver = BBSolver::new(p, options).unwrap();
+/// let result = solver.solve();
+/// ```
+pub trait BranchSelector {
+    /// Selects a variable of the node that is not fixed to branch on, or None if every variable is fixed
+    fn select(&mut self, solver: &BBSolver, node: &QuboBBNode) -> Option<usize>;
+}
+
+impl BranchSelector for BranchStrategy {
+    fn select(&mut self, solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+        self.make_branch(solver, node)
+    }
+}
+
+/// The order in which the two children of a branched node are explored
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BranchDirection {
+    /// The child with the branch variable fixed to one is explored first
+    Up,
+    /// The child with the branch variable fixed to zero is explored first
+    Down,
+    /// The child with the branch variable fixed to its rounded relaxed value is explored first
+    TowardRelaxation,
+}
+
+impl BranchDirection {
+    /// Checks if the one child is explored first, given the relaxed value of the branch variable in the parent
+    pub fn up_first(self, relaxed_value: f64) -> bool {
+        match self {
+            Self::Up => true,
+            Self::Down => false,
+            Self::TowardRelaxation => relaxed_value >= 0.5,
+        }
+    }
+}
+
+/// Running averages of the increase in the lower bound per unit change of each variable, observed from past branchings
+pub struct PseudoCosts {
+    pub down_sum: Array1<f64>,
+    pub down_count: Array1<usize>,
+    pub up_sum: Array1<f64>,
+    pub up_count: Array1<usize>,
+}
+
+impl PseudoCosts {
+    /// Creates empty pseudocost tables for a problem with `num_x` variables
+    pub fn new(num_x: usize) -> Self {
+        Self {
+            down_sum: Array1::zeros(num_x),
+            down_count: Array1::zeros(num_x),
+            up_sum: Array1::zeros(num_x),
+            up_count: Array1::zeros(num_x),
+        }
+    }
+
+    /// Records the increase in the lower bound per unit change, from fixing the variable to the given value
+    pub fn update(&mut self, variable: usize, value: usize, unit_gain: f64) {
+        if value == 0 {
+            self.down_sum[variable] += unit_gain;
+            self.down_count[variable] += 1;
+        } else {
+            self.up_sum[variable] += unit_gain;
+            self.up_count[variable] += 1;
+        }
+    }
+
+    /// The average increase in the lower bound per unit change from fixing the variable to zero, if it was observed
+    pub fn down(&self, variable: usize) -> Option<f64> {
+        (self.down_count[variable] > 0)
+            .then(|| self.down_sum[variable] / self.down_count[variable] as f64)
+    }
+
+    /// The average increase in the lower bound per unit change from fixing the variable to one, if it was observed
+    pub fn up(&self, variable: usize) -> Option<f64> {
+        (self.up_count[variable] > 0)
+            .then(|| self.up_sum[variable] / self.up_count[variable] as f64)
+    }
+
+    /// The average pseudocosts over all the observed variables, used for variables that were not branched on yet
+    pub fn averages(&self) -> Option<(f64, f64)> {
+        let down_count = self.down_count.sum();
+        let up_count = self.up_count.sum();
+
+        if down_count == 0 || up_count == 0 {
+            return None;
+        }
+
+        Some((
+            self.down_sum.sum() / down_count as f64,
+            self.up_sum.sum() / up_count as f64,
+        ))
+    }
+}
+
+/// Branches on the variable with the largest product of the estimated lower bound increases of the two branches
+///
+/// The estimates use the pseudocosts learned from past branchings, and this falls back to most violated until there
+/// are observations.
+pub fn pseudo_cost(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+    let Some((down_average, up_average)) = solver.pseudo_costs.averages() else {
+        return most_violated(solver, node);
+    };
+
+    // if the product is zero for both branches, then guard so that the other branch still breaks the tie
+    let epsilon = 1E-6;
+    let mut best_score = f64::NEG_INFINITY;
+    let mut best_variable = None;
+
+    for i in 0..solver.qubo.num_x() {
+        if node.fixed_variables.contains_key(&i) {
+            continue;
+        }
+
+        // variables that have not been branched on yet use the average pseudocosts
+        let down = solver.pseudo_costs.down(i).unwrap_or(down_average);
+        let up = solver.pseudo_costs.up(i).unwrap_or(up_average);
+
+        let x_i = node.solution[i];
+        let score = (down * x_i).max(epsilon) * (up * (1.0 - x_i)).max(epsilon);
+
+        if score > best_score {
+            best_score = score;
+            best_variable = Some(i);
+        }
+    }
+
+    best_variable
+}
+
+/// Branches with the same score as pseudocost branching, but variables are strong branched on until both of their
+/// pseudocosts have `reliability_threshold` observations
+pub fn reliability(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+    reliability_with_observations(solver, node).0
+}
+
+/// Selects the branch variable as in `reliability`, and also returns the child bounds of the strong branching as
+/// pseudocost observations, so that the strong branched variables become reliable
+///
+/// The gains are measured from the lower bound of the node, so it must be the bound of its own relaxation, as are the
+/// gains of the pseudocosts.
+pub fn reliability_with_observations(
+    solver: &BBSolver,
+    node: &QuboBBNode,
+) -> (Option<usize>, Vec<Event>) {
+    let threshold = solver.options.reliability_threshold;
+    let (down_average, up_average) = solver.pseudo_costs.averages().unwrap_or((0.0, 0.0));
+
+    // if the product is zero for both branches, then guard so that the other branch still breaks the tie
+    let epsilon = 1E-6;
+    let mut best_score = f64::NEG_INFINITY;
+    let mut best_variable = None;
+    let mut observations = Vec::new();
+
+    for i in 0..solver.qubo.num_x() {
+        if node.fixed_variables.contains_key(&i) {
+            continue;
+        }
+
+        let is_reliable =
+            solver.pseudo_costs.down_count[i].min(solver.pseudo_costs.up_count[i]) >= threshold;
+
+        let x_i = node.solution[i];
+        let (down_gain, up_gain) = if is_reliable {
+            // estimate the increases in the lower bound from the pseudocosts
+            let down = solver.pseudo_costs.down(i).unwrap_or(down_average);
+            let up = solver.pseudo_costs.up(i).unwrap_or(up_average);
+            (down * x_i, up * (1.0 - x_i))
+        } else {
+            // solve the child relaxations to get the increases in the lower bound
+            let (bound_0, bound_1) = strong_branch_bounds(solver, node, i);
+            let down_gain = (bound_0 - node.lower_bound).max(0.0);
+            let up_gain = (bound_1 - node.lower_bound).max(0.0);
+
+            // as with the branches that are taken, the per unit increase is only meaningful if the variable moved
+            for (value, gain, fractional_change) in [(0, down_gain, x_i), (1, up_gain, 1.0 - x_i)] {
+                if fractional_change > 1E-6 {
+                    observations.push(Event::UpdatePseudoCost(i, value, gain / fractional_change));
+                }
+            }
+
+            (down_gain, up_gain)
+        };
+
+        let score = down_gain.max(epsilon) * up_gain.max(epsilon);
+
+        if score > best_score {
+            best_score = score;
+            best_variable = Some(i);
+        }
+    }
+
+    (best_variable, observations)
+}
+
+/// Solves the relaxations of the two children from fixing the variable to zero and one, and returns their lower bounds
+fn strong_branch_bounds(solver: &BBSolver, node: &QuboBBNode, variable: usize) -> (f64, f64) {
+    let mut node_0 = node.clone();
+    let mut node_1 = node.clone();
+
+    node_0.fixed_variables.insert(variable, 0);
+    node_1.fixed_variables.insert(variable, 1);
+
+    let bound_0 = solver.subproblem_solver.solve_lower_bound(solver, &node_0);
+    let bound_1 = solver.subproblem_solver.solve_lower_bound(solver, &node_1);
+
+    (bound_0.0, bound_1.0)
+}
+
 /// Branches on the variable that has the most edges in the graph equivalent to the QUBO
-fn most_edges(solver: &BBSolver, node: &QuboBBNode) -> usize {
+fn most_edges(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
     // as a QUBO can be viewed as a graph, we can find the variable with the most (remaining) edges
     let mut edge_count = Array1::<usize>::zeros(solver.qubo.num_x());
 
@@ -64,9 +313,10 @@ fn most_edges(solver: &BBSolver, node: &QuboBBNode) -> usize {
         edge_count[j] += 1;
     }
 
-    // find the variable with the most edges (fixed variables in the node are not counted)
+    // find the variable with the most edges (fixed variables in the node are not counted), if none of them have edges
+    // left then the first variable that is not fixed is used
     let mut max_edges = 0;
-    let mut index_max_edges = 0;
+    let mut index_max_edges = first_not_fixed(solver, node)?;
 
     for i in 0..solver.qubo.num_x() {
         if !node.fixed_variables.contains_key(&i) && edge_count[i] > max_edges {
@@ -75,12 +325,12 @@ fn most_edges(solver: &BBSolver, node: &QuboBBNode) -> usize {
         }
     }
 
-    index_max_edges
+    Some(index_max_edges)
 }
 
 /// Branches on the largest edges in the qubo, ones that are most likely to be the largest
 /// determaning factor in the problem
-fn largest_edges(solver: &BBSolver, node: &QuboBBNode) -> usize {
+fn largest_edges(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
     // as a QUBO can be viewed as a graph, we can find the variable with the most (remaining) edges
     let mut edge_count = Array1::<f64>::zeros(solver.qubo.num_x());
 
@@ -100,9 +350,10 @@ fn largest_edges(solver: &BBSolver, node: &QuboBBNode) -> usize {
         edge_count[j] += value.abs();
     }
 
-    // find the variable with the most edges (fixed variables in the node are not counted)
+    // find the variable with the most edges (fixed variables in the node are not counted), if none of them have edges
+    // left then the first variable that is not fixed is used
     let mut min_edge_value = 0.0;
-    let mut index_max_edges = 0;
+    let mut index_max_edges = first_not_fixed(solver, node)?;
 
     for i in 0..solver.qubo.num_x() {
         if !node.fixed_variables.contains_key(&i) && edge_count[i] > min_edge_value {
@@ -111,13 +362,13 @@ fn largest_edges(solver: &BBSolver, node: &QuboBBNode) -> usize {
         }
     }
 
-    index_max_edges
+    Some(index_max_edges)
 }
 
 /// Computes what branch will generate the most fixed variables via the preprocesser
-pub fn most_fixed(solver: &BBSolver, node: &QuboBBNode) -> usize {
+pub fn most_fixed(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
     let mut most_fixed = 0;
-    let mut branch_var = 0;
+    let mut branch_var = None;
 
     for i in 0..solver.qubo.num_x() {
         if !node.fixed_variables.contains_key(&i) {
@@ -134,7 +385,7 @@ pub fn most_fixed(solver: &BBSolver, node: &QuboBBNode) -> usize {
 
             if min_fixed > most_fixed {
                 most_fixed = min_fixed;
-                branch_var = i;
+                branch_var = Some(i);
             }
         }
     }
@@ -142,28 +393,26 @@ pub fn most_fixed(solver: &BBSolver, node: &QuboBBNode) -> usize {
     branch_var
 }
 
-/// #Panics if the node does not have an unfixed variable
-pub fn first_not_fixed(solver: &BBSolver, node: &QuboBBNode) -> usize {
+/// Branches on the first variable that is not fixed, or None if every variable is fixed
+pub fn first_not_fixed(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
     // scan through the variables and find the first one that is not fixed
-    for i in 0..solver.qubo.num_x() {
-        if !node.fixed_variables.contains_key(&i) {
-            return i;
-        }
-    }
-    panic!("No variable to branch on");
+    (0..solver.qubo.num_x()).find(|i| !node.fixed_variables.contains_key(i))
 }
 
-pub fn most_violated(solver: &BBSolver, node: &QuboBBNode) -> usize {
-    let mut most_violated = 1.0;
-    let mut index_most_violated = 0;
+/// Branches on the most fractional variable of the relaxed solution, the one that is closest to 0.5, with ties going
+/// to the lowest index
+pub fn most_violated(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+    // the distance to 0.5, so the most violated variable has the smallest distance
+    let mut closest_distance = f64::INFINITY;
+    let mut index_most_violated = None;
 
     for i in 0..solver.qubo.num_x() {
         if !node.fixed_variables.contains_key(&i) {
-            let violation = (node.solution[i] - 0.5).abs();
+            let distance = (node.solution[i] - 0.5).abs();
 
-            if violation <= most_violated {
-                most_violated = violation;
-                index_most_violated = i;
+            if distance < closest_distance {
+                closest_distance = distance;
+                index_most_violated = Some(i);
             }
         }
     }
@@ -171,13 +420,22 @@ pub fn most_violated(solver: &BBSolver, node: &QuboBBNode) -> usize {
     index_most_violated
 }
 
-pub fn full_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> usize {
-    let unfixed_variables = (0..solver.qubo.num_x())
-        .filter(|i| !node.fixed_variables.contains_key(i))
-        .collect::<Vec<usize>>();
+/// Tentatively fixes each candidate variable to both values, and picks the variable with the largest minimum child bound
+///
+/// If `strong_branching_candidates` is set in the solver options, then only the top-k variables of the cheap
+/// approximation are candidates.
+pub fn full_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+    let unfixed_variables = solver.options.strong_branching_candidates.map_or_else(
+        || {
+            (0..solver.qubo.num_x())
+                .filter(|i| !node.fixed_variables.contains_key(i))
+                .collect::<Vec<usize>>()
+        },
+        |k| strong_branch_candidates(solver, node, k),
+    );
 
     let mut best_score = f64::NEG_INFINITY;
-    let mut best_variable = *unfixed_variables.first().unwrap();
+    let mut best_variable = *unfixed_variables.first()?;
 
     for i in &unfixed_variables {
         let mut list_0 = node.fixed_variables.clone();
@@ -191,12 +449,20 @@ pub fn full_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> usize {
             lower_bound: 0.0,
             fixed_variables: list_0,
             solution: node.solution.clone(),
+            branch_variable: None,
+            seed: 0,
+            depth: node.depth + 1,
+            id: node.id,
         };
 
         let node_1 = QuboBBNode {
             lower_bound: 0.0,
             fixed_variables: list_1,
             solution: node.solution.clone(),
+            branch_variable: None,
+            seed: 0,
+            depth: node.depth + 1,
+            id: node.id,
         };
 
         let bound_0 = solver.subproblem_solver.solve_lower_bound(solver, &node_0);
@@ -212,10 +478,12 @@ pub fn full_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> usize {
 
 
 
-    best_variable
+    Some(best_variable)
 }
 
-pub fn partial_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> usize {
+/// Ranks the unfixed variables by the product of the approximate objective changes of fixing them to zero and one,
+/// and returns the top k, which are the most promising candidates for strong branching
+pub fn strong_branch_candidates(solver: &BBSolver, node: &QuboBBNode, k: usize) -> Vec<usize> {
     // first compute the approximate objective change for each variable
     let (zero_flip, one_flip) = compute_strong_branch(solver, node);
     let mut score = Array1::zeros(solver.qubo.num_x());
@@ -229,22 +497,27 @@ pub fn partial_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> usize {
         score[i] = zero_flip[i].abs()*(one_flip[i].abs());
     }
 
-    let mut indx = unfixed_vars.clone();
+    let mut indx = unfixed_vars;
     indx.sort_by(|&i, &j| score[i].total_cmp(&score[j]).reverse());
 
-    // test strong branching on the most likely candidate set of 5 variables
+    // always keep at least one candidate to branch on
+    indx.truncate(k.max(1));
+    indx
+}
 
-    let end = usize::min(5, unfixed_vars.len());
+/// Strong branches on the 5 most likely candidates of the cheap approximation, and picks the variable with the largest
+/// product of the child bound increases
+pub fn partial_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+    // test strong branching on the most likely candidate set of 5 variables
+    let indx = strong_branch_candidates(solver, node, 5);
 
     let mut best_score = f64::NEG_INFINITY;
-    let mut best_variable = *indx.first().unwrap();
+    let mut best_variable = *indx.first()?;
 
-    for i in 0..end {
+    for &j in &indx {
         let mut list_0 = node.fixed_variables.clone();
         let mut list_1 = node.fixed_variables.clone();
 
-        let j = *indx.get(i).unwrap();
-
         list_0.insert(j, 0);
         list_1.insert(j, 1);
 
@@ -253,12 +526,20 @@ pub fn partial_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> usize {
             lower_bound: 0.0,
             fixed_variables: list_0,
             solution: node.solution.clone(),
+            branch_variable: None,
+            seed: 0,
+            depth: node.depth + 1,
+            id: node.id,
         };
 
         let node_1 = QuboBBNode {
             lower_bound: 0.0,
             fixed_variables: list_1,
             solution: node.solution.clone(),
+            branch_variable: None,
+            seed: 0,
+            depth: node.depth + 1,
+            id: node.id,
         };
 
         let bound_0 = solver.subproblem_solver.solve_lower_bound(solver, &node_0);
@@ -272,15 +553,23 @@ pub fn partial_strong_branching(solver: &BBSolver, node: &QuboBBNode) -> usize {
         }
     }
 
-    best_variable
+    Some(best_variable)
 }
 
-pub fn random(solver: &BBSolver, node: &QuboBBNode) -> usize {
-    // generate a prng
+/// Branches on the first variable that is not fixed, from a random starting point given by the seed of the node
+///
+/// # Panics
+///
+/// Will panic on a 32-bit system if the QUBO has more than $2^{32}$ variables.
+pub fn random(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+    // continue from the seed the node drew from the solver's generator
     let mut prng = PRNG {
-        generator: JsfLarge::from(solver.options.seed as u64 + solver.nodes_visited as u64),
+        generator: JsfLarge::from(node.seed),
     };
 
+    // if every variable is fixed, then there is nothing to branch on
+    first_not_fixed(solver, node)?;
+
     // generate a random index in the list of variables
     // This unwrap is 'safe' in that, the 32-bit system would crash trying to solve a QUBO with 2^32 variables
     let index = usize::try_from(prng.gen_u64() % solver.qubo.num_x() as u64).unwrap();
@@ -288,27 +577,21 @@ pub fn random(solver: &BBSolver, node: &QuboBBNode) -> usize {
     // scan thru the variables and find the first one that is not fixed starting at the random point
     for i in index..solver.qubo.num_x() {
         if !node.fixed_variables.contains_key(&i) {
-            return i;
+            return Some(i);
         }
     }
 
     // scan through the variables and find the first one that is not fixed starting at the beginning
-    for i in 0..index {
-        if !node.fixed_variables.contains_key(&i) {
-            return i;
-        }
-    }
-
-    panic!("No Variable to branch on")
+    (0..index).find(|i| !node.fixed_variables.contains_key(i))
 }
 
 /// Branches on the variable that has an estimated worst result, pushing up the lower bound as fast as possible
-pub fn worst_approximation(solver: &BBSolver, node: &QuboBBNode) -> usize {
+pub fn worst_approximation(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
     let (zero_flip, one_flip) = compute_strong_branch(solver, node);
 
     // tracking variables for the worst approximation
     let mut worst_approximation = f64::NEG_INFINITY;
-    let mut index_worst_approximation = 0;
+    let mut index_worst_approximation = None;
 
     // scan through the variables and find the worst gain
     for i in 0..solver.qubo.num_x() {
@@ -323,7 +606,7 @@ pub fn worst_approximation(solver: &BBSolver, node: &QuboBBNode) -> usize {
         // if it is the highest growing variable, then update the tracking variables
         if min_obj_gain > worst_approximation {
             worst_approximation = min_obj_gain;
-            index_worst_approximation = i;
+            index_worst_approximation = Some(i);
         }
     }
 
@@ -331,12 +614,12 @@ pub fn worst_approximation(solver: &BBSolver, node: &QuboBBNode) -> usize {
 }
 
 /// Branches on the variable that has an estimated best result, keeping the lower bound as low as possible
-pub fn best_approximation(solver: &BBSolver, node: &QuboBBNode) -> usize {
+pub fn best_approximation(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
     let (zero_flip, one_flip) = compute_strong_branch(solver, node);
 
     // tracking variables for the worst approximation
     let mut worst_approximation = f64::INFINITY;
-    let mut index_best_approximation = 0;
+    let mut index_best_approximation = None;
 
     // scan through the variables and find the worst gain
     for i in 0..solver.qubo.num_x() {
@@ -351,13 +634,19 @@ pub fn best_approximation(solver: &BBSolver, node: &QuboBBNode) -> usize {
         // if it is the highest growing variable, then update the tracking variables
         if max_obj_gain <= worst_approximation {
             worst_approximation = max_obj_gain;
-            index_best_approximation = i;
+            index_best_approximation = Some(i);
         }
     }
 
     index_best_approximation
 }
 
+/// Computes the change in the objective at the relaxed solution of the node from setting each variable to zero and to
+/// one, with the fixed variables at their values
+///
+/// The change from setting $x_i$ to $x_i + d$ is $0.5 d (d Q_{ii} + (Q x)_i + (Q^T x)_i + 2 c_i)$, and as the QUBO of
+/// the solver is symmetric, $Q^T x = Q x$, so this is $d (0.5 d Q_{ii} + (Q x)_i + c_i)$ and only one product with the
+/// Hessian is needed.
 pub fn compute_strong_branch(solver: &BBSolver, node: &QuboBBNode) -> (Array1<f64>, Array1<f64>) {
     let mut base_solution = Array1::<f64>::zeros(solver.qubo.num_x());
     let mut delta_zero = Array1::<f64>::zeros(solver.qubo.num_x());
@@ -378,7 +667,6 @@ pub fn compute_strong_branch(solver: &BBSolver, node: &QuboBBNode) -> (Array1<f6
     // build the intermediate vectors
     let q_jj = solver.qubo.q.diag().to_dense();
     let q_x = &solver.qubo.q * &base_solution;
-    let x_q = &solver.qubo.q.transpose_view() * &base_solution;
 
     // build the result vectors
     let mut zero_result = Array1::zeros(solver.qubo.num_x());
@@ -386,33 +674,27 @@ pub fn compute_strong_branch(solver: &BBSolver, node: &QuboBBNode) -> (Array1<f6
 
     // compute the deltas in the objective compared to the current solution
     for i in 0..solver.qubo.num_x() {
-        zero_result[i] = 0.5
-            * delta_zero[i]
-            * (delta_zero[i] * q_jj[i] + x_q[i] + q_x[i] + 2.0 * solver.qubo.c[i]);
-        one_result[i] = 0.5
-            * delta_one[i]
-            * (delta_one[i] * q_jj[i] + x_q[i] + q_x[i] + 2.0 * solver.qubo.c[i]);
+        zero_result[i] =
+            delta_zero[i] * (0.5 * delta_zero[i] * q_jj[i] + q_x[i] + solver.qubo.c[i]);
+        one_result[i] = delta_one[i] * (0.5 * delta_one[i] * q_jj[i] + q_x[i] + solver.qubo.c[i]);
     }
 
     (zero_result, one_result)
 }
 
-pub fn round_robin(solver: &BBSolver, node: &QuboBBNode) -> usize {
+/// Branches with one of the cheap branching strategies, picked at random from the seed of the node
+pub fn round_robin(solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
     // fun branching strat based on pseudo randomly picking a decent (and cheap branching strat)
 
-    // make a random seed that is unique to each node
-    let node_seed = node.fixed_variables.keys().sum::<usize>() as u64;
-    let solver_seed = solver.options.seed as u64 + solver.nodes_solved as u64;
-
+    // continue from the seed the node drew from the solver's generator
     let mut prng = PRNG {
-        generator: JsfLarge::from(node_seed + solver_seed),
+        generator: JsfLarge::from(node.seed),
     };
 
     match prng.gen_u64() % 4 {
         0 => largest_edges(solver, node),
         1 => most_edges(solver, node),
         2 => worst_approximation(solver, node),
-        3 => best_approximation(solver, node),
-        _ => panic!("Random branch selection failed"),
+        _ => best_approximation(solver, node),
     }
 }
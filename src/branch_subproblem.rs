@@ -11,6 +11,7 @@ pub trait SubProblemSolver {
     fn solve_lower_bound(&self, bbsolver: &BBSolver, node: &QuboBBNode) -> SubProblemResult;
 }
 
+#[derive(Clone)]
 pub enum SubProblemSelection {
     ClarabelQP,
     ClarabelLP,
@@ -22,6 +23,6 @@ pub fn get_sub_problem_solver(
 ) -> Box<dyn SubProblemSolver + Sync> {
     match sub_problem_selection {
         SubProblemSelection::ClarabelQP => Box::new(ClarabelQPSolver::new(qubo)),
-        SubProblemSelection::ClarabelLP => Box::new(ClarabelLPSolver::new(qubo)),
+        SubProblemSelection::ClarabelLP => Box::new(ClarabelLPSolver),
     }
 }
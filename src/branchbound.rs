@@ -2,19 +2,62 @@ use crate::qubo::Qubo;
 use ndarray::Array1;
 use rayon::prelude::*;
 
-use crate::branch_node::QuboBBNode;
-use crate::branch_stratagy::BranchStrategy;
+use crate::bounds::reduced_cost_fixing;
+use crate::branch_node::{QuboBBNode, TreeRecord};
+use crate::branch_stratagy::{reliability_with_observations, BranchStrategy, PseudoCosts};
 use crate::branch_subproblem::{
-    get_sub_problem_solver, SubProblemSolver
+    get_sub_problem_solver, SubProblemSelection, SubProblemSolver
 };
 use crate::branchbound_utils::{check_integer_feasibility, get_current_time};
 use crate::branchboundlogger::SolverOutputLogger;
+use crate::decompose::connected_components;
 use crate::early_termination::beck_proof;
+use crate::heuristics::{dive, relaxation_rounding};
+use crate::incumbent::Incumbent;
 use crate::lower_bound::li_lower_bound;
+use crate::node_selection::NodeQueue;
 use crate::preprocess;
 use crate::preprocess::preprocess_qubo;
-use crate::solver_options::SolverOptions;
-use std::collections::BinaryHeap;
+use crate::solver_options::{BoxedBranchSelector, SolverOptions};
+use crate::subproblemsolvers::clarabel_qp::solve_relaxed_qp_with_duals;
+use log::{debug, info};
+use smolprng::{JsfLarge, PRNG};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// The number of visited nodes between the summaries of the search that are logged at the info level
+const LOG_SUMMARY_INTERVAL: usize = 1000;
+
+/// Errors that can occur when setting up the B&B solver
+#[derive(Debug)]
+pub enum SolverError {
+    /// The Hessian of the QUBO is not symmetric
+    NotSymmetric,
+    /// The Hessian of the QUBO is not positive semidefinite, so the relaxations are not convex
+    NotConvex,
+    /// The initial solution given in the solver options is not a valid starting incumbent
+    InvalidInitialSolution(String),
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSymmetric => write!(
+                f,
+                "The QUBO is not symmetric, use Qubo::convex_symmetric_form to get an equivalent QUBO that is"
+            ),
+            Self::NotConvex => write!(
+                f,
+                "The QUBO is not convex, use Qubo::convex_symmetric_form to get an equivalent QUBO that is"
+            ),
+            Self::InvalidInitialSolution(msg) => write!(f, "Invalid initial solution: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
 
 /// Struct for the B&B Solver
 pub struct BBSolver {
@@ -22,27 +65,48 @@ pub struct BBSolver {
     pub qubo_pp_form: Qubo,
     pub best_solution: Array1<usize>,
     pub best_solution_value: f64,
-    pub nodes: BinaryHeap<QuboBBNode>,
+    /// The incumbent shared by the threads that process a batch of nodes, so that every thread prunes against the best
+    /// solution found by any of them
+    pub incumbent: Incumbent,
+    pub nodes: NodeQueue,
     pub nodes_processed: usize,
     pub nodes_solved: usize,
     pub nodes_visited: usize,
+    pub nodes_pruned: usize,
     pub time_start: f64,
     pub branch_strategy: BranchStrategy,
+    /// The custom branching rule taken from the options, behind a lock as the nodes are processed in parallel
+    pub branch_selector: Option<Mutex<BoxedBranchSelector>>,
+    pub pseudo_costs: PseudoCosts,
+    pub prng: PRNG<JsfLarge>,
     pub subproblem_solver: Box<dyn SubProblemSolver + Sync>,
     pub options: SolverOptions,
     pub early_stop: bool,
+    /// The lowest lower bound of the nodes that were not explored because of the depth limit, or infinity if there are
+    /// none
+    pub depth_limit_bound: f64,
+    /// The recorded branch and bound tree, indexed by the ids of the nodes, if tree recording is enabled
+    pub tree: Vec<TreeRecord>,
+    /// The number of times the search was restarted by the restart policy
+    pub restarts: usize,
+    /// The number of nodes that were visited when the search was last restarted
+    pub restart_nodes_visited: usize,
     pub solver_logger: SolverOutputLogger,
 }
 
+// the events are applied right after the batch they come from, so boxing the nodes would only add allocations
+#[allow(clippy::large_enum_variant)]
 pub enum Event {
     UpdateBestSolution(Array1<usize>, f64),
     AddBranches(QuboBBNode, QuboBBNode),
+    UpdatePseudoCost(usize, usize, f64),
     Nill,
 }
 
 pub enum NodeLoggingAction {
     Visited,
     Processed,
+    Pruned,
     Solved,
 }
 
@@ -57,45 +121,250 @@ pub struct ProcessNodeState {
     pub logging: NodeLoggingAction,
 }
 
-pub enum SolverResult {
-    OptimalSolution(Array1<f64>, f64),
-    SubOptimalSolution(Array1<f64>, f64),
+/// The reason that the B&B solver stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminationReason {
+    /// The search was completed, so the best solution is proven to be optimal
+    Optimal,
+    /// The gap between the best solution and the lower bound is within the absolute or relative gap tolerance
+    GapTolerance,
+    /// The time limit was reached before the search was completed, the best solution found so far is returned
+    TimeLimit,
+    /// The node limit was reached before the search was completed, the best solution found so far is returned
+    NodeLimit,
+    /// The search was completed up to the depth limit, the nodes below it could still contain a better solution
+    DepthLimit,
+    /// The solver stopped without finding a solution that agrees with the fixed variables
+    Infeasible,
+}
+
+/// The result of the B&B solver, with the best solution found and statistics of the search
+#[derive(Clone, Debug)]
+pub struct SolveResult {
+    /// The best solution found
+    pub solution: Array1<usize>,
+    /// The objective value of the best solution
+    pub objective: f64,
+    /// The number of nodes that were taken from the node queue
+    pub nodes_visited: usize,
+    /// The number of nodes that were pruned, either by bound or by being fully fixed
+    pub nodes_pruned: usize,
+    /// The wall-clock time of the solve in seconds
+    pub time: f64,
+    /// The proven lower bound on the optimal objective, from the open nodes when the solver stopped
+    pub lower_bound: f64,
+    /// The relative optimality gap, (upper_bound - lower_bound) / |upper_bound|
+    pub gap: f64,
+    /// Why the solver stopped
+    pub status: TerminationReason,
+    /// The number of times the search was restarted by the restart policy
+    pub restarts: usize,
+}
+
+/// The iterator of `BBSolver::solve_streaming`, which drives the search one batch of nodes at a time
+struct SolveStream<'a> {
+    solver: &'a mut BBSolver,
+    user_fixed_variables: HashMap<usize, usize>,
+    thread_pool: Option<rayon::ThreadPool>,
+    /// The objective of the last yielded incumbent
+    last_value: f64,
+    is_finished: bool,
+}
+
+impl Iterator for SolveStream<'_> {
+    type Item = (Array1<usize>, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.is_finished {
+            // yield the incumbent as soon as it improves, as long as it agrees with the fixed variables of the user
+            let solver = &self.solver;
+            let is_feasible = BBSolver::validate_initial_solution(
+                &solver.best_solution,
+                solver.qubo.num_x(),
+                &self.user_fixed_variables,
+            )
+            .is_ok();
+            if is_feasible && solver.best_solution_value < self.last_value {
+                self.last_value = solver.best_solution_value;
+                return Some((solver.best_solution.clone(), solver.best_solution_value));
+            }
+
+            if let Some(reason) = self.solver.termination_reason() {
+                self.solver
+                    .finish_search(reason, &self.user_fixed_variables);
+                self.is_finished = true;
+            } else {
+                self.solver.search_step(self.thread_pool.as_ref());
+            }
+        }
+
+        None
+    }
 }
 
 impl BBSolver {
-    /// Creates a new B&B solver
-    pub fn new(qubo: Qubo, options: SolverOptions) -> Self {
+    /// Creates a new B&B solver, the QUBO must be symmetric and convex so that the relaxations of the subproblems give
+    /// valid lower bounds
+    ///
+    /// The McCormick LP relaxation of the `ClarabelLP` subproblem solver is a valid lower bound for any QUBO, so with it
+    /// the QUBO only needs to be symmetric.
+    ///
+    /// If the options contain an initial solution, then it is used as the incumbent from the start of the search. The
+    /// convexity check computes the eigenvalues of the dense Hessian, so for QUBOs that are convex by construction
+    /// `new_assume_convex` skips it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the QUBO is not symmetric, if it is not convex and the subproblem solver needs it to be, if the
+    /// initial solution does not have one entry for
+    /// each variable, or if it does not agree with the fixed variables of the options.
+    pub fn new(qubo: Qubo, options: SolverOptions) -> Result<Self, SolverError> {
+        // check the preconditions of the solver, otherwise the lower bounds are silently wrong
+        if !qubo.is_symmetric() {
+            return Err(SolverError::NotSymmetric);
+        }
+
+        // only the QP relaxation needs a convex QUBO to give a lower bound
+        let needs_convexity =
+            !matches!(options.sub_problem_solver, SubProblemSelection::ClarabelLP);
+        if needs_convexity && !qubo.is_convex() {
+            return Err(SolverError::NotConvex);
+        }
+
+        Self::new_assume_convex(qubo, options)
+    }
+
+    /// Creates a new B&B solver as in `new`, but without checking that the QUBO is convex
+    ///
+    /// Checking convexity computes the eigenvalues of the dense Hessian, which is expensive for large QUBOs. This is
+    /// for QUBOs that are convex by construction, such as those from `convex_symmetric_form` or `convexify` and their
+    /// principal submatrices. If the QUBO is not convex, then the lower bounds of the search are not valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the QUBO is not symmetric, if the initial solution does not have one entry for each
+    /// variable, or if it does not agree with the fixed variables of the options.
+    pub fn new_assume_convex(qubo: Qubo, mut options: SolverOptions) -> Result<Self, SolverError> {
+        if !qubo.is_symmetric() {
+            return Err(SolverError::NotSymmetric);
+        }
+
         // create auxiliary variables
         let num_x = qubo.num_x();
 
         let subproblem_solver = get_sub_problem_solver(&qubo, &options.sub_problem_solver);
         let branch_strategy = options.branch_strategy;
+        let branch_selector = options.branch_selector.take().map(Mutex::new);
         let start_time = get_current_time();
         let output_level = options.verbose;
         let pp_form = preprocess::shift_qubo(&qubo);
+        let nodes = NodeQueue::new(options.node_selection);
+
+        let (best_solution, best_solution_value) = Self::initial_incumbent(&qubo, &options)?;
 
-        Self {
+        Ok(Self {
             qubo,
             qubo_pp_form: pp_form,
-            best_solution: Array1::zeros(num_x),
-            best_solution_value: 0.0,
-            nodes: BinaryHeap::new(),
+            incumbent: Incumbent::new(best_solution.clone(), best_solution_value),
+            best_solution,
+            best_solution_value,
+            nodes,
             nodes_processed: 0,
             nodes_visited: 0,
             nodes_solved: 0,
+            nodes_pruned: 0,
             time_start: start_time,
             branch_strategy,
+            branch_selector,
+            pseudo_costs: PseudoCosts::new(num_x),
+            prng: PRNG {
+                generator: JsfLarge::from(options.seed as u64),
+            },
             subproblem_solver,
             options,
             early_stop: false,
+            depth_limit_bound: f64::INFINITY,
+            tree: Vec::new(),
+            restarts: 0,
+            restart_nodes_visited: 0,
             solver_logger: SolverOutputLogger { output_level },
+        })
+    }
+
+    /// The incumbent that the search starts from, which always respects the fixed variables of the options
+    ///
+    /// An initial solution is validated and used as it is, and otherwise the search starts from all zeros with the fixed
+    /// variables applied, so that the starting incumbent never prunes the nodes that respect the fixed variables.
+    fn initial_incumbent(
+        qubo: &Qubo,
+        options: &SolverOptions,
+    ) -> Result<(Array1<usize>, f64), SolverError> {
+        let num_x = qubo.num_x();
+
+        let x = match &options.initial_solution {
+            Some(x) => {
+                Self::validate_initial_solution(x, num_x, &options.fixed_variables)?;
+                x.clone()
+            }
+            None => Self::apply_fixed_variables(Array1::zeros(num_x), &options.fixed_variables),
+        };
+
+        let value = qubo.eval_binary(&x);
+        Ok((x, value))
+    }
+
+    /// Sets the fixed variables of a solution to their fixed values
+    fn apply_fixed_variables(
+        mut x: Array1<usize>,
+        fixed_variables: &HashMap<usize, usize>,
+    ) -> Array1<usize> {
+        for (&index, &value) in fixed_variables {
+            if let Some(x_i) = x.get_mut(index) {
+                *x_i = value;
+            }
+        }
+        x
+    }
+
+    /// Checks that an initial solution has the right size, is binary, and respects the fixed variables
+    fn validate_initial_solution(
+        x: &Array1<usize>,
+        num_x: usize,
+        fixed_variables: &HashMap<usize, usize>,
+    ) -> Result<(), SolverError> {
+        if x.len() != num_x {
+            return Err(SolverError::InvalidInitialSolution(format!(
+                "expected {num_x} variables, found {}",
+                x.len()
+            )));
+        }
+
+        if let Some((index, &value)) = x.iter().enumerate().find(|(_, &x_i)| x_i > 1) {
+            return Err(SolverError::InvalidInitialSolution(format!(
+                "x_{index} is {value}, but must be 0 or 1"
+            )));
+        }
+
+        for (&index, &value) in fixed_variables {
+            if x.get(index) != Some(&value) {
+                return Err(SolverError::InvalidInitialSolution(format!(
+                    "x_{index} is fixed to {value}"
+                )));
+            }
         }
+
+        Ok(())
     }
 
     /// This function is used to warm start the solver with an initial solution if one is not provided
+    ///
+    /// The fixed variables of the options are applied to the initial solution, so that it can not prune the nodes that
+    /// respect them.
     pub fn warm_start(&mut self, initial_solution: Array1<usize>) {
-        self.best_solution = initial_solution;
+        self.best_solution =
+            Self::apply_fixed_variables(initial_solution, &self.options.fixed_variables);
         self.best_solution_value = self.qubo.eval_usize(&self.best_solution);
+        self.incumbent = Incumbent::new(self.best_solution.clone(), self.best_solution_value);
 
         // if we have an early stopping condition, then we can check if we have a solution
         let beck_proof = beck_proof(&self.qubo, &self.best_solution);
@@ -108,21 +377,59 @@ impl BBSolver {
     }
 
     /// The main solve function of the B&B algorithm
-    pub fn solve(&mut self) -> (Array1<usize>, f64) {
+    ///
+    /// The tree is explored iteratively, with the open nodes held in the node queue on the heap, so the depth of the
+    /// tree is bounded by memory rather than by the call stack for every node selection strategy.
+    ///
+    /// Along with the output of the verbose level, the search is logged through the `log` crate, with each improved
+    /// incumbent and a summary every `LOG_SUMMARY_INTERVAL` visited nodes at the info level, and the bound and branching
+    /// decision of each node at the debug level. Nothing is formatted unless a logger has enabled the level.
+    pub fn solve(&mut self) -> SolveResult {
+        let (user_fixed_variables, thread_pool) = self.start_search();
+
+        // until we have hit a termination condition, we will keep iterating
+        let termination_reason = loop {
+            if let Some(reason) = self.termination_reason() {
+                break reason;
+            }
+
+            self.search_step(thread_pool.as_ref());
+        };
+
+        self.finish_search(termination_reason, &user_fixed_variables)
+    }
+
+    /// Solves the QUBO like `solve`, but lazily, as an iterator over each improving incumbent and its objective
+    ///
+    /// Each call to `next` processes batches of nodes until the incumbent improves or the search stops, so the caller
+    /// can react to the progress of the search, or stop it early by dropping the iterator. The incumbent at the start
+    /// of the search is the first item, if it agrees with the fixed variables, and when the search completes within
+    /// its limits the last item is the optimal solution. The statistics of the search are left in the solver.
+    pub fn solve_streaming(&mut self) -> impl Iterator<Item = (Array1<usize>, f64)> + '_ {
+        let (user_fixed_variables, thread_pool) = self.start_search();
+
+        SolveStream {
+            solver: self,
+            user_fixed_variables,
+            thread_pool,
+            last_value: f64::INFINITY,
+            is_finished: false,
+        }
+    }
+
+    /// Sets up the search, with the preprocessed root node in the node queue, and returns the fixed variables of the
+    /// user along with the thread pool of the search
+    fn start_search(&mut self) -> (HashMap<usize, usize>, Option<rayon::ThreadPool>) {
+        // keep the fixed variables of the user, to check that the final solution is feasible
+        let user_fixed_variables = self.options.fixed_variables.clone();
+
         // preprocess the problem
         let fixed_variables =
             preprocess_qubo(&self.qubo_pp_form, &self.options.fixed_variables, true);
         self.options.fixed_variables = fixed_variables.clone();
 
-        // create the root node
-        let root_node = QuboBBNode {
-            lower_bound: f64::NEG_INFINITY,
-            solution: Array1::zeros(self.qubo.num_x()),
-            fixed_variables,
-        };
-
-        // add the root node to the list of nodes
-        self.nodes.push(root_node);
+        // create the root node, and add it to the list of nodes
+        self.push_root_node(fixed_variables, f64::NEG_INFINITY);
 
         // Reset start time as it can be different from the time we created the solver instance
         self.time_start = get_current_time();
@@ -136,42 +443,390 @@ impl BBSolver {
             self.solver_logger.output_warm_start_info(self);
         }
 
-        // until we have hit a termination condition, we will keep iterating
-        while !(*self).termination_condition() {
-            // get the most recent 25 nodes to process
-            let nodes = self.get_next_nodes(self.options.threads);
-
-            let process_results = nodes
-                .par_iter()
-                .map(|node| self.process_node(node))
-                .collect::<Vec<_>>();
-
-            // apply all the events from the parallel loop back to the solver
-            for state in process_results {
-                self.apply_events(state.events);
-                self.apply_logging_action(state.logging);
-            }
+        // set up a thread pool with the requested number of threads, if it can not be created we fall back on the
+        // global thread pool
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.options.threads.max(1))
+            .build()
+            .ok();
+
+        (user_fixed_variables, thread_pool)
+    }
+
+    /// Adds a root node with the given fixed variables and lower bound to the node queue, and to the recorded tree
+    fn push_root_node(&mut self, fixed_variables: HashMap<usize, usize>, lower_bound: f64) {
+        let root_node = QuboBBNode {
+            lower_bound,
+            id: self.tree.len(),
+            ..QuboBBNode::root(self.qubo.num_x(), fixed_variables)
+        };
+
+        if self.options.record_tree {
+            self.tree.push(TreeRecord {
+                parent: None,
+                branch: None,
+                lower_bound,
+                branch_variable: None,
+            });
+        }
+
+        self.nodes.push(root_node);
+    }
+
+    /// Discards the open nodes and starts the search again from the root node, if the restart policy calls for it
+    ///
+    /// The incumbent and the pseudo costs are kept, so the new tree prunes against the best solution so far and
+    /// branches with what was learned. The new root node starts from the global lower bound of the discarded tree, and
+    /// in the recorded tree it is a new root.
+    fn restart_if_due(&mut self) {
+        let Some(policy) = self.options.restart_policy else {
+            return;
+        };
+
+        if self.nodes_visited - self.restart_nodes_visited < policy.threshold(self.restarts) {
+            return;
+        }
+
+        let lower_bound = self.global_lower_bound();
+        self.restarts += 1;
+        self.restart_nodes_visited = self.nodes_visited;
+        self.nodes = NodeQueue::new(self.options.node_selection);
+
+        // the nodes cut off by the depth limit are found again by the new tree, if they are not pruned
+        self.depth_limit_bound = f64::INFINITY;
+
+        // the fixed variables of the options are those of the root node after preprocessing
+        self.push_root_node(self.options.fixed_variables.clone(), lower_bound);
+    }
+
+    /// Processes the next batch of nodes, one for each thread, and applies their results to the solver
+    fn search_step(&mut self, thread_pool: Option<&rayon::ThreadPool>) {
+        self.restart_if_due();
+        let nodes_visited = self.nodes_visited;
+
+        // get the next batch of nodes to process, one for each thread
+        let nodes = self.get_next_nodes(self.options.threads);
+
+        let process_results = thread_pool.map_or_else(
+            || self.process_nodes(&nodes),
+            |pool| pool.install(|| self.process_nodes(&nodes)),
+        );
 
-            // display the line, if verbose
-            self.solver_logger.generate_output_line(self);
+        // apply all the events from the parallel loop back to the solver
+        for state in process_results {
+            self.apply_events(state.events);
+            self.apply_logging_action(state.logging);
         }
 
+        // display the line, if verbose
+        self.solver_logger.generate_output_line(self);
+
+        // log a summary each time the visited nodes pass a multiple of the interval
+        if self.nodes_visited / LOG_SUMMARY_INTERVAL > nodes_visited / LOG_SUMMARY_INTERVAL {
+            info!(
+                "{} nodes visited, {} open nodes, best solution {}, lower bound {}, gap {}",
+                self.nodes_visited,
+                self.nodes.len(),
+                self.best_solution_value,
+                self.global_lower_bound(),
+                self.gap()
+            );
+        }
+    }
+
+    /// Ends the search, and collects the best solution and the statistics of the search
+    fn finish_search(
+        &self,
+        termination_reason: TerminationReason,
+        user_fixed_variables: &HashMap<usize, usize>,
+    ) -> SolveResult {
         // display the exit line
         self.solver_logger.generate_exit_line(self);
 
-        (self.best_solution.clone(), self.best_solution_value)
+        // if no solution that respects the fixed variables was found, then the best solution is not usable
+        let is_feasible = Self::validate_initial_solution(
+            &self.best_solution,
+            self.qubo.num_x(),
+            user_fixed_variables,
+        )
+        .is_ok();
+        let status = if is_feasible {
+            termination_reason
+        } else {
+            TerminationReason::Infeasible
+        };
+        info!(
+            "search finished as {status:?} after {} visited nodes, best solution {}",
+            self.nodes_visited, self.best_solution_value
+        );
+
+        SolveResult {
+            solution: self.best_solution.clone(),
+            objective: self.best_solution_value,
+            nodes_visited: self.nodes_visited,
+            nodes_pruned: self.nodes_pruned,
+            time: get_current_time() - self.time_start,
+            lower_bound: self.global_lower_bound(),
+            gap: self.gap(),
+            status,
+            restarts: self.restarts,
+        }
+    }
+
+    /// Solves the QUBO by splitting it into the connected components of its interaction graph, solving each component
+    /// with its own B&B solver, and combining their solutions
+    ///
+    /// For problems with block structure this can be much faster than a direct solve, as the size of the search tree
+    /// depends on the size of the largest component rather than on the number of variables. Components with a single
+    /// variable are solved directly, and if more than one thread is used, then the components are solved in parallel.
+    /// Each component uses the options of this solver restricted to its variables, without output or a callback, and
+    /// the callback of this solver is called once with the combined solution. A custom branch selector works on the
+    /// indices of the full problem, so the components branch with the branch strategy instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the B&B solver of a component can not be created, which does not happen as every component of a
+    /// symmetric and convex QUBO is also symmetric and convex.
+    pub fn solve_decomposed(&mut self) -> SolveResult {
+        self.time_start = get_current_time();
+
+        let components = connected_components(&self.qubo);
+
+        // the components are independent, so they can be solved at the same time
+        let results = if self.options.threads > 1 {
+            let thread_pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(self.options.threads)
+                .build()
+                .ok();
+            let solve_all = || {
+                components
+                    .par_iter()
+                    .map(|(sub_qubo, members)| self.solve_component(sub_qubo, members))
+                    .collect::<Vec<_>>()
+            };
+            thread_pool
+                .as_ref()
+                .map_or_else(solve_all, |pool| pool.install(solve_all))
+        } else {
+            components
+                .iter()
+                .map(|(sub_qubo, members)| self.solve_component(sub_qubo, members))
+                .collect::<Vec<_>>()
+        };
+
+        // stitch the solutions of the components back together, the objective and lower bound are sums over them
+        let mut solution = Array1::zeros(self.qubo.num_x());
+        let mut lower_bound = 0.0;
+        let mut termination_reason = TerminationReason::Optimal;
+
+        // the combined search is only as complete as the least complete component
+        let rank = |reason: TerminationReason| match reason {
+            TerminationReason::Optimal => 0,
+            TerminationReason::GapTolerance => 1,
+            TerminationReason::TimeLimit
+            | TerminationReason::NodeLimit
+            | TerminationReason::DepthLimit => 2,
+            TerminationReason::Infeasible => 3,
+        };
+
+        for ((_, members), result) in components.iter().zip(&results) {
+            for (k, &i) in members.iter().enumerate() {
+                solution[i] = result.solution[k];
+            }
+
+            lower_bound += result.lower_bound;
+            self.nodes_visited += result.nodes_visited;
+            self.nodes_pruned += result.nodes_pruned;
+            self.restarts += result.restarts;
+
+            if rank(result.status) > rank(termination_reason) {
+                termination_reason = result.status;
+            }
+        }
+
+        let objective = self.qubo.eval_usize(&solution);
+        self.update_solution_if_better(&solution, objective);
+
+        // if no solution that respects the fixed variables was found, then the best solution is not usable
+        let is_feasible = Self::validate_initial_solution(
+            &self.best_solution,
+            self.qubo.num_x(),
+            &self.options.fixed_variables,
+        )
+        .is_ok();
+        let status = if is_feasible {
+            termination_reason
+        } else {
+            TerminationReason::Infeasible
+        };
+
+        let upper_bound = self.best_solution_value;
+        let lower_bound = lower_bound.min(upper_bound);
+
+        SolveResult {
+            solution: self.best_solution.clone(),
+            objective: upper_bound,
+            nodes_visited: self.nodes_visited,
+            nodes_pruned: self.nodes_pruned,
+            time: get_current_time() - self.time_start,
+            lower_bound,
+            gap: (upper_bound - lower_bound) / upper_bound.abs().max(1E-10),
+            status,
+            restarts: self.restarts,
+        }
+    }
+
+    /// Solves one component of the QUBO, where `members` are the original indices of its variables
+    fn solve_component(&self, sub_qubo: &Qubo, members: &[usize]) -> SolveResult {
+        let fixed_variables = members
+            .iter()
+            .enumerate()
+            .filter_map(|(k, i)| self.options.fixed_variables.get(i).map(|&value| (k, value)))
+            .collect::<HashMap<usize, usize>>();
+
+        // a free single variable is set to one only if that decreases the objective
+        if members.len() == 1 {
+            let q_00 = sub_qubo.q.get(0, 0).copied().unwrap_or(0.0);
+            let x_0 = fixed_variables
+                .get(&0)
+                .copied()
+                .unwrap_or_else(|| usize::from(sub_qubo.c[0] + 0.5 * q_00 < 0.0));
+            let solution = Array1::from_elem(1, x_0);
+            let objective = sub_qubo.eval_usize(&solution);
+
+            return SolveResult {
+                solution,
+                objective,
+                nodes_visited: 0,
+                nodes_pruned: 0,
+                time: 0.0,
+                lower_bound: objective,
+                gap: 0.0,
+                status: TerminationReason::Optimal,
+                restarts: 0,
+            };
+        }
+
+        let mut options = SolverOptions::new();
+        options.fixed_variables = fixed_variables;
+        options.initial_solution = self
+            .options
+            .initial_solution
+            .as_ref()
+            .map(|x| members.iter().map(|&i| x[i]).collect());
+        options.branch_strategy = self.options.branch_strategy;
+        options.strong_branching_candidates = self.options.strong_branching_candidates;
+        options.reliability_threshold = self.options.reliability_threshold;
+        options.node_selection = self.options.node_selection;
+        options.sub_problem_solver = self.options.sub_problem_solver.clone();
+        options.heuristic = self.options.heuristic.clone();
+        options.max_time = self.options.max_time - (get_current_time() - self.time_start);
+        options.max_nodes = self.options.max_nodes;
+        options.max_depth = self.options.max_depth;
+        options.gap_tolerance = self.options.gap_tolerance;
+        options.relative_gap_tolerance = self.options.relative_gap_tolerance;
+        options.seed = self.options.seed;
+        options.verbose = 0;
+        options.threads = self.options.threads;
+        options.restart_policy = self.options.restart_policy;
+        options.dive_frequency = self.options.dive_frequency;
+        options.reduced_cost_fixing = self.options.reduced_cost_fixing;
+        options.branch_direction = self.options.branch_direction;
+
+        // a component is a principal submatrix of a convex QUBO, so it is convex as well and that is not checked again
+        let mut solver = Self::new_assume_convex(sub_qubo.clone(), options)
+            .expect("components of a symmetric QUBO are symmetric");
+        solver.solve()
+    }
+
+    /// The lowest lower bound of all the open nodes and of the nodes cut off by the depth limit, this is the best bound
+    /// on the optimal objective
+    pub fn global_lower_bound(&self) -> f64 {
+        let bound = self.best_solution_value.min(self.depth_limit_bound);
+
+        self.nodes
+            .iter()
+            .map(|node| node.lower_bound)
+            .fold(bound, f64::min)
+    }
+
+    /// The relative gap between the best solution and the global lower bound, which is zero if the search is complete
+    pub fn gap(&self) -> f64 {
+        let upper_bound = self.best_solution_value;
+        let lower_bound = self.global_lower_bound();
+
+        // guard against dividing by zero, if the best solution has an objective of zero
+        (upper_bound - lower_bound) / upper_bound.abs().max(1E-10)
+    }
+
+    /// The relative gap between the incumbent and the relaxation of the root node, a measure of how hard the QUBO is
+    ///
+    /// The root node is preprocessed as at the start of the search, but nothing is searched, so this can be called
+    /// before `solve` to decide between exact solving and heuristics, where a large root gap predicts a large tree. The
+    /// incumbent is the initial solution or warm start, and otherwise all zeros with the fixed variables applied.
+    pub fn root_gap(&self) -> f64 {
+        let fixed_variables =
+            preprocess_qubo(&self.qubo_pp_form, &self.options.fixed_variables, true);
+
+        // a fully fixed root has no relaxation, so its bound is the objective of the only point
+        let root_bound = if fixed_variables.len() == self.qubo.num_x() {
+            let solution = Array1::from_shape_fn(self.qubo.num_x(), |i| fixed_variables[&i]);
+            self.qubo.eval_usize(&solution)
+        } else {
+            let root_node = QuboBBNode::root(self.qubo.num_x(), fixed_variables);
+            self.solve_node(&root_node).0
+        };
+
+        let upper_bound = self.get_incumbent_value();
+        (upper_bound - root_bound) / upper_bound.abs().max(1E-10)
+    }
+
+    /// Checks a claimed optimal solution against its objective and a proven lower bound, without solving anything
+    ///
+    /// The certificate holds if the claimed solution is a binary point of the QUBO, its objective is the claimed
+    /// objective, and the claimed objective matches the lower bound, all within a tolerance of 1E-6 that is relative
+    /// to the objective when it is larger than one.
+    pub fn verify(
+        qubo: &Qubo,
+        claimed: &Array1<usize>,
+        claimed_obj: f64,
+        lower_bound: f64,
+    ) -> bool {
+        if claimed.len() != qubo.num_x() || claimed.iter().any(|&x_i| x_i > 1) {
+            return false;
+        }
+
+        let tolerance = 1E-6 * claimed_obj.abs().max(1.0);
+        let objective_matches = (qubo.eval_binary(claimed) - claimed_obj).abs() <= tolerance;
+        let bound_matches = (claimed_obj - lower_bound).abs() <= tolerance;
+
+        objective_matches && bound_matches
+    }
+
+    /// Processes a batch of nodes in parallel, where each node can prune against the incumbents found by the others
+    pub fn process_nodes(&self, nodes: &[QuboBBNode]) -> Vec<ProcessNodeState> {
+        nodes
+            .par_iter()
+            .map(|node| self.process_node(node))
+            .collect::<Vec<_>>()
+    }
+
+    /// The best objective value found by any thread so far, this can be better than `best_solution_value` while a
+    /// batch of nodes is being processed
+    pub fn get_incumbent_value(&self) -> f64 {
+        self.incumbent.value()
     }
 
     /// Checks if we can prune the node, based on the lower bound and best solution, returns an action
     pub fn can_prune_action(&self, node: &QuboBBNode) -> (PruneAction, Event) {
         // if our parent solution is above our current feasible soltion then prune
-        if node.lower_bound > self.best_solution_value {
+        if node.lower_bound > self.get_incumbent_value() {
             return (PruneAction::Prune, Event::Nill);
         }
 
         // if the solution is complete, then we can update the best solution if better
         // we can also prune the node, as there are no more variables to fix
-        if node.fixed_variables.len() == self.qubo.num_x() {
+        if node.is_leaf(self.qubo.num_x()) {
             // generate the solution vector
             let mut solution = Array1::zeros(self.qubo.num_x());
             for (&index, &value) in &node.fixed_variables {
@@ -201,6 +856,11 @@ impl BBSolver {
                 // increment the number of nodes processed
                 self.nodes_processed += 1;
             }
+            NodeLoggingAction::Pruned => {
+                // increment the number of nodes processed and pruned
+                self.nodes_processed += 1;
+                self.nodes_pruned += 1;
+            }
             NodeLoggingAction::Solved => {
                 // increment the number of nodes solved and processed
                 self.nodes_processed += 1;
@@ -211,8 +871,9 @@ impl BBSolver {
 
     /// main loop of the branch and bound algorithm
     pub fn process_node(&self, node: &QuboBBNode) -> ProcessNodeState {
-        // create a mutable copy of the node
+        // create a mutable copy of the node, the lower bound it was created with is the lower bound of its parent
         let mut node = node.clone();
+        let parent_lower_bound = node.lower_bound;
 
         // pass to the presolver to see if there are any variables we can fix
         node.fixed_variables = preprocess_qubo(&self.qubo_pp_form, &node.fixed_variables, true);
@@ -229,15 +890,31 @@ impl BBSolver {
             return ProcessNodeState {
                 prune_action,
                 events: vec![event],
-                logging: NodeLoggingAction::Processed,
+                logging: NodeLoggingAction::Pruned,
             };
         }
 
         // We now need to solve the node to generate the lower bound and solution
-        let (lower_bound, solution) = self.solve_node(&node);
+        let (lower_bound, solution, reduced_costs) = self.solve_node_with_duals(&node);
+        debug!(
+            "node at depth {} with {} fixed variables has lower bound {lower_bound}",
+            node.depth,
+            node.num_fixed()
+        );
 
-        // inject the solution back into the node
+        // attribute the change in the lower bound to the branch that created this node
+        let pseudo_cost_event = Self::pseudo_cost_event(&node, parent_lower_bound, lower_bound);
+
+        // inject the solution and its lower bound back into the node, the branch strategies measure gains from it
         node.solution = solution.clone();
+        node.lower_bound = lower_bound;
+
+        // fix the free variables that can not leave their bound without passing the incumbent, so that the children
+        // of this node inherit the fixings
+        if let Some(reduced_costs) = reduced_costs {
+            let fixings = reduced_cost_fixing(&node, &reduced_costs, self.get_incumbent_value());
+            node.fixed_variables.extend(fixings);
+        }
 
         // check if integer-feasible solution
         // if not all variables are fixed, we can still check if we are 'near' integer-feasible (within 1E-10) of 0 or 1
@@ -249,35 +926,71 @@ impl BBSolver {
             let value = self.qubo.eval_usize(&rounded_sol);
 
             // if it is better, then we will attempt to update the solution otherwise prune
-            if value < self.best_solution_value {
+            if self.incumbent.try_update(&rounded_sol, value) {
                 return ProcessNodeState {
                     prune_action,
-                    events: vec![Event::UpdateBestSolution(rounded_sol, value)],
+                    events: vec![
+                        Event::UpdateBestSolution(rounded_sol, value),
+                        pseudo_cost_event,
+                    ],
                     logging: NodeLoggingAction::Solved,
                 };
             }
             return ProcessNodeState {
                 prune_action,
-                events: vec![Event::Nill],
+                events: vec![pseudo_cost_event],
                 logging: NodeLoggingAction::Solved,
             };
         }
 
         // if we are going to branch, then we can generate a heuristic solution
         let (heur_sol, heur_obj) = self.options.heuristic.make_heuristic(self, &node);
+        self.incumbent.try_update(&heur_sol, heur_obj);
+
+        // at the root node, also round and polish the relaxed solution to get a good incumbent early
+        let root_event = self.root_incumbent_event(&node);
+
+        // determine what variable we are branching on, if every variable is fixed then the node is a leaf and its
+        // only point is evaluated directly
+        let (branch_id, branch_events) = self.make_branch_with_events(&node);
+        let Some(branch_id) = branch_id else {
+            let leaf_solution =
+                Array1::from_shape_fn(self.qubo.num_x(), |i| node.fixed_variables[&i]);
+            let leaf_value = self.qubo.eval_usize(&leaf_solution);
+            self.incumbent.try_update(&leaf_solution, leaf_value);
+
+            return ProcessNodeState {
+                prune_action,
+                events: vec![
+                    Event::UpdateBestSolution(leaf_solution, leaf_value),
+                    Event::UpdateBestSolution(heur_sol, heur_obj),
+                    pseudo_cost_event,
+                    root_event,
+                ],
+                logging: NodeLoggingAction::Solved,
+            };
+        };
+
+        debug!("branching on x_{branch_id} at depth {}", node.depth);
 
-        // determine what variable we are branching on
-        let branch_id = self.make_branch(&node);
+        // at every level of the tree given by the dive frequency, also dive to a binary solution
+        let dive_event = self.dive_event(&node);
 
         // generate the branches
         let (zero_branch, one_branch) = Self::branch(node, branch_id, lower_bound, solution);
 
+        let mut events = vec![
+            Event::AddBranches(zero_branch, one_branch),
+            Event::UpdateBestSolution(heur_sol, heur_obj),
+            pseudo_cost_event,
+            root_event,
+            dive_event,
+        ];
+        events.extend(branch_events);
+
         ProcessNodeState {
             prune_action,
-            events: vec![
-                Event::AddBranches(zero_branch, one_branch),
-                Event::UpdateBestSolution(heur_sol, heur_obj),
-            ],
+            events,
             logging: NodeLoggingAction::Solved,
         }
     }
@@ -288,20 +1001,175 @@ impl BBSolver {
                 Event::UpdateBestSolution(solution, value) => {
                     self.update_solution_if_better(&solution, value);
                 }
-                Event::AddBranches(zero_branch, one_branch) => {
-                    self.nodes.push(zero_branch);
-                    self.nodes.push(one_branch);
+                Event::AddBranches(mut zero_branch, mut one_branch) => {
+                    if self.options.record_tree {
+                        self.record_branches(&mut zero_branch, &mut one_branch);
+                    }
+
+                    // nodes below the depth limit are not explored, but their lower bound is kept
+                    if self
+                        .options
+                        .max_depth
+                        .is_some_and(|max_depth| zero_branch.depth > max_depth)
+                    {
+                        self.depth_limit_bound = self
+                            .depth_limit_bound
+                            .min(zero_branch.lower_bound)
+                            .min(one_branch.lower_bound);
+                        continue;
+                    }
+
+                    // the children still have the relaxed solution of their parent
+                    let up_first = zero_branch.branch_variable.is_some_and(|variable| {
+                        let relaxed_value = zero_branch.solution[variable];
+                        self.options.branch_direction.up_first(relaxed_value)
+                    });
+
+                    if up_first {
+                        self.nodes.push_children(one_branch, zero_branch);
+                    } else {
+                        self.nodes.push_children(zero_branch, one_branch);
+                    }
+                }
+                Event::UpdatePseudoCost(variable, value, unit_gain) => {
+                    self.pseudo_costs.update(variable, value, unit_gain);
                 }
                 Event::Nill => {}
             }
         }
     }
 
+    /// Records the branching of a node in the tree, and gives the two children their ids in it
+    fn record_branches(&mut self, zero_branch: &mut QuboBBNode, one_branch: &mut QuboBBNode) {
+        // the children are copies of their parent, so they still have its id and the bound it was solved to
+        let parent = zero_branch.id;
+        let variable = zero_branch.branch_variable;
+        self.tree[parent].lower_bound = zero_branch.lower_bound;
+        self.tree[parent].branch_variable = variable;
+
+        for (child, value) in [(zero_branch, 0), (one_branch, 1)] {
+            child.id = self.tree.len();
+            self.tree.push(TreeRecord {
+                parent: Some(parent),
+                branch: variable.map(|variable| (variable, value)),
+                lower_bound: child.lower_bound,
+                branch_variable: None,
+            });
+        }
+    }
+
+    /// Writes the recorded branch and bound tree as a Graphviz DOT graph, where each node is labeled with its lower
+    /// bound and the variable it was branched on, and each edge with the fixing that created the child
+    ///
+    /// The tree is only recorded if `record_tree` is set in the solver options, otherwise the graph is empty. The
+    /// lower bound of a leaf is the bound of its parent, and nodes cut off by the depth limit are leaves.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if it is not possible to write to the writer.
+    pub fn export_tree_dot(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writeln!(writer, "digraph bb_tree {{")?;
+
+        for (id, record) in self.tree.iter().enumerate() {
+            let label = record.branch_variable.map_or_else(
+                || format!("lb = {:.6}", record.lower_bound),
+                |variable| format!("lb = {:.6}\\nbranch x_{variable}", record.lower_bound),
+            );
+            writeln!(writer, "    {id} [label=\"{label}\"];")?;
+
+            if let (Some(parent), Some((variable, value))) = (record.parent, record.branch) {
+                writeln!(
+                    writer,
+                    "    {parent} -> {id} [label=\"x_{variable} = {value}\"];"
+                )?;
+            }
+        }
+
+        writeln!(writer, "}}")
+    }
+
+    /// Generates an incumbent from the relaxed solution of the root node using relaxation rounding, as the rounded
+    /// point is polished over all the variables it is only used if it still agrees with the fixed variables
+    pub fn root_incumbent_event(&self, node: &QuboBBNode) -> Event {
+        // only the root node was not created by a branch
+        if node.branch_variable.is_some() {
+            return Event::Nill;
+        }
+
+        let solution = relaxation_rounding(&self.qubo, &node.solution);
+        let is_consistent = node
+            .fixed_variables
+            .iter()
+            .all(|(&index, &value)| solution[index] == value);
+
+        if !is_consistent {
+            return Event::Nill;
+        }
+
+        let value = self.qubo.eval_usize(&solution);
+        self.incumbent.try_update(&solution, value);
+        Event::UpdateBestSolution(solution, value)
+    }
+
+    /// Dives from a solved node to a binary solution, if its depth is a multiple of the dive frequency, and generates
+    /// the event of the solution the dive found
+    pub fn dive_event(&self, node: &QuboBBNode) -> Event {
+        let is_due = self
+            .options
+            .dive_frequency
+            .is_some_and(|frequency| frequency > 0 && node.depth.is_multiple_of(frequency));
+        if !is_due {
+            return Event::Nill;
+        }
+
+        let Some(solution) = dive(self, node) else {
+            return Event::Nill;
+        };
+
+        let value = self.qubo.eval_usize(&solution);
+        self.incumbent.try_update(&solution, value);
+        Event::UpdateBestSolution(solution, value)
+    }
+
+    /// Generates the pseudocost observation of a solved node, which is the increase of its lower bound over the
+    /// parent's lower bound, per unit change of the branched variable from the parent's relaxed solution
+    pub fn pseudo_cost_event(
+        node: &QuboBBNode,
+        parent_lower_bound: f64,
+        lower_bound: f64,
+    ) -> Event {
+        let Some(variable) = node.branch_variable else {
+            return Event::Nill;
+        };
+
+        // the solution of the node is the relaxed solution of the parent until it is solved
+        let value = node.fixed_variables[&variable];
+        let fractional_change = (value as f64 - node.solution[variable]).abs();
+
+        // if the variable barely moved, then the per unit increase is not meaningful
+        if fractional_change <= 1E-6 {
+            return Event::Nill;
+        }
+
+        let gain = (lower_bound - parent_lower_bound).max(0.0);
+        Event::UpdatePseudoCost(variable, value, gain / fractional_change)
+    }
+
     /// update the best solution if better than the current best solution
     pub fn update_solution_if_better(&mut self, solution: &Array1<usize>, solution_value: f64) {
         if solution_value < self.best_solution_value {
             self.best_solution = solution.clone();
             self.best_solution_value = solution_value;
+            self.incumbent.try_update(solution, solution_value);
+            info!(
+                "new best solution {solution_value} after {} visited nodes",
+                self.nodes_visited
+            );
+
+            // let the user know about the improved solution
+            if let Some(on_incumbent) = self.options.on_incumbent.as_mut() {
+                on_incumbent(&self.best_solution, self.best_solution_value);
+            }
 
             // if we have an early stopping condition, then we can check if we have a solution
             let beck_proof = beck_proof(&self.qubo, &self.best_solution);
@@ -339,6 +1207,8 @@ impl BBSolver {
             if matches!(prune, PruneAction::Dont) {
                 return Some(node);
             }
+
+            self.nodes_pruned += 1;
         }
 
         None
@@ -352,7 +1222,10 @@ impl BBSolver {
             let next_node = self.get_next_node();
 
             // if there is a node to add, do so, else break out as there aren't any nodes left
-            if let Some(node) = next_node {
+            if let Some(mut node) = next_node {
+                // the seeds are drawn in the order the nodes are taken, so a fixed seed gives the same random
+                // decisions no matter how the threads are scheduled
+                node.seed = self.prng.gen_u64();
                 nodes.push(node);
             } else {
                 break;
@@ -364,30 +1237,108 @@ impl BBSolver {
 
     /// Checks for termination conditions of the B&B algorithm, such as time limit or no more nodes
     pub fn termination_condition(&self) -> bool {
+        self.termination_reason().is_some()
+    }
+
+    /// Returns the reason the B&B algorithm should stop, or None if the search should continue
+    pub fn termination_reason(&self) -> Option<TerminationReason> {
         // get current time to check if we have exceeded the maximum time
         let current_time = get_current_time();
 
         // check if we violated the time limit
         if current_time - self.time_start > self.options.max_time {
-            return true;
+            return Some(TerminationReason::TimeLimit);
         }
 
-        // check if we have no more nodes to process
+        // check if we have no more nodes to process, the search is only complete if no nodes were cut off
         if self.nodes.is_empty() {
-            return true;
+            if self.depth_limit_bound < self.best_solution_value {
+                return Some(TerminationReason::DepthLimit);
+            }
+            return Some(TerminationReason::Optimal);
         }
 
         // if we have an early stopping condition, then we can check if we have a solution
         if self.early_stop {
-            return true;
+            return Some(TerminationReason::Optimal);
+        }
+
+        // if no open node can beat the best solution, then the search has proven it optimal
+        let absolute_gap = self.best_solution_value - self.global_lower_bound();
+        if absolute_gap <= 0.0 {
+            return Some(TerminationReason::Optimal);
+        }
+
+        // check if the best solution is close enough to the lower bound, if there is a tolerance on the gap
+        if (self.options.gap_tolerance > 0.0 && absolute_gap <= self.options.gap_tolerance)
+            || (self.options.relative_gap_tolerance > 0.0
+                && self.gap() <= self.options.relative_gap_tolerance)
+        {
+            return Some(TerminationReason::GapTolerance);
+        }
+
+        // check if we have visited as many nodes as we are allowed to
+        if self
+            .options
+            .max_nodes
+            .is_some_and(|max_nodes| self.nodes_visited >= max_nodes)
+        {
+            return Some(TerminationReason::NodeLimit);
         }
 
-        false
+        None
+    }
+
+    /// Selects the variable to branch on with the custom branching rule if there is one, and otherwise with the branch
+    /// strategy of the solver, or None if every variable is fixed
+    ///
+    /// If the custom branching rule gives None for a node that still has free variables, then the branch strategy of
+    /// the solver picks the variable instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the custom branching rule picks a fixed variable, or if it panicked while selecting for another node.
+    pub fn make_branch(&self, node: &QuboBBNode) -> Option<usize> {
+        self.branch_selector.as_ref().map_or_else(
+            || self.branch_strategy.make_branch(self, node),
+            |selector| {
+                let branch_variable = selector
+                    .lock()
+                    .expect("the branch selector panicked on another node")
+                    .select(self, node);
+
+                // hard assert that the variable is not fixed
+                if let Some(branch_variable) = branch_variable {
+                    assert!(
+                        !node.fixed_variables.contains_key(&branch_variable),
+                        "Branching on a fixed variable"
+                    );
+                }
+
+                // only a leaf has nothing to branch on, so fall back to the branch strategy for any other node
+                if branch_variable.is_none() && !node.is_leaf(self.qubo.num_x()) {
+                    return self.branch_strategy.make_branch(self, node);
+                }
+
+                branch_variable
+            },
+        )
     }
 
-    /// Branch Selection Strategy - Currently selects the first variable that is not fixed
-    pub fn make_branch(&self, node: &QuboBBNode) -> usize {
-        self.branch_strategy.make_branch(self, node)
+    /// Selects the variable to branch on as in `make_branch`, along with the pseudocost observations that reliability
+    /// branching learns from its strong branching
+    ///
+    /// # Panics
+    ///
+    /// Panics if the custom branching rule picks a fixed variable, or if it panicked while selecting for another node.
+    pub fn make_branch_with_events(&self, node: &QuboBBNode) -> (Option<usize>, Vec<Event>) {
+        if self.branch_selector.is_none()
+            && matches!(self.branch_strategy, BranchStrategy::Reliability)
+        {
+            return reliability_with_observations(self, node);
+        }
+
+        (self.make_branch(node), Vec::new())
     }
 
     /// Actually branches the node into two new nodes
@@ -406,6 +1357,12 @@ impl BBSolver {
         zero_branch.fixed_variables.insert(branch_id, 0);
         one_branch.fixed_variables.insert(branch_id, 1);
 
+        // record the branching decision, so the children can report how much their lower bound increased
+        zero_branch.branch_variable = Some(branch_id);
+        one_branch.branch_variable = Some(branch_id);
+        zero_branch.depth += 1;
+        one_branch.depth += 1;
+
         // update the solution and lower bound for the new nodes
         zero_branch.solution = solution.clone();
         one_branch.solution = solution;
@@ -420,19 +1377,46 @@ impl BBSolver {
     pub fn solve_node(&self, node: &QuboBBNode) -> (f64, Array1<f64>) {
         self.subproblem_solver.solve_lower_bound(self, node)
     }
+
+    /// Solves the node like `solve_node`, and also returns the reduced costs of the relaxation if they are used for
+    /// reduced cost fixing, which is only done when the relaxation is the QP
+    fn solve_node_with_duals(&self, node: &QuboBBNode) -> (f64, Array1<f64>, Option<Array1<f64>>) {
+        if self.options.reduced_cost_fixing
+            && matches!(
+                self.options.sub_problem_solver,
+                SubProblemSelection::ClarabelQP
+            )
+        {
+            let (lower_bound, solution, reduced_costs) =
+                solve_relaxed_qp_with_duals(&self.qubo, &node.fixed_variables);
+            return (lower_bound, solution, Some(reduced_costs));
+        }
+
+        let (lower_bound, solution) = self.solve_node(node);
+        (lower_bound, solution, None)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::branch_stratagy::BranchStrategy;
+    use crate::branch_node::QuboBBNode;
+    use crate::branch_stratagy::{
+        compute_strong_branch, BranchDirection, BranchSelector, BranchStrategy,
+    };
+    use crate::branch_subproblem::SubProblemSelection;
+    use crate::branchbound::{BBSolver, Event, SolverError, TerminationReason};
+    use crate::exact::brute_force;
+    use crate::node_selection::NodeSelectionStrategy;
     use crate::preprocess::preprocess_qubo;
     use crate::qubo::Qubo;
-    use crate::solver_options::SolverOptions;
+    use crate::solver_options::{RestartPolicy, SolverOptions};
     use crate::tests::{make_solver_qubo, make_test_prng};
     use crate::{branchbound, local_search};
     use ndarray::Array1;
     use sprs::CsMat;
     use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
 
     pub fn get_default_solver_options() -> SolverOptions {
         let mut options = SolverOptions::new();
@@ -450,12 +1434,19 @@ mod tests {
         let p = Qubo::new_with_c(eye, c);
 
         let guess = local_search::particle_swarm_search(&p, 100, 1000, &mut prng);
-        let mut solver = branchbound::BBSolver::new(p, SolverOptions::new());
+        let mut solver = branchbound::BBSolver::new(p, SolverOptions::new()).unwrap();
         solver.warm_start(guess);
-        solver.solve();
+        let result = solver.solve();
 
         assert_eq!(solver.best_solution_value, -4.6);
         assert_eq!(solver.best_solution, Array1::from_vec(vec![1, 1, 1]));
+
+        // the result should match the state of the solver
+        assert_eq!(result.solution, solver.best_solution);
+        assert_eq!(result.nodes_visited, solver.nodes_visited);
+        assert!(result.nodes_pruned <= result.nodes_visited);
+        assert!(result.gap >= 0.0);
+        assert!(result.time >= 0.0);
     }
     #[test]
     pub fn branch_bound_most_violated_branching() {
@@ -493,9 +1484,11 @@ mod tests {
             BranchStrategy::MostEdges,
             BranchStrategy::LargestEdges,
             BranchStrategy::MostFixed,
-            BranchStrategy::FullStrongBranching,
+            BranchStrategy::FullStrongBranch,
             BranchStrategy::PartialStrongBranching,
             BranchStrategy::RoundRobin,
+            BranchStrategy::PseudoCost,
+            BranchStrategy::Reliability,
         ];
 
         // let heuristic_options = vec![
@@ -508,6 +1501,1046 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn branch_bound_mostly_fixed_variables() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(40, &mut prng, 0.5).convex_symmetric_form();
+
+        // fix all but every fifth variable
+        let fixed_variables = (0..40)
+            .filter(|i| i % 5 != 4)
+            .map(|i| (i, usize::from(prng.gen_f64() < 0.5)))
+            .collect::<HashMap<usize, usize>>();
+
+        let p_reduced = p.fix_and_reduce(&fixed_variables);
+        let (_, obj_reduced) = brute_force(&p_reduced);
+
+        for branch_strategy in [
+            BranchStrategy::FirstNotFixed,
+            BranchStrategy::MostViolated,
+            BranchStrategy::Random,
+            BranchStrategy::WorstApproximation,
+            BranchStrategy::BestApproximation,
+            BranchStrategy::MostEdges,
+            BranchStrategy::LargestEdges,
+            BranchStrategy::MostFixed,
+            BranchStrategy::FullStrongBranch,
+            BranchStrategy::PartialStrongBranching,
+            BranchStrategy::RoundRobin,
+            BranchStrategy::PseudoCost,
+            BranchStrategy::Reliability,
+        ] {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.branch_strategy = branch_strategy;
+            options.fixed_variables = fixed_variables.clone();
+
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+            let result = solver.solve();
+
+            assert_eq!(result.status, TerminationReason::Optimal);
+            assert!(fixed_variables
+                .iter()
+                .all(|(&i, &value)| result.solution[i] == value));
+            assert!((result.objective - obj_reduced).abs() < 1E-8);
+        }
+    }
+
+    #[test]
+    pub fn branch_bound_edge_strategies_skip_fixed() {
+        // without any edges, the edge based strategies have no scores to pick a variable by
+        let p = Qubo::new_with_c(CsMat::zero((3, 3)), Array1::from_vec(vec![1.0, -1.0, 1.0]));
+
+        for branch_strategy in [BranchStrategy::MostEdges, BranchStrategy::LargestEdges] {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.branch_strategy = branch_strategy;
+            let solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+            let node = QuboBBNode::root(3, HashMap::from([(0, 0)]));
+            assert_eq!(solver.make_branch(&node), Some(1));
+        }
+    }
+
+    #[test]
+    pub fn branch_bound_most_violated_selection() {
+        let p = make_solver_qubo().convex_symmetric_form();
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.branch_strategy = BranchStrategy::MostViolated;
+        let solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        let mut solution = Array1::zeros(p.num_x());
+        solution[1] = 0.9;
+        solution[2] = 0.4;
+        solution[3] = 0.6;
+        solution[4] = 0.5;
+        solution[5] = 0.5;
+
+        let mut node = QuboBBNode {
+            solution,
+            ..QuboBBNode::root(p.num_x(), HashMap::new())
+        };
+
+        // the variable closest to 0.5 is picked, and ties go to the lowest index
+        assert_eq!(solver.make_branch(&node), Some(4));
+
+        // fixed variables are skipped, even if they are the most fractional
+        node.fixed_variables.insert(4, 0);
+        assert_eq!(solver.make_branch(&node), Some(5));
+    }
+
+    #[test]
+    pub fn branch_bound_all_fixed_node() {
+        let p = make_solver_qubo().convex_symmetric_form();
+        let num_x = p.num_x();
+
+        // every strategy finds nothing to branch on, instead of panicking
+        for branch_strategy in [
+            BranchStrategy::FirstNotFixed,
+            BranchStrategy::MostViolated,
+            BranchStrategy::Random,
+            BranchStrategy::WorstApproximation,
+            BranchStrategy::BestApproximation,
+            BranchStrategy::MostEdges,
+            BranchStrategy::LargestEdges,
+            BranchStrategy::MostFixed,
+            BranchStrategy::FullStrongBranch,
+            BranchStrategy::PartialStrongBranching,
+            BranchStrategy::RoundRobin,
+            BranchStrategy::PseudoCost,
+            BranchStrategy::Reliability,
+        ] {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.branch_strategy = branch_strategy;
+            let solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+            let node = QuboBBNode {
+                solution: Array1::from_elem(num_x, 0.5),
+                ..QuboBBNode::root(num_x, (0..num_x).map(|i| (i, i % 2)).collect())
+            };
+            assert!(node.is_leaf(num_x));
+            assert_eq!(solver.make_branch(&node), None);
+        }
+
+        // a node with a free variable is not a leaf
+        let node = QuboBBNode {
+            solution: Array1::from_elem(num_x, 0.5),
+            ..QuboBBNode::root(num_x, (1..num_x).map(|i| (i, i % 2)).collect())
+        };
+        assert_eq!(node.num_fixed(), num_x - 1);
+        assert!(!node.is_leaf(num_x));
+    }
+
+    #[test]
+    pub fn branch_bound_strong_branching_candidates() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut objectives = Vec::new();
+        for candidates in [None, Some(3)] {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.branch_strategy = BranchStrategy::FullStrongBranch;
+            options.strong_branching_candidates = candidates;
+
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+            objectives.push(solver.solve().objective);
+        }
+
+        assert!((objectives[0] - objectives[1]).abs() < 1E-8);
+    }
+
+    #[test]
+    pub fn branch_bound_solve_decomposed() {
+        let mut prng = make_test_prng();
+        let p_1 = Qubo::make_random_qubo(15, &mut prng, 0.3).convex_symmetric_form();
+        let p_2 = Qubo::make_random_qubo(12, &mut prng, 0.3).convex_symmetric_form();
+
+        // two blocks and a single variable that is worth setting to one
+        let p_3 = Qubo::new_with_c(CsMat::eye(1), Array1::from_vec(vec![-2.0]));
+        let p = p_1.direct_sum(&p_2).direct_sum(&p_3);
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let direct = solver.solve();
+
+        for threads in [1, 4] {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.threads = threads;
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+            let decomposed = solver.solve_decomposed();
+
+            assert_eq!(decomposed.status, TerminationReason::Optimal);
+            assert_eq!(decomposed.solution[27], 1);
+            assert!((p.eval_usize(&decomposed.solution) - decomposed.objective).abs() < 1E-10);
+            assert!((decomposed.objective - direct.objective).abs() < 1E-8);
+            assert!(decomposed.gap.abs() < 1E-8);
+        }
+    }
+
+    #[test]
+    pub fn branch_bound_compute_strong_branch() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(20, &mut prng, 0.3).convex_symmetric_form();
+        let solver = branchbound::BBSolver::new(p.clone(), get_default_solver_options()).unwrap();
+
+        let node = QuboBBNode {
+            lower_bound: 0.0,
+            solution: Array1::from_shape_fn(20, |i| (i as f64 + 0.5) / 20.0),
+            ..QuboBBNode::root(20, HashMap::from([(3, 1), (7, 0)]))
+        };
+        let (zero_flip, one_flip) = compute_strong_branch(&solver, &node);
+
+        // compare against evaluating the objective at the changed points
+        let mut x = node.solution;
+        x[3] = 1.0;
+        x[7] = 0.0;
+        let obj = p.eval(&x);
+        for i in 0..20 {
+            for (value, flip) in [(0.0, zero_flip[i]), (1.0, one_flip[i])] {
+                let mut x_i = x.clone();
+                x_i[i] = value;
+                assert!((p.eval(&x_i) - obj - flip).abs() < 1E-10);
+            }
+        }
+    }
+
+    #[test]
+    pub fn branch_bound_node_limit() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.threads = 1;
+        options.verbose = 0;
+        options.max_nodes = Some(3);
+
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let result = solver.solve();
+
+        // a batch is only taken while under the limit, so the limit is exceeded by less than a batch
+        assert_eq!(result.status, TerminationReason::NodeLimit);
+        assert!(result.nodes_visited >= 3 && result.nodes_visited < 5);
+        assert!((p.eval_usize(&result.solution) - result.objective).abs() < 1E-10);
+        assert!(result.lower_bound <= result.objective);
+    }
+
+    #[test]
+    pub fn branch_bound_restarts() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(14, &mut prng, 0.5).convex_symmetric_form();
+        let (_, opt) = brute_force(&p);
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.record_tree = true;
+        options.restart_policy = Some(RestartPolicy::Luby { unit: 2 });
+
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        let result = solver.solve();
+
+        // the search is still complete, and each restart adds a new root to the recorded tree
+        assert_eq!(result.status, TerminationReason::Optimal);
+        assert!((result.objective - opt).abs() < 1E-6);
+        assert!(result.restarts > 0);
+        assert_eq!(result.restarts, solver.restarts);
+        let num_roots = solver
+            .tree
+            .iter()
+            .filter(|record| record.parent.is_none())
+            .count();
+        assert_eq!(num_roots, result.restarts + 1);
+    }
+
+    #[test]
+    pub fn branch_bound_diving() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(14, &mut prng, 0.5).convex_symmetric_form();
+        let (_, opt) = brute_force(&p);
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.dive_frequency = Some(2);
+
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        let result = solver.solve();
+
+        // the dives only add incumbents, so the search still finds the optimum
+        assert_eq!(result.status, TerminationReason::Optimal);
+        assert!((result.objective - opt).abs() < 1E-6);
+    }
+
+    #[test]
+    pub fn branch_bound_reduced_cost_fixing() {
+        let mut prng = make_test_prng();
+        let mut nodes_visited = [0, 0];
+
+        for _ in 0..5 {
+            let p = Qubo::make_random_qubo(30, &mut prng, 0.5).convex_symmetric_form();
+
+            // solve once without the fixings to get the optimum, which is then the incumbent from the start
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            let reference = branchbound::BBSolver::new(p.clone(), options)
+                .unwrap()
+                .solve();
+            let (x_opt, opt) = (reference.solution, reference.objective);
+
+            for (k, reduced_cost_fixing) in [false, true].into_iter().enumerate() {
+                let mut options = get_default_solver_options();
+                options.verbose = 0;
+                options.initial_solution = Some(x_opt.clone());
+                options.reduced_cost_fixing = reduced_cost_fixing;
+
+                let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+                let result = solver.solve();
+
+                assert_eq!(result.status, TerminationReason::Optimal);
+                assert!((result.objective - opt).abs() < 1E-6);
+                nodes_visited[k] += result.nodes_visited;
+            }
+        }
+
+        // with the optimum as the incumbent, the fixings cut off subtrees that would otherwise be explored
+        assert!(nodes_visited[1] < nodes_visited[0]);
+    }
+
+    #[test]
+    pub fn branch_bound_branch_direction() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(14, &mut prng, 0.5).convex_symmetric_form();
+        let (_, opt) = brute_force(&p);
+
+        for (direction, relaxed_value, first_value) in [
+            (BranchDirection::Up, 0.2, 1),
+            (BranchDirection::Down, 0.8, 0),
+            (BranchDirection::TowardRelaxation, 0.2, 0),
+            (BranchDirection::TowardRelaxation, 0.8, 1),
+        ] {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.node_selection = NodeSelectionStrategy::DepthFirst;
+            options.branch_direction = direction;
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+            // the child in the branch direction is taken from the queue first
+            let node = QuboBBNode::root(p.num_x(), HashMap::new());
+            let mut solution = Array1::from_elem(p.num_x(), 0.5);
+            solution[3] = relaxed_value;
+            let (zero_branch, one_branch) = BBSolver::branch(node, 3, f64::NEG_INFINITY, solution);
+            solver.apply_events(vec![Event::AddBranches(zero_branch, one_branch)]);
+            assert_eq!(solver.nodes.pop().unwrap().fixed_variables[&3], first_value);
+            assert_eq!(
+                solver.nodes.pop().unwrap().fixed_variables[&3],
+                1 - first_value
+            );
+
+            // the order of the children does not change the optimum
+            let result = solver.solve();
+            assert_eq!(result.status, TerminationReason::Optimal);
+            assert!((result.objective - opt).abs() < 1E-6);
+        }
+    }
+
+    #[test]
+    pub fn branch_bound_logging() {
+        // counts the info records of the solver, the records of the other tests that run at the same time only add
+        struct CountingLogger(AtomicUsize);
+
+        impl log::Log for CountingLogger {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Info
+            }
+
+            fn log(&self, record: &log::Record) {
+                if self.enabled(record.metadata()) && record.target().starts_with("hercules") {
+                    self.0.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CountingLogger = CountingLogger(AtomicUsize::new(0));
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Info);
+
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(14, &mut prng, 0.5).convex_symmetric_form();
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        solver.solve();
+
+        // at least the improved incumbents and the end of the search are logged
+        assert!(LOGGER.0.load(Ordering::Relaxed) >= 2);
+    }
+
+    #[test]
+    pub fn branch_bound_root_gap() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(14, &mut prng, 0.5).convex_symmetric_form();
+        let (x_opt, opt) = brute_force(&p);
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.initial_solution = Some(x_opt.clone());
+        let solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        // the root relaxation is a lower bound on the optimum, so the gap to the optimum is not negative
+        let root_gap = solver.root_gap();
+        assert!(root_gap >= -1E-8);
+        assert!((solver.get_incumbent_value() - opt).abs() < 1E-10);
+
+        // with every variable fixed to the optimum, there is no gap at the root
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.initial_solution = Some(x_opt.clone());
+        options.fixed_variables = x_opt.iter().copied().enumerate().collect();
+        let solver = branchbound::BBSolver::new(p, options).unwrap();
+        assert!(solver.root_gap().abs() < 1E-10);
+    }
+
+    #[test]
+    pub fn branch_bound_depth_limit() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(40, &mut prng, 0.5).convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.max_depth = Some(2);
+
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        let result = solver.solve();
+
+        // at most the root and two levels below it are explored
+        assert_eq!(result.status, TerminationReason::DepthLimit);
+        assert!(result.nodes_visited <= 7);
+        assert!(result.lower_bound <= result.objective);
+    }
+
+    #[test]
+    pub fn branch_bound_custom_branch_selector() {
+        // branches on the last variable that is not fixed, and counts how often it is asked
+        struct LastNotFixed {
+            calls: Arc<Mutex<usize>>,
+        }
+
+        impl BranchSelector for LastNotFixed {
+            fn select(&mut self, solver: &BBSolver, node: &QuboBBNode) -> Option<usize> {
+                *self.calls.lock().unwrap() += 1;
+                (0..solver.qubo.num_x())
+                    .rev()
+                    .find(|i| !node.fixed_variables.contains_key(i))
+            }
+        }
+
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let default_result = solver.solve();
+
+        let calls = Arc::new(Mutex::new(0));
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.branch_selector = Some(Box::new(LastNotFixed {
+            calls: calls.clone(),
+        }));
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        let custom_result = solver.solve();
+
+        // the custom rule is used for every branch, and the search still finds the optimum
+        assert!(*calls.lock().unwrap() > 0);
+        assert!((custom_result.objective - default_result.objective).abs() < 1E-8);
+
+        // the built-in strategies are selectors as well
+        let node = QuboBBNode::root(solver.qubo.num_x(), HashMap::from([(0, 1)]));
+        let mut strategy = BranchStrategy::FirstNotFixed;
+        assert_eq!(strategy.select(&solver, &node), Some(1));
+    }
+
+    #[test]
+    pub fn branch_bound_branch_selector_none() {
+        // a selector that never picks a variable, even when there are free variables
+        struct NeverBranch;
+
+        impl BranchSelector for NeverBranch {
+            fn select(&mut self, _: &BBSolver, _: &QuboBBNode) -> Option<usize> {
+                None
+            }
+        }
+
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let default_result = branchbound::BBSolver::new(p.clone(), options)
+            .unwrap()
+            .solve();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.branch_selector = Some(Box::new(NeverBranch));
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        // the branch strategy of the solver picks the variable instead, so the search still finds the optimum
+        let node = QuboBBNode::root(p.num_x(), HashMap::from([(0, 1)]));
+        assert!(solver.make_branch(&node).is_some());
+
+        let result = solver.solve();
+        assert_eq!(result.status, TerminationReason::Optimal);
+        assert!((result.objective - default_result.objective).abs() < 1E-8);
+    }
+
+    #[test]
+    pub fn branch_bound_verify() {
+        let p = make_solver_qubo().convex_symmetric_form();
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let result = solver.solve();
+        let (x, obj, bound) = (&result.solution, result.objective, result.lower_bound);
+        assert!(BBSolver::verify(&p, x, obj, bound));
+
+        // a wrong objective, a loose bound, and points that are not binary points of the QUBO all fail
+        assert!(!BBSolver::verify(&p, x, obj + 1.0, bound));
+        assert!(!BBSolver::verify(&p, x, obj, bound - 1.0));
+
+        let mut x_bad = x.clone();
+        x_bad[0] = 2;
+        assert!(!BBSolver::verify(&p, &x_bad, obj, bound));
+        let x_short = x.slice(ndarray::s![1..]).to_owned();
+        assert!(!BBSolver::verify(&p, &x_short, obj, bound));
+    }
+
+    #[test]
+    pub fn branch_bound_deep_depth_first() {
+        // every term is zero at both binary values but negative at one half, and the weak couplings of alternating
+        // sign keep the preprocessing from fixing more than about one variable per branch, so the nodes can not be
+        // pruned until they are nearly fixed, and the first dive goes far down the tree
+        let num_x = 120;
+        let mut q = sprs::TriMat::new((num_x, num_x));
+        for i in 0..num_x {
+            q.add_triplet(i, i, 4.0);
+            if i + 1 < num_x {
+                let coupling = if i % 2 == 0 { 0.01 } else { -0.01 };
+                q.add_triplet(i, i + 1, coupling);
+                q.add_triplet(i + 1, i, coupling);
+            }
+        }
+        let p = Qubo::new_with_c(q.to_csr(), Array1::from_elem(num_x, -2.0));
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.threads = 1;
+        options.node_selection = NodeSelectionStrategy::DepthFirst;
+        options.max_nodes = Some(num_x + 1);
+        options.record_tree = true;
+
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        let result = solver.solve();
+        assert_eq!(result.status, TerminationReason::NodeLimit);
+
+        // the depth of the deepest recorded node
+        let depth = |mut id: usize| {
+            let mut depth = 0;
+            while let Some(parent) = solver.tree[id].parent {
+                id = parent;
+                depth += 1;
+            }
+            depth
+        };
+        let max_depth = (0..solver.tree.len()).map(depth).max().unwrap();
+        assert!(max_depth >= num_x / 3);
+    }
+
+    #[test]
+    pub fn branch_bound_export_tree_dot() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(20, &mut prng, 0.5).convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.record_tree = true;
+
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        solver.solve();
+
+        // every node but the root was created by a branch, two at a time
+        assert_eq!(solver.tree.len() % 2, 1);
+        assert!(solver.tree[0].parent.is_none());
+        assert!(solver.tree[1..]
+            .iter()
+            .all(|record| record.parent.is_some()));
+
+        let mut dot = Vec::new();
+        solver.export_tree_dot(&mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+
+        assert!(dot.starts_with("digraph bb_tree {"));
+        assert_eq!(dot.matches(" -> ").count(), solver.tree.len() - 1);
+    }
+
+    #[test]
+    pub fn branch_bound_threads_match_serial() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut objectives = Vec::new();
+        for threads in [1, 4] {
+            let mut options = get_default_solver_options();
+            options.threads = threads;
+            options.verbose = 0;
+
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+            let result = solver.solve();
+
+            // the search runs to completion, so there is no gap left
+            assert!((p.eval_usize(&result.solution) - result.objective).abs() < 1E-10);
+            assert!(result.gap.abs() < 1E-10);
+            assert_eq!(result.status, TerminationReason::Optimal);
+            objectives.push(result.objective);
+        }
+
+        assert!((objectives[0] - objectives[1]).abs() < 1E-8);
+    }
+
+    #[test]
+    pub fn branch_bound_node_selection() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut objectives = Vec::new();
+        for node_selection in [
+            NodeSelectionStrategy::BestBound,
+            NodeSelectionStrategy::DepthFirst,
+            NodeSelectionStrategy::BreadthFirst,
+        ] {
+            let mut options = get_default_solver_options();
+            options.node_selection = node_selection;
+            options.verbose = 0;
+
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+            let result = solver.solve();
+            objectives.push(result.objective);
+        }
+
+        assert!((objectives[0] - objectives[1]).abs() < 1E-8);
+        assert!((objectives[0] - objectives[2]).abs() < 1E-8);
+    }
+
+    #[test]
+    pub fn branch_bound_optimal_status() {
+        let mut prng = make_test_prng();
+
+        // with the default options, a search that finishes has proven its solution optimal
+        for _ in 0..30 {
+            let p = Qubo::make_random_qubo(12, &mut prng, 0.5).convex_symmetric_form();
+            let (_, opt) = brute_force(&p);
+
+            let mut options = SolverOptions::new();
+            options.verbose = 0;
+            let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+            let result = solver.solve();
+
+            assert_eq!(result.status, TerminationReason::Optimal);
+            assert!((result.objective - opt).abs() < 1E-6);
+        }
+    }
+
+    #[test]
+    pub fn branch_bound_gap_tolerance() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        // solve to optimality to get a reference objective
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let optimal = branchbound::BBSolver::new(p.clone(), options)
+            .unwrap()
+            .solve();
+
+        // with a very loose tolerance the solver stops as soon as there is a finite lower bound
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.relative_gap_tolerance = 1E10;
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let result = solver.solve();
+
+        assert_eq!(result.status, TerminationReason::GapTolerance);
+        assert!(result.gap <= 1E10);
+        assert!(result.nodes_visited <= optimal.nodes_visited);
+        assert!(result.objective >= optimal.objective - 1E-8);
+        assert!((p.eval_usize(&result.solution) - result.objective).abs() < 1E-10);
+    }
+
+    #[test]
+    pub fn branch_bound_initial_solution() {
+        let p = make_solver_qubo().convex_symmetric_form();
+        let x_0 = Array1::ones(p.num_x());
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.initial_solution = Some(x_0.clone());
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        // the initial solution is the incumbent before the search starts
+        assert!((solver.best_solution_value - p.eval_binary(&x_0)).abs() < 1E-10);
+        assert!((solver.get_incumbent_value() - solver.best_solution_value).abs() < 1E-10);
+
+        let result = solver.solve();
+        assert!(result.objective <= p.eval_binary(&x_0) + 1E-10);
+    }
+
+    #[test]
+    pub fn branch_bound_invalid_initial_solution() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        // an initial solution that disagrees with a fixed variable is rejected
+        let mut options = get_default_solver_options();
+        options.fixed_variables.insert(0, 0);
+        options.initial_solution = Some(Array1::ones(p.num_x()));
+        assert!(branchbound::BBSolver::new(p.clone(), options).is_err());
+
+        // as is an initial solution of the wrong size
+        let mut options = get_default_solver_options();
+        options.initial_solution = Some(Array1::ones(p.num_x() + 1));
+        assert!(branchbound::BBSolver::new(p.clone(), options).is_err());
+
+        // and an initial solution that is not binary
+        let mut options = get_default_solver_options();
+        let mut x_0 = Array1::zeros(p.num_x());
+        x_0[3] = 2;
+        options.initial_solution = Some(x_0);
+        let result = branchbound::BBSolver::new(p, options);
+        assert!(matches!(
+            result,
+            Err(SolverError::InvalidInitialSolution(_))
+        ));
+    }
+
+    #[test]
+    pub fn branch_bound_lp_non_convex() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.5).make_symmetric();
+        let (_, opt) = brute_force(&p);
+        assert!(!p.is_convex());
+
+        // the McCormick LP is a lower bound of any QUBO, so the QUBO does not need to be convex
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.sub_problem_solver = SubProblemSelection::ClarabelLP;
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        let result = solver.solve();
+
+        assert_eq!(result.status, TerminationReason::Optimal);
+        assert!((result.objective - opt).abs() < 1E-6);
+    }
+
+    #[test]
+    pub fn branch_bound_rejects_invalid_qubo() {
+        let p = make_solver_qubo();
+
+        // the test QUBO is neither symmetric nor convex
+        let result = branchbound::BBSolver::new(p.clone(), get_default_solver_options());
+        assert!(matches!(result, Err(SolverError::NotSymmetric)));
+
+        let result = branchbound::BBSolver::new(p.make_symmetric(), get_default_solver_options());
+        assert!(matches!(result, Err(SolverError::NotConvex)));
+
+        let result =
+            branchbound::BBSolver::new(p.convex_symmetric_form(), get_default_solver_options());
+        assert!(result.is_ok());
+
+        // without the convexity check only the symmetry is checked
+        let result = BBSolver::new_assume_convex(p.clone(), get_default_solver_options());
+        assert!(matches!(result, Err(SolverError::NotSymmetric)));
+
+        let result = BBSolver::new_assume_convex(p.make_symmetric(), get_default_solver_options());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    pub fn branch_bound_pseudo_cost_tables_update() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.threads = 1;
+        options.branch_strategy = BranchStrategy::PseudoCost;
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        // nothing is learned before the search starts
+        assert_eq!(solver.pseudo_costs.down_count.sum(), 0);
+        assert_eq!(solver.pseudo_costs.up_count.sum(), 0);
+
+        // process the root node by hand, it branches but its children are not solved yet
+        let root = QuboBBNode::root(p.num_x(), HashMap::new());
+        let state = solver.process_node(&root);
+        solver.apply_events(state.events);
+        assert_eq!(solver.pseudo_costs.down_count.sum(), 0);
+        assert_eq!(solver.nodes.len(), 2);
+
+        // processing the children records an observation for the branched variable
+        let children = solver.get_next_nodes(1);
+        for state in solver.process_nodes(&children) {
+            solver.apply_events(state.events);
+        }
+
+        let observations =
+            solver.pseudo_costs.down_count.sum() + solver.pseudo_costs.up_count.sum();
+        assert!(observations > 0);
+        for i in 0..p.num_x() {
+            assert!(solver.pseudo_costs.down(i).unwrap_or_default() >= 0.0);
+            assert!(solver.pseudo_costs.up(i).unwrap_or_default() >= 0.0);
+        }
+    }
+
+    #[test]
+    pub fn branch_bound_pseudo_cost_matches_most_violated() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut objectives = Vec::new();
+        for branch_strategy in [BranchStrategy::MostViolated, BranchStrategy::PseudoCost] {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.branch_strategy = branch_strategy;
+
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+            objectives.push(solver.solve().objective);
+        }
+
+        assert!((objectives[0] - objectives[1]).abs() < 1E-8);
+    }
+
+    #[test]
+    pub fn branch_bound_random_branching_is_seeded() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut results = Vec::new();
+        for _ in 0..2 {
+            let mut options = get_default_solver_options();
+            options.verbose = 0;
+            options.seed = 7;
+            options.threads = 1;
+            options.branch_strategy = BranchStrategy::Random;
+
+            let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+            let result = solver.solve();
+            results.push((result.solution, result.nodes_visited));
+        }
+
+        // the same seed gives the same tree
+        assert_eq!(results[0], results[1]);
+
+        // the branching variable only depends on the seed of the node
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.branch_strategy = BranchStrategy::Random;
+        let solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        let node = QuboBBNode {
+            seed: 12345,
+            ..QuboBBNode::root(p.num_x(), HashMap::new())
+        };
+        assert_eq!(solver.make_branch(&node), solver.make_branch(&node));
+    }
+
+    #[test]
+    pub fn branch_bound_on_incumbent_callback() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let incumbents = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&incumbents);
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.on_incumbent = Some(Box::new(move |x: &Array1<usize>, value: f64| {
+            recorded.lock().unwrap().push((x.clone(), value));
+        }));
+
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let result = solver.solve();
+
+        // every improvement is reported with its objective, and the last one is the final solution
+        let incumbents = std::mem::take(&mut *incumbents.lock().unwrap());
+        assert!(!incumbents.is_empty());
+        for (x, value) in &incumbents {
+            assert!((p.eval_usize(x) - value).abs() < 1E-10);
+        }
+        for pair in incumbents.windows(2) {
+            assert!(pair[1].1 < pair[0].1);
+        }
+
+        let (x_last, value_last) = incumbents.last().unwrap();
+        assert_eq!(*x_last, result.solution);
+        assert!((value_last - result.objective).abs() < 1E-10);
+    }
+
+    #[test]
+    pub fn branch_bound_solve_streaming() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let result = branchbound::BBSolver::new(p.clone(), options)
+            .unwrap()
+            .solve();
+
+        // the stream yields strictly improving incumbents, ending with the optimum of the full solve
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let incumbents = solver.solve_streaming().collect::<Vec<_>>();
+        assert!(!incumbents.is_empty());
+        for (x, value) in &incumbents {
+            assert!((p.eval_usize(x) - value).abs() < 1E-10);
+        }
+        for pair in incumbents.windows(2) {
+            assert!(pair[1].1 < pair[0].1);
+        }
+
+        let (_, value_last) = incumbents.last().unwrap();
+        assert!((value_last - result.objective).abs() < 1E-10);
+        assert_eq!(
+            solver.termination_reason(),
+            Some(TerminationReason::Optimal)
+        );
+    }
+
+    #[test]
+    pub fn branch_bound_time_limit() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.max_time = 0.0;
+
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let result = solver.solve();
+
+        // the incumbent is still a valid solution, and the lower bound is below it
+        assert_eq!(result.status, TerminationReason::TimeLimit);
+        assert_eq!(result.solution.len(), p.num_x());
+        assert!((p.eval_usize(&result.solution) - result.objective).abs() < 1E-10);
+        assert!(result.lower_bound <= result.objective);
+    }
+
+    #[test]
+    pub fn branch_bound_fixing_above_offset() {
+        // the optimum with x_0 fixed to one is above the offset, so the start must not prune it
+        let q = CsMat::eye(2).map(|&q_ii: &f64| 2.0 * q_ii);
+        let p = Qubo::new_with_c(q, Array1::zeros(2));
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.fixed_variables.insert(0, 1);
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let result = solver.solve();
+
+        assert_eq!(result.status, TerminationReason::Optimal);
+        assert_eq!(result.solution, Array1::from_vec(vec![1, 0]));
+        assert!((result.objective - 1.0).abs() < 1E-10);
+
+        // a warm start that breaks the fixing is made to agree with it
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.fixed_variables.insert(0, 1);
+        let mut solver = branchbound::BBSolver::new(p, options).unwrap();
+        solver.warm_start(Array1::zeros(2));
+        assert_eq!(solver.best_solution[0], 1);
+        assert_eq!(solver.solve().status, TerminationReason::Optimal);
+    }
+
+    #[test]
+    pub fn branch_bound_fixing_without_search() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        // even with no time to search, the starting incumbent agrees with the fixing
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.max_time = 0.0;
+        options.fixed_variables.insert(0, 1);
+
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+        let result = solver.solve();
+
+        assert_eq!(result.status, TerminationReason::TimeLimit);
+        assert_eq!(result.solution[0], 1);
+        assert!((p.eval_usize(&result.solution) - result.objective).abs() < 1E-10);
+    }
+
+    #[test]
+    pub fn branch_bound_reliability_learns_from_strong_branching() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        options.threads = 1;
+        options.branch_strategy = BranchStrategy::Reliability;
+        options.reliability_threshold = 1;
+        let mut solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        let root = QuboBBNode::root(p.num_x(), HashMap::new());
+        let strong_branched = |events: &[Event]| {
+            events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::UpdatePseudoCost(variable, _, _) => Some(*variable),
+                    _ => None,
+                })
+                .collect::<Vec<usize>>()
+        };
+
+        // the root has no branch variable, so every observation comes from strong branching
+        let state = solver.process_node(&root);
+        let first = strong_branched(&state.events);
+        solver.apply_events(state.events);
+
+        let observations =
+            solver.pseudo_costs.down_count.sum() + solver.pseudo_costs.up_count.sum();
+        assert!(!first.is_empty());
+        assert_eq!(observations, first.len());
+
+        // the variables with observations in both directions are now reliable, and are not strong branched again
+        let is_reliable =
+            |i: usize| solver.pseudo_costs.down_count[i].min(solver.pseudo_costs.up_count[i]) >= 1;
+        let num_reliable = (0..p.num_x()).filter(|&i| is_reliable(i)).count();
+        assert!(num_reliable > 0);
+
+        let state = solver.process_node(&root);
+        let second = strong_branched(&state.events);
+        assert!(second.iter().all(|&i| !is_reliable(i)));
+        assert!(second.len() < first.len());
+    }
+
+    #[test]
+    pub fn branch_bound_root_relaxation_rounding() {
+        let p = make_solver_qubo().convex_symmetric_form();
+
+        let mut options = get_default_solver_options();
+        options.verbose = 0;
+        let solver = branchbound::BBSolver::new(p.clone(), options).unwrap();
+
+        let mut root = QuboBBNode::root(p.num_x(), HashMap::new());
+        let (_, solution) = solver.solve_node(&root);
+        root.solution = solution;
+
+        // the root node gives an incumbent that is a 1-opt local minimum
+        let Event::UpdateBestSolution(x, value) = solver.root_incumbent_event(&root) else {
+            panic!("The root node did not generate an incumbent");
+        };
+        assert!((p.eval_usize(&x) - value).abs() < 1E-10);
+        assert!(solver.get_incumbent_value() <= value);
+        assert_eq!(local_search::local_search_1opt(&p, x.clone()), x);
+
+        // other nodes do not
+        root.branch_variable = Some(0);
+        assert!(matches!(solver.root_incumbent_event(&root), Event::Nill));
+    }
+
     pub fn setup_and_solve_problem(branch: BranchStrategy) {
         let mut prng = make_test_prng();
 
@@ -525,10 +2558,10 @@ mod tests {
         options.branch_strategy = branch;
         options.fixed_variables = fixed_variables.clone();
 
-        let mut solver = branchbound::BBSolver::new(p_symm_conv, options);
+        let mut solver = branchbound::BBSolver::new(p_symm_conv, options).unwrap();
         solver.warm_start(guess);
 
-        let (solution, _) = solver.solve();
+        let solution = solver.solve().solution;
 
         // ensure that the solution is actually possible with the preprocessor
         for (&index, &val) in fixed_variables.iter() {
@@ -133,7 +133,8 @@ mod tests {
         let mut solver = BBSolver::new(
             Qubo::new_with_c(CsMat::eye(3), Array1::from_vec(vec![1.0, -2.0, 3.0])),
             SolverOptions::new(),
-        );
+        )
+        .unwrap();
 
         let solver_logger = SolverOutputLogger { output_level: 1 };
 
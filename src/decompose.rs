@@ -0,0 +1,156 @@
+//! This module contains functions to decompose a QUBO into independent subproblems
+//!
+//! If the interaction graph of a QUBO is disconnected, then the objective is a sum of independent parts, one for each
+//! connected component, and each part can be solved on its own.
+
+use crate::qubo::Qubo;
+use ndarray::Array1;
+use sprs::TriMat;
+
+/// Splits the QUBO into the sub-QUBOs of the connected components of its interaction graph, along with the original
+/// indices of their variables.
+///
+/// Two variables are connected if $Q_{ij} + Q_{ji} \neq 0$. The components are ordered by their smallest variable,
+/// and the variables of each component are in increasing order, so the k-th variable of a sub-QUBO is the k-th
+/// index of its list. Variables without interactions are singleton components, whose optimal value only depends on
+/// the sign of $c_i + 0.5 Q_{ii}$. The offset of the QUBO is given to the first component, so for every point, the
+/// objective of the QUBO is the sum of the objectives of the components at their variables.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::decompose::connected_components;
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p_1 = Qubo::make_random_qubo(10, &mut prng, 0.5);
+/// let p_2 = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // a QUBO with at least two components
+/// let p = p_1.direct_sum(&p_2);
+/// let components = connected_components(&p);
+/// ```
+pub fn connected_components(qubo: &Qubo) -> Vec<(Qubo, Vec<usize>)> {
+    let num_x = qubo.num_x();
+    let q_sym = qubo.make_symmetric();
+
+    // label the components with a depth first search over the interactions
+    let mut component = vec![usize::MAX; num_x];
+    let mut variables = Vec::<Vec<usize>>::new();
+
+    for root in 0..num_x {
+        if component[root] != usize::MAX {
+            continue;
+        }
+
+        let label = variables.len();
+        let mut members = vec![root];
+        let mut stack = vec![root];
+        component[root] = label;
+
+        while let Some(i) = stack.pop() {
+            if let Some(row) = q_sym.q.outer_view(i) {
+                for (j, &value) in row.iter() {
+                    if i != j && value != 0.0 && component[j] == usize::MAX {
+                        component[j] = label;
+                        members.push(j);
+                        stack.push(j);
+                    }
+                }
+            }
+        }
+
+        members.sort_unstable();
+        variables.push(members);
+    }
+
+    // the index of each variable inside of its component
+    let mut local_index = vec![0; num_x];
+    for members in &variables {
+        for (k, &i) in members.iter().enumerate() {
+            local_index[i] = k;
+        }
+    }
+
+    // distribute the terms of the QUBO to the components, a term between components is always zero
+    let mut q_parts = variables
+        .iter()
+        .map(|members| TriMat::<f64>::new((members.len(), members.len())))
+        .collect::<Vec<_>>();
+    for (&value, (i, j)) in &qubo.q {
+        if component[i] == component[j] {
+            q_parts[component[i]].add_triplet(local_index[i], local_index[j], value);
+        }
+    }
+
+    q_parts
+        .into_iter()
+        .zip(variables)
+        .enumerate()
+        .map(|(label, (q, members))| {
+            let c = members.iter().map(|&i| qubo.c[i]).collect::<Array1<f64>>();
+            let mut sub_qubo = Qubo::new_with_c(q.to_csr(), c);
+            if label == 0 {
+                sub_qubo.offset = qubo.offset;
+            }
+            (sub_qubo, members)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decompose::connected_components;
+    use crate::qubo::Qubo;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_connected_components() {
+        // x_0 - x_3 and x_1 - x_4 - x_5 are coupled, x_2 only has a diagonal term, and x_6 is coupled in a way that
+        // cancels
+        let p = Qubo::from_vec(
+            vec![0, 1, 5, 2, 6, 0],
+            vec![3, 4, 4, 2, 0, 6],
+            vec![1.0, -2.0, 3.0, 1.5, 2.0, -2.0],
+            vec![0.5, -1.0, 0.0, 2.0, 1.0, -1.0, 3.0],
+            7,
+        );
+
+        let components = connected_components(&p);
+        let variables = components
+            .iter()
+            .map(|(_, members)| members.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(variables, vec![vec![0, 3], vec![1, 4, 5], vec![2], vec![6]]);
+
+        // the objective is the sum of the objectives of the components
+        for k in 0..(1usize << 7) {
+            let x = Array1::from_shape_fn(7, |i| (k >> i) & 1);
+            let obj_parts = components
+                .iter()
+                .map(|(sub_qubo, members)| {
+                    let x_sub = members.iter().map(|&i| x[i]).collect::<Array1<usize>>();
+                    sub_qubo.eval_usize(&x_sub)
+                })
+                .sum::<f64>();
+
+            assert!((p.eval_usize(&x) - obj_parts).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_connected_components_direct_sum() {
+        let mut prng = make_test_prng();
+        let p_1 = Qubo::make_random_qubo(10, &mut prng, 0.8);
+        let p_2 = Qubo::make_random_qubo(8, &mut prng, 0.8);
+        let p = p_1.direct_sum(&p_2);
+
+        // the blocks never interact, so no component spans both
+        for (_, members) in connected_components(&p) {
+            assert!(members.iter().all(|&i| i < 10) || members.iter().all(|&i| i >= 10));
+        }
+    }
+}
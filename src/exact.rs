@@ -0,0 +1,284 @@
+//! This module contains exact solvers for small QUBO problems, that are used as a baseline for the other solvers
+//!
+//! Currently the following solvers are implemented:
+//! - Brute force enumeration in Gray code order
+//! - Enumeration of all optimal solutions, by brute force for small problems and by B&B otherwise
+
+use crate::branch_node::QuboBBNode;
+use crate::branchbound::BBSolver;
+use crate::local_search::flip_and_update_deltas;
+use crate::qubo::Qubo;
+use crate::solver_options::SolverOptions;
+use ndarray::Array1;
+use std::collections::HashMap;
+
+/// The largest number of variables that brute force will enumerate, as the run time doubles with every variable
+pub const MAX_BRUTE_FORCE_VARIABLES: usize = 25;
+
+/// Finds a global minimum of the QUBO by enumerating all $2^n$ binary points, and returns it with its objective.
+///
+/// The points are visited in Gray code order, so consecutive points differ by a single flip. The single flip deltas
+/// are kept up to date incrementally, so each step only costs a pass over one column of the Hessian, rather than a
+/// full evaluation. On ties, the first point in Gray code order is returned, starting at the zero vector.
+///
+/// # Panics
+///
+/// Will panic if the QUBO has more than `MAX_BRUTE_FORCE_VARIABLES` variables.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::exact::brute_force;
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(12, &mut prng, 0.3);
+///
+/// let (x_opt, obj_opt) = brute_force(&p);
+/// ```
+pub fn brute_force(qubo: &Qubo) -> (Array1<usize>, f64) {
+    let num_x = qubo.num_x();
+    assert!(
+        num_x <= MAX_BRUTE_FORCE_VARIABLES,
+        "Brute force is limited to {MAX_BRUTE_FORCE_VARIABLES} variables, the QUBO has {num_x}"
+    );
+
+    // at the zero vector, the gradient is c, so the single flip deltas are c_i + 0.5 S_ii
+    let q_sym = qubo.make_symmetric();
+    let mut x = Array1::<usize>::zeros(num_x);
+    let mut direction = Array1::<f64>::ones(num_x);
+    let mut deltas = &q_sym.c + &(0.5 * &q_sym.q.diag().to_dense());
+
+    let mut obj = qubo.offset;
+    let mut best_x = x.clone();
+    let mut best_obj = obj;
+
+    // the k-th step of the Gray code flips the lowest set bit of k
+    for k in 1usize..(1 << num_x) {
+        let i = k.trailing_zeros() as usize;
+
+        obj += deltas[i];
+        flip_and_update_deltas(&q_sym, i, &mut x, &mut direction, &mut deltas);
+
+        if obj < best_obj {
+            best_obj = obj;
+            best_x.assign(&x);
+        }
+    }
+
+    // recompute the objective, so that it does not carry the rounding error of the incremental updates
+    let best_obj = qubo.eval_usize(&best_x);
+    (best_x, best_obj)
+}
+
+/// Finds every global minimum of the QUBO, which is every binary point with an objective within `tol` of the optimum,
+/// in lexicographic order.
+///
+/// QUBOs with at most `MAX_BRUTE_FORCE_VARIABLES` variables are enumerated by brute force. Larger QUBOs are solved
+/// with B&B on the convex symmetric form to find the optimal objective, and then the tree is searched again where a
+/// node is only pruned if its lower bound is strictly above the optimum plus `tol`, so that the alternative optima
+/// with the same objective are kept. As the objectives of equal optima can differ by rounding error, `tol` should be
+/// a little above zero, and the number of optima can be exponential in the number of variables.
+///
+/// Example:
+/// ``` rust
+/// use hercules::exact::all_optima;
+/// use hercules::generators::{max_cut_qubo, Graph};
+///
+/// // the maximum cuts of a square are the two alternating cuts
+/// let mut graph = Graph::new(4);
+/// graph.add_edge(0, 1, 1.0).add_edge(1, 2, 1.0).add_edge(2, 3, 1.0).add_edge(3, 0, 1.0);
+///
+/// let optima = all_optima(&max_cut_qubo(&graph), 1E-8);
+/// assert_eq!(optima.len(), 2);
+/// ```
+pub fn all_optima(qubo: &Qubo, tol: f64) -> Vec<Array1<usize>> {
+    let candidates = if qubo.num_x() <= MAX_BRUTE_FORCE_VARIABLES {
+        brute_force_candidates(qubo, tol)
+    } else {
+        branch_and_bound_candidates(qubo, tol)
+    };
+
+    // the candidates contain every optimum, so keep the ones within the tolerance of the best of them
+    let objectives = candidates
+        .iter()
+        .map(|x| qubo.eval_usize(x))
+        .collect::<Vec<f64>>();
+    let best_obj = objectives.iter().copied().fold(f64::INFINITY, f64::min);
+
+    let mut optima = candidates
+        .into_iter()
+        .zip(objectives)
+        .filter(|(_, obj)| *obj <= best_obj + tol)
+        .map(|(x, _)| x)
+        .collect::<Vec<Array1<usize>>>();
+    optima.sort_by(|x, y| x.iter().cmp(y.iter()));
+    optima
+}
+
+/// The points of the brute force enumeration that are within `tol` of the best point visited after them
+fn brute_force_candidates(qubo: &Qubo, tol: f64) -> Vec<Array1<usize>> {
+    let num_x = qubo.num_x();
+
+    // the same Gray code walk as brute force, where the incremental objective is off by rounding error, so the points
+    // are kept a little beyond the tolerance and filtered on their exact objective afterward
+    let tol = tol + 1E-9;
+    let q_sym = qubo.make_symmetric();
+    let mut x = Array1::<usize>::zeros(num_x);
+    let mut direction = Array1::<f64>::ones(num_x);
+    let mut deltas = &q_sym.c + &(0.5 * &q_sym.q.diag().to_dense());
+
+    let mut obj = qubo.offset;
+    let mut best_obj = obj;
+    let mut candidates = vec![(x.clone(), obj)];
+
+    for k in 1usize..(1 << num_x) {
+        let i = k.trailing_zeros() as usize;
+
+        obj += deltas[i];
+        flip_and_update_deltas(&q_sym, i, &mut x, &mut direction, &mut deltas);
+
+        if obj <= best_obj + tol {
+            candidates.push((x.clone(), obj));
+
+            // a new best point can rule out the earlier candidates
+            if obj < best_obj {
+                best_obj = obj;
+                candidates.retain(|(_, obj_j)| *obj_j <= best_obj + tol);
+            }
+        }
+    }
+
+    candidates.into_iter().map(|(x, _)| x).collect()
+}
+
+/// The leaves of a B&B search that only prunes nodes with a lower bound strictly above the optimum plus `tol`
+///
+/// Unlike the B&B solver, the nodes are not preprocessed and every node is branched on until all of its variables are
+/// fixed, as fixing persistent variables or stopping at an integral relaxation can both discard alternative optima.
+fn branch_and_bound_candidates(qubo: &Qubo, tol: f64) -> Vec<Array1<usize>> {
+    let num_x = qubo.num_x();
+
+    let mut options = SolverOptions::new();
+    options.verbose = 0;
+    let p = qubo.make_symmetric().convex_symmetric_form();
+    let mut solver = BBSolver::new_assume_convex(p, options)
+        .expect("the convex symmetric form of a QUBO is symmetric");
+
+    // the relaxations are only solved to a tolerance, so their bounds are given some slack before pruning on them
+    let threshold = solver.solve().objective + tol;
+    let slack = 1E-6 * threshold.abs().max(1.0);
+
+    let mut candidates = Vec::new();
+    let mut stack = vec![HashMap::new()];
+
+    while let Some(fixed_variables) = stack.pop() {
+        if fixed_variables.len() == num_x {
+            let x = Array1::from_shape_fn(num_x, |i| fixed_variables[&i]);
+            if qubo.eval_usize(&x) <= threshold + slack {
+                candidates.push(x);
+            }
+            continue;
+        }
+
+        let node = QuboBBNode::root(num_x, fixed_variables);
+
+        // only a bound that is strictly worse than the optimum prunes, so equal valued alternatives are kept
+        let (lower_bound, _) = solver.solve_node(&node);
+        if lower_bound > threshold + slack {
+            continue;
+        }
+
+        let variable = (0..num_x)
+            .find(|i| !node.fixed_variables.contains_key(i))
+            .expect("a node with free variables has a variable to branch on");
+        for value in [1, 0] {
+            let mut fixed_variables = node.fixed_variables.clone();
+            fixed_variables.insert(variable, value);
+            stack.push(fixed_variables);
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::exact::{all_optima, branch_and_bound_candidates, brute_force};
+    use crate::generators::{max_cut_qubo, Graph};
+    use crate::qubo::Qubo;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+    use sprs::CsMat;
+
+    #[test]
+    fn test_brute_force() {
+        let mut prng = make_test_prng();
+
+        for num_x in [1, 2, 5, 10] {
+            let p = Qubo::make_random_qubo(num_x, &mut prng, 0.5);
+            let (x, obj) = brute_force(&p);
+
+            // compare against evaluating every point directly
+            let opt = (0..(1usize << num_x))
+                .map(|k| p.eval_usize(&Array1::from_shape_fn(num_x, |i| (k >> i) & 1)))
+                .fold(f64::INFINITY, f64::min);
+
+            assert!((obj - opt).abs() < 1e-10);
+            assert!((p.eval_usize(&x) - obj).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_brute_force_no_variables() {
+        let p = Qubo::new(CsMat::<f64>::zero((0, 0)));
+        let (x, obj) = brute_force(&p);
+
+        assert_eq!(x.len(), 0);
+        assert!(obj.abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_all_optima() {
+        // the maximum cuts of an even cycle are the two alternating cuts
+        let mut graph = Graph::new(6);
+        for u in 0..6 {
+            graph.add_edge(u, (u + 1) % 6, 1.0);
+        }
+        let p = max_cut_qubo(&graph);
+
+        let alternating = Array1::from_shape_fn(6, |i| i % 2);
+        let target = vec![alternating.clone(), alternating.mapv(|x_i| 1 - x_i)];
+        assert_eq!(all_optima(&p, 1E-8), target);
+        assert_eq!(branch_and_bound_candidates(&p, 1E-8), target);
+
+        // with a large enough tolerance, every point is an optimum
+        assert_eq!(all_optima(&p, 100.0).len(), 64);
+    }
+
+    #[test]
+    fn test_all_optima_branch_and_bound() {
+        // the first three variables do not change the objective, and the rest are one at the optimum
+        let c = Array1::from_shape_fn(30, |i| if i < 3 { -0.5 } else { -1.0 });
+        let p = Qubo::new_with_c(CsMat::<f64>::eye(30), c);
+
+        let optima = all_optima(&p, 1E-8);
+        assert_eq!(optima.len(), 8);
+        for (k, x) in optima.iter().enumerate() {
+            // the optima are in lexicographic order, so the free variables count up in binary
+            let free = (0..3).map(|i| x[i] << (2 - i)).sum::<usize>();
+            assert_eq!(free, k);
+            assert!(x.iter().skip(3).all(|&x_i| x_i == 1));
+            assert!((p.eval_usize(x) + 13.5).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Brute force is limited")]
+    fn test_brute_force_too_large() {
+        let p = Qubo::new(CsMat::<f64>::eye(26));
+        brute_force(&p);
+    }
+}
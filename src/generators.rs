@@ -0,0 +1,176 @@
+//! This module contains generators for structured QUBO problems, from standard reductions of well known problems
+//!
+//! Currently the following problems are implemented:
+//! - Max-Cut
+//! - Number Partitioning
+
+use crate::qubo::Qubo;
+use ndarray::Array1;
+use sprs::TriMat;
+
+/// A weighted undirected graph, stored as a list of edges
+#[derive(Clone, Debug)]
+pub struct Graph {
+    /// The number of nodes in the graph
+    pub num_nodes: usize,
+    /// The edges of the graph, as (u, v, weight)
+    pub edges: Vec<(usize, usize, f64)>,
+}
+
+impl Graph {
+    /// Creates a graph with `num_nodes` nodes and no edges
+    pub const fn new(num_nodes: usize) -> Self {
+        Self {
+            num_nodes,
+            edges: Vec::new(),
+        }
+    }
+
+    /// Adds an undirected edge between u and v with the given weight
+    pub fn add_edge(&mut self, u: usize, v: usize, weight: f64) -> &mut Self {
+        self.edges.push((u, v, weight));
+        self
+    }
+}
+
+/// Creates the QUBO of the max-cut problem of a graph, where $x_u$ is the side of the cut node u is on. The weight of
+/// the cut is
+///
+/// $$ \sum_{(u, v)} w_{uv} (x_u + x_v - 2x_u x_v) $$
+///
+/// So the QUBO is the negative of this, and the optimal objective of the QUBO is the negative of the maximum cut.
+///
+/// Example:
+/// ``` rust
+/// use hercules::generators::{max_cut_qubo, Graph};
+///
+/// // the triangle graph, where the maximum cut has a weight of 2
+/// let mut graph = Graph::new(3);
+/// graph.add_edge(0, 1, 1.0).add_edge(1, 2, 1.0).add_edge(0, 2, 1.0);
+///
+/// let p = max_cut_qubo(&graph);
+/// ```
+pub fn max_cut_qubo(graph: &Graph) -> Qubo {
+    let num_x = graph.num_nodes;
+    let mut q = TriMat::<f64>::new((num_x, num_x));
+    let mut c = Array1::<f64>::zeros(num_x);
+
+    for &(u, v, weight) in &graph.edges {
+        // self loops can never be cut
+        if u == v {
+            continue;
+        }
+
+        // the term -2 w x_u x_v is split between Q_uv and Q_vu, with the factor of 0.5 in the objective
+        q.add_triplet(u, v, 2.0 * weight);
+        q.add_triplet(v, u, 2.0 * weight);
+        c[u] -= weight;
+        c[v] -= weight;
+    }
+
+    Qubo::new_with_c(q.to_csr(), c)
+}
+
+/// Creates the QUBO of the number partitioning problem, where $x_i$ is the set that value i is placed in. The squared
+/// difference of the sums of the two sets is
+///
+/// $$ \left(\sum_i a_i (2x_i - 1)\right)^2 = 4 \left(\sum_i a_i x_i\right)^2 - 4 S \sum_i a_i x_i + S^2 $$
+///
+/// Where S is the sum of all the values. The constant $S^2$ is the offset, so the objective of the QUBO is the squared
+/// difference, and a perfect partition has an objective of zero.
+///
+/// Example:
+/// ``` rust
+/// use hercules::generators::number_partition_qubo;
+///
+/// let p = number_partition_qubo(&[3, 1, 1, 2, 2, 1]);
+/// ```
+pub fn number_partition_qubo(values: &[i64]) -> Qubo {
+    let num_x = values.len();
+    let total = values.iter().sum::<i64>() as f64;
+
+    let mut q = TriMat::<f64>::new((num_x, num_x));
+    let mut c = Array1::<f64>::zeros(num_x);
+
+    for (i, &a_i) in values.iter().enumerate() {
+        let a_i = a_i as f64;
+
+        // as x_i^2 = x_i, the squared terms are linear
+        c[i] = 4.0 * a_i * a_i - 4.0 * total * a_i;
+
+        for (j, &a_j) in values.iter().enumerate() {
+            if i != j {
+                q.add_triplet(i, j, 8.0 * a_i * a_j as f64);
+            }
+        }
+    }
+
+    Qubo {
+        q: q.to_csr(),
+        c,
+        offset: total * total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators::{max_cut_qubo, number_partition_qubo, Graph};
+    use crate::qubo::Qubo;
+    use ndarray::Array1;
+
+    fn brute_force(p: &Qubo) -> (Array1<usize>, f64) {
+        let num_x = p.num_x();
+        (0..(1usize << num_x))
+            .map(|k| Array1::from_shape_fn(num_x, |i| (k >> i) & 1))
+            .map(|x| {
+                let obj = p.eval_usize(&x);
+                (x, obj)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_max_cut_triangle() {
+        let mut graph = Graph::new(3);
+        graph
+            .add_edge(0, 1, 1.0)
+            .add_edge(1, 2, 1.0)
+            .add_edge(0, 2, 1.0);
+        let p = max_cut_qubo(&graph);
+
+        // the objective is the negative of the cut at every point
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| (k >> i) & 1);
+            let cut = graph
+                .edges
+                .iter()
+                .filter(|&&(u, v, _)| x[u] != x[v])
+                .map(|&(_, _, weight)| weight)
+                .sum::<f64>();
+            assert!((p.eval_usize(&x) + cut).abs() < 1e-10);
+        }
+
+        // the maximum cut of a triangle cuts two edges
+        let (_, obj) = brute_force(&p);
+        assert!((obj + 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_number_partition() {
+        let values = [3, 1, 1, 2, 2, 1];
+        let p = number_partition_qubo(&values);
+        let (x, obj) = brute_force(&p);
+
+        // there is a perfect partition, so the objective is zero
+        assert!(obj.abs() < 1e-10);
+
+        let set_sum = values
+            .iter()
+            .zip(x.iter())
+            .filter(|(_, &x_i)| x_i == 1)
+            .map(|(&a_i, _)| a_i)
+            .sum::<i64>();
+        assert_eq!(set_sum, 5);
+    }
+}
@@ -0,0 +1,273 @@
+//! # Genetic Algorithm
+//!
+//! This module contains a genetic algorithm for QUBO problems, a population based heuristic for large instances. Each
+//! generation, parents are picked by tournament selection and combined with a crossover operator, uniform crossover
+//! by default, and the offspring are mutated by random bit flips. Optionally, every offspring is improved with 1-opt
+//! local search, which is the memetic variant of the algorithm. The offspring of a generation are created and
+//! evaluated in parallel.
+
+pub mod crossover;
+
+use crate::genetic::crossover::CrossoverOperator;
+use crate::initial_points::generate_random_binary_points;
+use crate::local_search::local_search_1opt;
+use crate::qubo::Qubo;
+use ndarray::Array1;
+use rayon::prelude::*;
+use smolprng::{Algorithm, JsfLarge, PRNG};
+
+/// The number of individuals that compete in each tournament when selecting a parent
+const TOURNAMENT_SIZE: usize = 2;
+
+/// Runs a genetic algorithm with a population of `pop_size` random points for `generations` generations, and returns
+/// the best individual found across all generations.
+///
+/// Each bit of an offspring is flipped with probability `mutation_rate`. If `memetic` is true, then every offspring is
+/// improved with 1-opt local search before it joins the population. The best individual of each generation is always
+/// carried over to the next one.
+///
+/// The parents of every offspring are selected up front, along with a seed drawn from `prng`, and each offspring is
+/// then created with its own generator seeded from it. So the offspring can be created, improved and evaluated in
+/// parallel, and the result only depends on the state of `prng` and not on the thread scheduling.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::genetic::genetic_algorithm;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+///
+/// // run the memetic variant with a population of 20 for 50 generations, with a 2% mutation rate
+/// let x_sol = genetic_algorithm(&p, 20, 50, 0.02, true, &mut prng);
+/// ```
+pub fn genetic_algorithm<T: Algorithm>(
+    qubo: &Qubo,
+    pop_size: usize,
+    generations: usize,
+    mutation_rate: f64,
+    memetic: bool,
+    prng: &mut PRNG<T>,
+) -> Array1<usize> {
+    genetic_algorithm_with_crossover(
+        qubo,
+        pop_size,
+        generations,
+        mutation_rate,
+        memetic,
+        crossover::uniform,
+        prng,
+    )
+}
+
+/// Runs the genetic algorithm like `genetic_algorithm`, but combines the parents with the given crossover operator
+///
+/// Any function with the signature of the operators in `crossover` can be used, as long as it only draws from the
+/// generator it is given, so that the result still only depends on the state of `prng`.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::genetic::{crossover, genetic_algorithm_with_crossover};
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+///
+/// // run the genetic algorithm with two point crossover
+/// let x_sol = genetic_algorithm_with_crossover(&p, 20, 50, 0.02, false, crossover::two_point, &mut prng);
+/// ```
+pub fn genetic_algorithm_with_crossover<T: Algorithm>(
+    qubo: &Qubo,
+    pop_size: usize,
+    generations: usize,
+    mutation_rate: f64,
+    memetic: bool,
+    crossover: CrossoverOperator,
+    prng: &mut PRNG<T>,
+) -> Array1<usize> {
+    let num_x = qubo.num_x();
+    let pop_size = pop_size.max(1);
+
+    let mut population = generate_random_binary_points(num_x, pop_size, prng);
+    if memetic {
+        population = population
+            .into_par_iter()
+            .map(|x| local_search_1opt(qubo, x))
+            .collect();
+    }
+    let mut fitness = qubo.eval_many(&population);
+
+    let (mut best_x, mut best_obj) = fittest(&population, &fitness);
+
+    for _ in 0..generations {
+        // select the parents of every offspring, and the seed of the generator it is created with
+        let matings = (1..pop_size)
+            .map(|_| {
+                let parent_a = tournament_selection(&fitness, prng);
+                let parent_b = tournament_selection(&fitness, prng);
+                (parent_a, parent_b, prng.gen_u64())
+            })
+            .collect::<Vec<(usize, usize, u64)>>();
+
+        let offspring = matings
+            .into_par_iter()
+            .map(|(parent_a, parent_b, seed)| {
+                let mut child_prng = PRNG {
+                    generator: JsfLarge::from(seed),
+                };
+
+                let mut child = crossover(
+                    &population[parent_a],
+                    &population[parent_b],
+                    &mut child_prng,
+                );
+                mutate(&mut child, mutation_rate, &mut child_prng);
+
+                if memetic {
+                    child = local_search_1opt(qubo, child);
+                }
+                child
+            })
+            .collect::<Vec<Array1<usize>>>();
+
+        // elitism, the best individual survives into the next generation unchanged
+        let mut next_population = vec![best_x.clone()];
+        let mut next_fitness = vec![best_obj];
+        next_fitness.extend(qubo.eval_many(&offspring));
+        next_population.extend(offspring);
+
+        population = next_population;
+        fitness = next_fitness;
+
+        let (generation_x, generation_obj) = fittest(&population, &fitness);
+        if generation_obj < best_obj {
+            best_x = generation_x;
+            best_obj = generation_obj;
+        }
+    }
+
+    best_x
+}
+
+/// Returns a copy of the individual with the lowest objective, and its objective
+fn fittest(population: &[Array1<usize>], fitness: &[f64]) -> (Array1<usize>, f64) {
+    let mut best = 0;
+    for (i, &value) in fitness.iter().enumerate() {
+        if value < fitness[best] {
+            best = i;
+        }
+    }
+
+    (population[best].clone(), fitness[best])
+}
+
+/// Picks a few random individuals and returns the index of the one with the lowest objective
+fn tournament_selection<T: Algorithm>(fitness: &[f64], prng: &mut PRNG<T>) -> usize {
+    #[allow(clippy::cast_possible_truncation)]
+    // the population size is bounded by usize::MAX
+    let mut winner = prng.gen_u64() as usize % fitness.len();
+
+    for _ in 1..TOURNAMENT_SIZE {
+        #[allow(clippy::cast_possible_truncation)]
+        // the population size is bounded by usize::MAX
+        let challenger = prng.gen_u64() as usize % fitness.len();
+        if fitness[challenger] < fitness[winner] {
+            winner = challenger;
+        }
+    }
+
+    winner
+}
+
+/// Flips each bit of x with probability `mutation_rate`
+fn mutate<T: Algorithm>(x: &mut Array1<usize>, mutation_rate: f64, prng: &mut PRNG<T>) {
+    for x_i in x.iter_mut() {
+        if prng.gen_f64() < mutation_rate {
+            *x_i = 1 - *x_i;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::genetic::crossover::{self, CrossoverOperator};
+    use crate::genetic::{genetic_algorithm, genetic_algorithm_with_crossover};
+    use crate::qubo::Qubo;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+
+    fn brute_force_optimum(p: &Qubo) -> f64 {
+        let num_x = p.num_x();
+        (0..(1usize << num_x))
+            .map(|k| p.eval_usize(&Array1::from_shape_fn(num_x, |i| (k >> i) & 1)))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn test_genetic_algorithm_optimal() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.5);
+        let opt = brute_force_optimum(&p);
+
+        let x_ga = genetic_algorithm(&p, 20, 50, 0.05, false, &mut prng);
+        assert!((p.eval_usize(&x_ga) - opt).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_memetic_algorithm_optimal() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.5);
+        let opt = brute_force_optimum(&p);
+
+        let x_ga = genetic_algorithm(&p, 10, 10, 0.05, true, &mut prng);
+        assert!((p.eval_usize(&x_ga) - opt).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_genetic_algorithm_crossover_operators() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.5);
+        let opt = brute_force_optimum(&p);
+
+        let operators: [CrossoverOperator; 3] = [
+            crossover::uniform,
+            crossover::single_point,
+            crossover::two_point,
+        ];
+        for operator in operators {
+            let x_ga =
+                genetic_algorithm_with_crossover(&p, 10, 10, 0.05, true, operator, &mut prng);
+            assert!((p.eval_usize(&x_ga) - opt).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_genetic_algorithm_deterministic() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(40, &mut prng, 0.2);
+
+        // the offspring are created in parallel, but the same generator state gives the same result
+        let x_a = genetic_algorithm(&p, 30, 20, 0.05, true, &mut make_test_prng());
+        let x_b = genetic_algorithm(&p, 30, 20, 0.05, true, &mut make_test_prng());
+        assert_eq!(x_a, x_b);
+    }
+
+    #[test]
+    fn test_genetic_algorithm_no_generations() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(30, &mut prng, 0.2);
+
+        // with no generations, the best of the initial memetic population is a local minimum
+        let x_ga = genetic_algorithm(&p, 5, 0, 0.05, true, &mut prng);
+        let x_1opt = crate::local_search::local_search_1opt(&p, x_ga.clone());
+        assert_eq!(x_ga, x_1opt);
+    }
+}
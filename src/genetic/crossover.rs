@@ -0,0 +1,158 @@
+//! This module contains the crossover operators of the genetic algorithm, which combine two parents into a child
+//!
+//! Currently the following operators are implemented:
+//! - Uniform, each bit is taken from either parent with equal probability
+//! - Single point, the bits before a random cut come from the first parent and the rest from the second
+//! - Two point, the bits between two random cuts come from the second parent and the rest from the first
+//!
+//! Every operator only draws from the given generator, so the child is determined by the parents and its state.
+
+use ndarray::Array1;
+use smolprng::{Algorithm, JsfLarge, PRNG};
+
+/// A crossover operator as used by the genetic algorithm, which creates each child with its own generator
+pub type CrossoverOperator =
+    fn(&Array1<usize>, &Array1<usize>, &mut PRNG<JsfLarge>) -> Array1<usize>;
+
+/// Creates a child that takes each bit from either parent with equal probability
+///
+/// Example:
+/// ``` rust
+/// use hercules::genetic::crossover;
+/// use ndarray::Array1;
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+///
+/// let child = crossover::uniform(&Array1::zeros(10), &Array1::ones(10), &mut prng);
+/// ```
+pub fn uniform<T: Algorithm>(
+    parent_a: &Array1<usize>,
+    parent_b: &Array1<usize>,
+    prng: &mut PRNG<T>,
+) -> Array1<usize> {
+    Array1::from_shape_fn(parent_a.len(), |i| {
+        if prng.gen_bool() {
+            parent_a[i]
+        } else {
+            parent_b[i]
+        }
+    })
+}
+
+/// Creates a child that takes the bits before a random cut from the first parent, and the rest from the second
+///
+/// The cut is drawn uniformly from the positions between two bits, so each parent gives at least one bit. With fewer
+/// than two bits there is nowhere to cut, and the child is a copy of the first parent.
+pub fn single_point<T: Algorithm>(
+    parent_a: &Array1<usize>,
+    parent_b: &Array1<usize>,
+    prng: &mut PRNG<T>,
+) -> Array1<usize> {
+    let num_x = parent_a.len();
+    if num_x < 2 {
+        return parent_a.clone();
+    }
+
+    let cut = 1 + random_index(num_x - 1, prng);
+    Array1::from_shape_fn(num_x, |i| if i < cut { parent_a[i] } else { parent_b[i] })
+}
+
+/// Creates a child that takes the bits between two random cuts from the second parent, and the rest from the first
+///
+/// The two cuts are a uniformly random pair of distinct positions between two bits, so both ends come from the first
+/// parent and at least one bit in the middle from the second. With fewer than three bits there is no such pair, and
+/// this falls back on single point crossover.
+pub fn two_point<T: Algorithm>(
+    parent_a: &Array1<usize>,
+    parent_b: &Array1<usize>,
+    prng: &mut PRNG<T>,
+) -> Array1<usize> {
+    let num_x = parent_a.len();
+    if num_x < 3 {
+        return single_point(parent_a, parent_b, prng);
+    }
+
+    // pick two distinct positions out of the num_x - 1 positions between bits
+    let num_cuts = num_x - 1;
+    let first = random_index(num_cuts, prng);
+    let mut second = random_index(num_cuts - 1, prng);
+    if second >= first {
+        second += 1;
+    }
+
+    let start = 1 + first.min(second);
+    let end = 1 + first.max(second);
+    Array1::from_shape_fn(num_x, |i| {
+        if (start..end).contains(&i) {
+            parent_b[i]
+        } else {
+            parent_a[i]
+        }
+    })
+}
+
+/// Draws a uniformly random index below `n`
+fn random_index<T: Algorithm>(n: usize, prng: &mut PRNG<T>) -> usize {
+    #[allow(clippy::cast_possible_truncation)]
+    // the number of variables is bounded by usize::MAX
+    let index = prng.gen_u64() as usize % n;
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::genetic::crossover::{single_point, two_point, uniform, CrossoverOperator};
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_crossover_segments() {
+        let mut prng = make_test_prng();
+        let parent_a = Array1::<usize>::zeros(10);
+        let parent_b = Array1::<usize>::ones(10);
+
+        for _ in 0..100 {
+            // a run of zeros from the first parent, followed by a run of ones from the second
+            let child = single_point(&parent_a, &parent_b, &mut prng);
+            let cut = child.iter().take_while(|&&x_i| x_i == 0).count();
+            assert!((1..10).contains(&cut));
+            assert!(child.iter().skip(cut).all(|&x_i| x_i == 1));
+
+            // a run of ones from the second parent, with zeros from the first parent on both sides
+            let child = two_point(&parent_a, &parent_b, &mut prng);
+            let start = child.iter().take_while(|&&x_i| x_i == 0).count();
+            let end = start
+                + child
+                    .iter()
+                    .skip(start)
+                    .take_while(|&&x_i| x_i == 1)
+                    .count();
+            assert!(start >= 1 && end > start && end <= 9);
+            assert!(child.iter().skip(end).all(|&x_i| x_i == 0));
+
+            // every bit agrees with both parents where they agree
+            let child = uniform(&parent_a, &parent_a, &mut prng);
+            assert_eq!(child, parent_a);
+        }
+
+        // too short to cut, so the first parent is copied
+        let child = two_point(&Array1::zeros(1), &Array1::ones(1), &mut prng);
+        assert_eq!(child, Array1::<usize>::zeros(1));
+    }
+
+    #[test]
+    fn test_crossover_deterministic() {
+        let parent_a = Array1::from_shape_fn(30, |i| i % 2);
+        let parent_b = Array1::from_shape_fn(30, |i| (i / 3) % 2);
+
+        let operators: [CrossoverOperator; 3] = [uniform, single_point, two_point];
+        for operator in operators {
+            let child_a = operator(&parent_a, &parent_b, &mut make_test_prng());
+            let child_b = operator(&parent_a, &parent_b, &mut make_test_prng());
+            assert_eq!(child_a, child_b);
+        }
+    }
+}
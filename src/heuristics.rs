@@ -0,0 +1,213 @@
+//! This module contains heuristics that turn the solutions of relaxations into feasible binary solutions, or that
+//! construct them from scratch
+//!
+//! Currently the following heuristics are implemented:
+//! - Relaxation rounding, rounding to the nearest binary point and polishing with 1-opt
+//! - Greedy construction, setting variables to one while it decreases the objective
+//! - Diving, fixing the most fractional variable of the relaxation of a B&B node until the relaxation is integral
+
+use crate::branch_node::QuboBBNode;
+use crate::branchbound::BBSolver;
+use crate::branchbound_utils::check_integer_feasibility;
+use crate::local_search::{flip_and_update_deltas, local_search_1opt};
+use crate::qubo::Qubo;
+use crate::utils::rounded_vector;
+use ndarray::Array1;
+
+/// Rounds a fractional solution of the continuous relaxation to the nearest binary point, then polishes it with 1-opt.
+///
+/// This is a cheap way to get a good incumbent from the relaxation of the root node.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::heuristics::relaxation_rounding;
+/// use hercules::initial_points;
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+///
+/// // a fractional point, such as the solution of the relaxation
+/// let x_relaxed = initial_points::generate_central_starting_points(p.num_x());
+///
+/// let x = relaxation_rounding(&p, &x_relaxed);
+/// ```
+pub fn relaxation_rounding(qubo: &Qubo, x_relaxed: &Array1<f64>) -> Array1<usize> {
+    let x_rounded = rounded_vector(x_relaxed);
+    local_search_1opt(qubo, x_rounded)
+}
+
+/// Starts from the zero vector, and repeatedly sets the variable to one that gives the largest decrease in the
+/// objective, until setting any other variable to one would not decrease it.
+///
+/// Unlike 1-opt local search, variables are only ever set from zero to one, so this takes at most n steps. The single
+/// flip deltas are updated incrementally after each step, so each step costs a pass over the deltas and the nonzeros
+/// of one row. This is a cheap way to get a warm start for the branch and bound solver.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::heuristics::greedy_construct;
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+///
+/// let x = greedy_construct(&p);
+/// ```
+pub fn greedy_construct(qubo: &Qubo) -> Array1<usize> {
+    let num_x = qubo.num_x();
+
+    // at the zero vector, the gradient is c, so the single flip deltas are c_i + 0.5 S_ii
+    let q_sym = qubo.make_symmetric();
+    let mut x = Array1::<usize>::zeros(num_x);
+    let mut direction = Array1::<f64>::ones(num_x);
+    let mut deltas = &q_sym.c + &(0.5 * &q_sym.q.diag().to_dense());
+
+    // set the variable that decreases the objective the most, the deltas of the variables that are one are ignored
+    while let Some((k, _)) = deltas
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(i, delta)| x[i] == 0 && delta < 0.0)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    {
+        flip_and_update_deltas(&q_sym, k, &mut x, &mut direction, &mut deltas);
+    }
+
+    x
+}
+
+/// Dives from a solved node of the B&B tree to a binary solution.
+///
+/// Each step fixes the most fractional free variable to its rounded value and solves the relaxation again, until the
+/// relaxed solution is integral. The dive is abandoned when the lower bound of the relaxation reaches the incumbent of
+/// the solver, as no solution below it can improve on the incumbent, and then None is returned. Otherwise the returned
+/// solution agrees with the fixed variables of the node. Each step solves one relaxation, so a dive solves at most as
+/// many relaxations as there are free variables.
+pub fn dive(solver: &BBSolver, node: &QuboBBNode) -> Option<Array1<usize>> {
+    let mut dive_node = node.clone();
+
+    loop {
+        let (is_int_feasible, rounded_solution) = check_integer_feasibility(&dive_node);
+        if is_int_feasible {
+            return Some(rounded_solution);
+        }
+
+        // the free variable that is closest to 0.5, which exists as the relaxed solution is not integral
+        let fractionality = |x_i: f64| x_i.min(1.0 - x_i);
+        let variable = (0..solver.qubo.num_x())
+            .filter(|i| !dive_node.fixed_variables.contains_key(i))
+            .max_by(|&i, &j| {
+                fractionality(dive_node.solution[i])
+                    .total_cmp(&fractionality(dive_node.solution[j]))
+            })?;
+        let value = usize::from(dive_node.solution[variable] >= 0.5);
+        dive_node.fixed_variables.insert(variable, value);
+
+        // like the fully fixed nodes of the tree, the last point of the dive is not solved as a relaxation
+        if dive_node.is_leaf(solver.qubo.num_x()) {
+            return Some(Array1::from_shape_fn(solver.qubo.num_x(), |i| {
+                dive_node.fixed_variables[&i]
+            }));
+        }
+
+        let (lower_bound, solution) = solver.solve_node(&dive_node);
+        if lower_bound >= solver.get_incumbent_value() {
+            return None;
+        }
+
+        dive_node.lower_bound = lower_bound;
+        dive_node.solution = solution;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::branch_node::QuboBBNode;
+    use crate::branchbound::BBSolver;
+    use crate::branchbound_utils::check_integer_feasibility;
+    use crate::exact::brute_force;
+    use crate::heuristics::{dive, greedy_construct, relaxation_rounding};
+    use crate::incumbent::Incumbent;
+    use crate::local_search::local_search_1opt;
+    use crate::qubo::Qubo;
+    use crate::solver_options::SolverOptions;
+    use crate::tests::make_test_prng;
+    use crate::utils::rounded_vector;
+    use ndarray::Array1;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_relaxation_rounding() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(30, &mut prng, 0.2);
+        let x_relaxed = Array1::from_shape_fn(p.num_x(), |_| prng.gen_f64());
+
+        let x = relaxation_rounding(&p, &x_relaxed);
+
+        // the result is no worse than the rounded point, and is a local minimum
+        assert!(p.eval_usize(&x) <= p.eval_usize(&rounded_vector(&x_relaxed)) + 1e-10);
+        assert_eq!(local_search_1opt(&p, x.clone()), x);
+    }
+
+    #[test]
+    fn test_greedy_construct() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(30, &mut prng, 0.2);
+        let x = greedy_construct(&p);
+
+        // no variable can be set to one to decrease the objective, and the result is no worse than the zero vector
+        let obj = p.eval_usize(&x);
+        let p_sym = p.make_symmetric();
+        for i in (0..p.num_x()).filter(|&i| x[i] == 0) {
+            assert!(p_sym.flip_delta(&x, i) >= -1e-10);
+        }
+        assert!(obj <= p.eval_usize(&Array1::zeros(p.num_x())) + 1e-10);
+
+        // with only a linear term, exactly the negative coefficients are set
+        let c = Array1::from_vec(vec![1.0, -2.0, 0.0, -0.5]);
+        let p = Qubo::new_with_c(sprs::CsMat::zero((4, 4)), c);
+        assert_eq!(greedy_construct(&p), Array1::from_vec(vec![0, 1, 0, 1]));
+    }
+
+    #[test]
+    fn test_dive() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.5).convex_symmetric_form();
+        let (_, opt) = brute_force(&p);
+
+        let mut options = SolverOptions::new();
+        options.verbose = 0;
+        let solver = BBSolver::new(p.clone(), options).unwrap();
+
+        // with an incumbent that is worse than any solution, the dive always reaches a binary solution
+        let solver = BBSolver {
+            incumbent: Incumbent::new(Array1::zeros(12), f64::INFINITY),
+            ..solver
+        };
+
+        let mut node = QuboBBNode::root(12, HashMap::from([(0, 1), (5, 0)]));
+        let (lower_bound, solution) = solver.solve_node(&node);
+        node.lower_bound = lower_bound;
+        node.solution = solution;
+
+        let x = dive(&solver, &node).unwrap();
+        assert_eq!((x[0], x[5]), (1, 0));
+        assert!(p.eval_usize(&x) >= opt - 1e-10);
+        assert!(p.eval_usize(&x) >= lower_bound - 1e-6);
+
+        // with an incumbent that is better than any solution, the dive is abandoned after its first step
+        let solver = BBSolver {
+            incumbent: Incumbent::new(Array1::zeros(12), f64::NEG_INFINITY),
+            ..solver
+        };
+        assert!(!check_integer_feasibility(&node).0);
+        assert!(dive(&solver, &node).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+//! This module contains a thread-safe incumbent, the best solution found so far by a parallel search
+//!
+//! The objective of the incumbent is also kept in an atomic, so that the threads can prune against it without taking
+//! the lock, and the lock is only taken when a thread has a candidate that might be better.
+
+use ndarray::Array1;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The best solution found so far, shared between threads
+///
+/// Cloning an incumbent gives another handle to the same shared solution, so that each thread can own one.
+///
+/// Example:
+/// ``` rust
+/// use hercules::incumbent::Incumbent;
+/// use ndarray::Array1;
+///
+/// let incumbent = Incumbent::new(Array1::zeros(3), 0.0);
+///
+/// // only a strictly better solution replaces the incumbent
+/// assert!(incumbent.try_update(&Array1::ones(3), -1.0));
+/// assert!(!incumbent.try_update(&Array1::zeros(3), -1.0));
+/// assert_eq!(incumbent.value(), -1.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Incumbent {
+    /// The best solution and its objective
+    best: Arc<Mutex<(Array1<usize>, f64)>>,
+    /// The bits of the objective of the best solution, which can be read without the lock
+    value: Arc<AtomicU64>,
+}
+
+impl Incumbent {
+    /// Creates an incumbent from a starting solution and its objective
+    pub fn new(solution: Array1<usize>, objective: f64) -> Self {
+        Self {
+            best: Arc::new(Mutex::new((solution, objective))),
+            value: Arc::new(AtomicU64::new(objective.to_bits())),
+        }
+    }
+
+    /// The objective of the incumbent, this does not take the lock
+    pub fn value(&self) -> f64 {
+        f64::from_bits(self.value.load(Ordering::Acquire))
+    }
+
+    /// Returns a copy of the incumbent and its objective
+    ///
+    /// # Panics
+    ///
+    /// Will panic if another thread panicked while holding the lock.
+    pub fn get(&self) -> (Array1<usize>, f64) {
+        self.best.lock().unwrap().clone()
+    }
+
+    /// Replaces the incumbent if the objective is strictly better, and returns if it was replaced
+    ///
+    /// # Panics
+    ///
+    /// Will panic if another thread panicked while holding the lock.
+    pub fn try_update(&self, solution: &Array1<usize>, objective: f64) -> bool {
+        // most candidates are not better, so they are rejected before taking the lock
+        if objective >= self.value() {
+            return false;
+        }
+
+        let mut best = self.best.lock().unwrap();
+
+        // another thread could have found a better solution while we waited on the lock
+        if objective >= best.1 {
+            return false;
+        }
+
+        best.0.clone_from(solution);
+        best.1 = objective;
+        self.value.store(objective.to_bits(), Ordering::Release);
+        drop(best);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incumbent::Incumbent;
+    use ndarray::Array1;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_incumbent_strictly_better() {
+        let incumbent = Incumbent::new(Array1::zeros(2), 1.0);
+
+        assert!(!incumbent.try_update(&Array1::ones(2), 1.0));
+        assert!(!incumbent.try_update(&Array1::ones(2), 2.0));
+        assert!(incumbent.try_update(&Array1::ones(2), 0.5));
+
+        let (solution, objective) = incumbent.get();
+        assert_eq!(solution, Array1::<usize>::ones(2));
+        assert!((objective - 0.5).abs() < 1e-10);
+        assert!((incumbent.value() - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_incumbent_concurrent_updates() {
+        let incumbent = Incumbent::new(Array1::zeros(1), f64::INFINITY);
+
+        // many threads offer candidates in an arbitrary order, where the solution encodes the objective
+        let candidates = (0..10_000usize)
+            .map(|k| (k * 7_919) % 10_007)
+            .collect::<Vec<usize>>();
+        let num_updates = candidates
+            .par_iter()
+            .with_min_len(1)
+            .filter(|&&k| incumbent.try_update(&Array1::from_elem(1, k), k as f64))
+            .count();
+
+        // the true minimum survives, and it is stored along with its own solution
+        let minimum = *candidates.iter().min().unwrap();
+        let (solution, objective) = incumbent.get();
+        assert_eq!(solution[0], minimum);
+        assert!((objective - minimum as f64).abs() < 1e-10);
+        assert!((incumbent.value() - objective).abs() < 1e-10);
+        assert!(num_updates >= 1);
+
+        // a clone shares the incumbent
+        let handle = incumbent.clone();
+        assert!(handle.try_update(&Array1::from_elem(1, 0), -1.0));
+        assert!((incumbent.value() + 1.0).abs() < 1e-10);
+    }
+}
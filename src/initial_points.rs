@@ -6,6 +6,8 @@
 //! - Alpha points
 //! - Rho points
 //! - Random binary points
+//! - Biased binary points
+//! - One-hot binary points
 
 use crate::qubo::Qubo;
 use ndarray::Array1;
@@ -158,3 +160,145 @@ pub fn gen_binary_point_from_dist<T: Algorithm>(
 
     x
 }
+
+/// Generates a set of random binary points biased towards the alpha starting point.
+///
+/// Each variable has a probability of being 1 equal to its value in the alpha starting point, so the points are
+/// concentrated around the solution of the relaxation rather than spread uniformly.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+///
+/// let mut prng = PRNG {
+///    generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+/// let xs = initial_points::generate_biased_binary_points(&p, 10, &mut prng);
+/// ```
+pub fn generate_biased_binary_points<T: Algorithm>(
+    qubo: &Qubo,
+    num_points: usize,
+    prng: &mut PRNG<T>,
+) -> Vec<Array1<usize>> {
+    let probabilities = generate_alpha_starting_point(qubo);
+
+    (0..num_points)
+        .map(|_| probabilities.mapv(|p_i| usize::from(prng.gen_f64() < p_i)))
+        .collect()
+}
+
+/// Generates random binary points that satisfy one-hot constraints, where exactly one variable of each group is one
+/// and each free variable is one with probability 0.5.
+///
+/// The groups are assumed to be disjoint, and the active variable of each group is picked uniformly at random. The
+/// points have one entry for each variable up to the largest index in the groups and the free variables, and the
+/// variables that are in neither are zero.
+///
+/// Example:
+/// ``` rust
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+///
+/// let mut prng = PRNG {
+///    generator: JsfLarge::default(),
+/// };
+///
+/// // two groups of three variables, and two free variables
+/// let groups = vec![vec![0, 1, 2], vec![3, 4, 5]];
+/// let xs = initial_points::generate_one_hot_points(&groups, &[6, 7], 10, &mut prng);
+/// ```
+///
+/// # Panics
+///
+/// Will panic if one of the groups is empty, as then no variable can be picked.
+pub fn generate_one_hot_points<T: Algorithm>(
+    groups: &[Vec<usize>],
+    free: &[usize],
+    num_points: usize,
+    prng: &mut PRNG<T>,
+) -> Vec<Array1<usize>> {
+    assert!(
+        groups.iter().all(|group| !group.is_empty()),
+        "Every one-hot group must contain at least one variable"
+    );
+
+    let num_x = groups
+        .iter()
+        .flatten()
+        .chain(free.iter())
+        .max()
+        .map_or(0, |&i| i + 1);
+
+    (0..num_points)
+        .map(|_| {
+            let mut x = Array1::zeros(num_x);
+            for group in groups {
+                #[allow(clippy::cast_possible_truncation)]
+                // the size of a group is bounded by usize::MAX
+                let active = prng.gen_u64() as usize % group.len();
+                x[group[active]] = 1;
+            }
+            for &i in free {
+                x[i] = usize::from(prng.gen_bool());
+            }
+            x
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::initial_points::{generate_biased_binary_points, generate_one_hot_points};
+    use crate::qubo::Qubo;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+    use sprs::CsMat;
+
+    #[test]
+    fn test_generate_biased_binary_points() {
+        let mut prng = make_test_prng();
+
+        // alpha is clamped to 0, 0.5 and 1 for these linear terms
+        for (c_i, expected) in [(1.0, 0.0), (-1.0, 0.5), (-6.0, 1.0)] {
+            let p = Qubo::new_with_c(CsMat::<f64>::eye(3), Array1::from_elem(3, c_i));
+            assert!((p.alpha() - expected).abs() < 1e-12);
+
+            let xs = generate_biased_binary_points(&p, 1000, &mut prng);
+            assert_eq!(xs.len(), 1000);
+
+            let mean = xs.iter().map(|x| x.sum() as f64).sum::<f64>() / 3000.0;
+            assert!((mean - expected).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_generate_one_hot_points() {
+        let mut prng = make_test_prng();
+        let groups = vec![vec![0, 3, 5], vec![1, 2], vec![7]];
+        let free = [4, 8];
+
+        let xs = generate_one_hot_points(&groups, &free, 200, &mut prng);
+        assert_eq!(xs.len(), 200);
+
+        for x in &xs {
+            assert_eq!(x.len(), 9);
+
+            // exactly one variable of each group is active, and variables in no group are zero
+            for group in &groups {
+                assert_eq!(group.iter().map(|&i| x[i]).sum::<usize>(), 1);
+            }
+            assert_eq!(x[6], 0);
+        }
+
+        // every variable of a group is picked, and the free variables take both values
+        for i in [0, 1, 2, 3, 4, 5, 8] {
+            assert!(xs.iter().any(|x| x[i] == 1));
+        }
+        for i in free {
+            assert!(xs.iter().any(|x| x[i] == 0));
+        }
+    }
+}
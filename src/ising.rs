@@ -0,0 +1,97 @@
+//! This module contains the Ising model struct, which is the spin form of a QUBO
+//!
+//! The Ising model uses spins $s_i \in \{-1, 1\}$ instead of binary variables, and is related to the QUBO form by the
+//! substitution $x = (1 + s)/2$. The coupling matrix is stored in CSR order, and it is not assumed to be symmetrical.
+
+use ndarray::Array1;
+use sprs::CsMat;
+
+/// The Ising model struct, which contains the coupling matrix, the linear fields, and the constant offset. With the
+/// following form:
+///
+/// $$ \min_{s\in \{-1,1\}^n} s^T J s + h^Ts + \text{offset} $$
+#[derive(Clone)]
+pub struct IsingModel {
+    /// The linear fields of the Ising model
+    pub h: Array1<f64>,
+    /// The coupling matrix of the Ising model
+    pub j: CsMat<f64>,
+    /// The constant offset of the Ising model
+    pub offset: f64,
+}
+
+impl IsingModel {
+    /// Generate a new Ising model from the linear fields, coupling matrix, and offset
+    ///
+    /// Example to create an Ising model with antiferromagnetic coupling between two spins:
+    /// ```rust
+    /// use hercules::ising::IsingModel;
+    /// use ndarray::Array1;
+    /// use sprs::TriMat;
+    ///
+    /// let mut j = TriMat::new((2, 2));
+    /// j.add_triplet(0, 1, 1.0);
+    /// let h = Array1::zeros(2);
+    /// let ising = IsingModel::new(h, j.to_csr(), 0.0);
+    /// ```
+    pub const fn new(h: Array1<f64>, j: CsMat<f64>, offset: f64) -> Self {
+        Self { h, j, offset }
+    }
+
+    /// Given a spin configuration, s, calculate the energy of the Ising model
+    ///
+    /// Example of calculating the energy of an Ising model:
+    /// ```rust
+    /// use hercules::ising::IsingModel;
+    /// use ndarray::Array1;
+    /// use sprs::TriMat;
+    ///
+    /// let mut j = TriMat::new((2, 2));
+    /// j.add_triplet(0, 1, 1.0);
+    /// let h = Array1::zeros(2);
+    /// let ising = IsingModel::new(h, j.to_csr(), 0.0);
+    ///
+    /// let s = Array1::from_vec(vec![1.0, -1.0]);
+    /// let energy = ising.energy(&s);
+    /// ```
+    pub fn energy(&self, s: &Array1<f64>) -> f64 {
+        let temp = &self.j * s;
+        s.dot(&temp) + self.h.dot(s) + self.offset
+    }
+
+    /// Return the number of spins in the Ising model
+    pub fn num_x(&self) -> usize {
+        self.j.cols()
+    }
+
+    /// Calculates the constant term of the substitution $s = 2x - 1$, which is the offset of the QUBO from
+    /// `Qubo::from_ising`, such that the energy at s is the non-constant part of the QUBO objective at $x = (1 + s)/2$
+    /// plus this value.
+    pub fn qubo_offset(&self) -> f64 {
+        // substituting s = 2x - 1, every coupling contributes a constant, and the diagonal couplings are only constant
+        let coupling_offset: f64 = self.j.data().iter().sum();
+
+        coupling_offset - self.h.sum() + self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ising::IsingModel;
+    use ndarray::Array1;
+    use sprs::TriMat;
+
+    #[test]
+    fn test_ising_energy() {
+        let mut j = TriMat::new((2, 2));
+        j.add_triplet(0, 1, 1.0);
+        let h = Array1::from_vec(vec![0.5, 0.0]);
+        let ising = IsingModel::new(h, j.to_csr(), 2.0);
+
+        let s = Array1::from_vec(vec![1.0, -1.0]);
+        assert!((ising.energy(&s) - 1.5).abs() < 1e-10);
+
+        let s = Array1::from_vec(vec![-1.0, -1.0]);
+        assert!((ising.energy(&s) - 2.5).abs() < 1e-10);
+    }
+}
@@ -19,15 +19,17 @@ pub fn solve_kopt(
     // compute the persistent variables
     persistent = compute_iterative_persistence(qubo, &persistent, 100);
 
-    // create a new QUBO to store the reduced QUBO
-    let reduced_qubo = qubo.clone();
+    // create a new QUBO to store the reduced QUBO, in the convex symmetric form required by the solver
+    let reduced_qubo = qubo.convex_symmetric_form();
 
     let mut options = SolverOptions::new();
 
     options.fixed_variables = persistent;
 
-    // use branch and bound to solve the problem
-    let mut solver = BBSolver::new(reduced_qubo, options);
+    // use branch and bound to solve the problem, the QUBO is convex by construction so that is not checked again, and
+    // there is no initial solution to validate, so the solver can only fail to be created if the QUBO is not symmetric
+    let mut solver = BBSolver::new_assume_convex(reduced_qubo, options)
+        .expect("the convex symmetric form of a QUBO is symmetric");
 
     // warm start the solver, if we are provided a guess
 
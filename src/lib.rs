@@ -21,26 +21,41 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 use pyo3::prelude::*;
 
-mod branch_node;
-mod branch_stratagy;
+pub mod alns;
+pub mod bounds;
+pub mod branch_node;
+pub mod branch_stratagy;
 mod branch_subproblem;
-mod branchbound;
+pub mod branchbound;
 pub mod branchbound_utils;
 mod branchboundlogger;
 mod constraint;
 pub mod constraint_reduction;
+pub mod decompose;
 pub mod early_termination;
+pub mod exact;
+pub mod generators;
+pub mod genetic;
+pub mod heuristics;
 mod heuristic_stratagy;
+pub mod incumbent;
+pub mod ising;
 pub mod initial_points;
 mod kopt;
 pub mod local_search;
 pub mod local_search_utils;
-mod lower_bound;
+pub mod lower_bound;
+mod node_selection;
 pub mod persistence;
-mod preprocess;
+pub mod polish;
+pub mod preprocess;
 pub mod python_interopt;
 pub mod qubo;
-mod solver_options;
+pub mod relaxation;
+pub mod repair;
+pub mod simulated_annealing;
+pub mod tabu;
+pub mod solver_options;
 pub mod utils;
 pub mod variable_reduction;
 
@@ -3,19 +3,29 @@
 //! This module contains all of the implemented local search algorithms, which are:
 //! - One step local search
 //! - Simple local search
+//! - Incremental 1-opt local search
+//! - 1-opt local search with plateau moves
+//! - Incremental 2-opt local search
+//! - Path relinking
 //! - Simple gain criteria search
 //! - Simple mixed search
 //! - Multi simple local search
 //! - Multi simple gain criteria search
+//! - Multi-start 1-opt local search
+//! - Adaptive multi-start 1-opt local search
 //! - Simple Particle Swarm Search
 
-use crate::initial_points::generate_random_binary_point;
+use crate::initial_points::{generate_random_binary_point, generate_random_binary_points};
 use crate::local_search_utils;
 use crate::qubo::Qubo;
-use crate::utils::get_best_point;
+use crate::utils::{get_best_point, solution_diff};
 use ndarray::Array1;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use smolprng::{Algorithm, PRNG};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use smolprng::{Algorithm, JsfLarge, PRNG};
+use std::collections::HashSet;
+
+/// The number of flips per variable that the plateau search can take in total, so that it always terminates
+const PLATEAU_SEARCH_FLIPS_PER_VARIABLE: usize = 100;
 
 /// Given a QUBO and an integral initial point, run simple local search until the point converges or the step limit is hit.
 ///
@@ -86,6 +96,548 @@ pub fn multi_simple_local_search(qubo: &Qubo, xs: &Vec<Array1<usize>>) -> Vec<Ar
         .collect()
 }
 
+/// Given a QUBO and an integral initial point, run a steepest descent 1-opt local search until no single bit flip
+/// improves the objective, and return the local minimum.
+///
+/// The change in objective of flipping each variable is computed once, and then updated incrementally after each
+/// flip. With the symmetric form of the Hessian, S, and the direction of the flip $d_i = 1 - 2x_i$, the change from
+/// flipping $x_i$ is
+///
+/// $$\Delta_i = d_i (Sx + c)_i + 0.5 S_{ii}$$
+///
+/// So after flipping $x_k$, $\Delta_k$ changes sign, and for every other $j$ with $S_{jk} \neq 0$
+///
+/// $$\Delta_j \leftarrow \Delta_j + d_j d_k S_{jk}$$
+///
+/// Where $d_k$ is the direction of the flip that was just taken. As such, each step only costs the nonzeros in the
+/// flipped column, and the search for the best flip.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // generate a random point inside with x in {0, 1}^10
+/// let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+///
+/// // perform a 1-opt local search starting at x_0
+/// let x_sol = local_search::local_search_1opt(&p, x_0);
+/// ```
+pub fn local_search_1opt(qubo: &Qubo, x_0: Array1<usize>) -> Array1<usize> {
+    let q_sym = qubo.make_symmetric();
+    let q_diag = q_sym.q.diag().to_dense();
+    let grad = q_sym.eval_grad_usize(&x_0);
+
+    let mut x = x_0;
+    let mut direction = x.mapv(|x_i| 1.0 - 2.0 * x_i as f64);
+    let mut deltas = &direction * &grad + 0.5 * &q_diag;
+
+    // find the flip that gives the largest decrease in the objective, until there are no improving flips
+    while let Some((k, delta_k)) = deltas
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, delta)| *delta < 0.0)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    {
+        flip_and_update_deltas(&q_sym, k, &mut x, &mut direction, &mut deltas);
+        debug_assert!((deltas[k] + delta_k).abs() < 1E-8);
+    }
+
+    x
+}
+
+/// Flips $x_k$, and updates the single flip deltas and directions that depend on $x_k$, where `q_sym` is the symmetric
+/// form of the QUBO
+pub(crate) fn flip_and_update_deltas(
+    q_sym: &Qubo,
+    k: usize,
+    x: &mut Array1<usize>,
+    direction: &mut Array1<f64>,
+    deltas: &mut Array1<f64>,
+) {
+    let d_k = direction[k];
+    if let Some(col) = q_sym.q.outer_view(k) {
+        for (j, &value) in col.iter() {
+            if j != k {
+                deltas[j] += direction[j] * d_k * value;
+            }
+        }
+    }
+
+    x[k] = 1 - x[k];
+    direction[k] = -d_k;
+    deltas[k] = -deltas[k];
+}
+
+/// Runs 1-opt local search, but instead of stopping at the first local minimum, takes up to `max_plateau_steps` non
+/// improving flips to walk across plateaus, and returns the best solution seen.
+///
+/// When there is no improving flip, this takes the flip with the smallest increase in the objective, which is a
+/// sideways move on a plateau, picking at random between ties and never undoing the last flip. The plateau steps are
+/// counted from the last time the best solution improved, and the total number of flips, of either kind, is capped at
+/// `PLATEAU_SEARCH_FLIPS_PER_VARIABLE` per variable. With `max_plateau_steps` set to zero, this is the same as
+/// `local_search_1opt`.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // generate a random point inside with x in {0, 1}^10
+/// let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+///
+/// // perform a 1-opt local search starting at x_0, taking up to 20 plateau steps
+/// let x_sol = local_search::local_search_with_restarts(&p, x_0, 20, &mut prng);
+/// ```
+pub fn local_search_with_restarts<T: Algorithm>(
+    qubo: &Qubo,
+    x_0: Array1<usize>,
+    max_plateau_steps: usize,
+    prng: &mut PRNG<T>,
+) -> Array1<usize> {
+    let num_x = qubo.num_x();
+    let q_sym = qubo.make_symmetric();
+    let q_diag = q_sym.q.diag().to_dense();
+    let grad = q_sym.eval_grad_usize(&x_0);
+
+    let mut obj = qubo.eval_usize(&x_0);
+    let mut x = x_0;
+    let mut direction = x.mapv(|x_i| 1.0 - 2.0 * x_i as f64);
+    let mut deltas = &direction * &grad + 0.5 * &q_diag;
+
+    let mut best_x = x.clone();
+    let mut best_obj = obj;
+    let mut plateau_steps = 0;
+    let mut last_flip = None;
+
+    for _ in 0..PLATEAU_SEARCH_FLIPS_PER_VARIABLE * num_x {
+        // take the best improving flip if there is one, otherwise a plateau step
+        let improving_flip = deltas
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, delta)| *delta < 0.0)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        let k = match improving_flip {
+            Some((k, _)) => k,
+            None if plateau_steps < max_plateau_steps => {
+                plateau_steps += 1;
+                match plateau_flip(&deltas, last_flip, prng) {
+                    Some(k) => k,
+                    None => break,
+                }
+            }
+            None => break,
+        };
+
+        obj += deltas[k];
+        flip_and_update_deltas(&q_sym, k, &mut x, &mut direction, &mut deltas);
+        last_flip = Some(k);
+
+        if obj < best_obj - 1E-10 {
+            best_obj = obj;
+            best_x.assign(&x);
+            plateau_steps = 0;
+        }
+    }
+
+    best_x
+}
+
+/// Picks the flip with the smallest change in the objective other than the last flip, at random between ties
+fn plateau_flip<T: Algorithm>(
+    deltas: &Array1<f64>,
+    last_flip: Option<usize>,
+    prng: &mut PRNG<T>,
+) -> Option<usize> {
+    let candidates = (0..deltas.len())
+        .filter(|&i| Some(i) != last_flip)
+        .collect::<Vec<usize>>();
+
+    let smallest_delta = candidates
+        .iter()
+        .map(|&i| deltas[i])
+        .fold(f64::INFINITY, f64::min);
+
+    let ties = candidates
+        .into_iter()
+        .filter(|&i| deltas[i] <= smallest_delta + 1E-10)
+        .collect::<Vec<usize>>();
+
+    if ties.is_empty() {
+        return None;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    // the number of variables is bounded by usize::MAX
+    let pick = prng.gen_u64() as usize % ties.len();
+    Some(ties[pick])
+}
+
+/// The change in the objective from flipping both $x_i$ and $x_j$, given the single flip deltas and directions. With
+/// the symmetric form S, the cross term between the two flips is counted by
+///
+/// $$\Delta_{ij} = \Delta_i + \Delta_j + d_i d_j S_{ij}$$
+fn pair_flip_delta(
+    deltas: &Array1<f64>,
+    direction: &Array1<f64>,
+    s_ij: f64,
+    i: usize,
+    j: usize,
+) -> f64 {
+    deltas[i] + deltas[j] + direction[i] * direction[j] * s_ij
+}
+
+/// Given a QUBO and a binary initial point, run a 2-opt local search, where a step either flips one variable or
+/// flips a pair of variables at the same time, until no such move improves the objective.
+///
+/// Improving single flips are taken first, in best improvement order. Once $x$ is a 1-opt local minimum, every
+/// $\Delta_i \geq 0$, so a pair $(i, j)$ can only improve the objective if $S_{ij} \neq 0$. So only the nonzeros of
+/// the symmetric form are scanned for the best pair flip, rather than all pairs.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // generate a random point inside with x in {0, 1}^10
+/// let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+///
+/// // perform a 2-opt local search starting at x_0
+/// let x_sol = local_search::local_search_2opt(&p, x_0);
+/// ```
+pub fn local_search_2opt(qubo: &Qubo, x_0: Array1<usize>) -> Array1<usize> {
+    let q_sym = qubo.make_symmetric();
+    let q_diag = q_sym.q.diag().to_dense();
+    let grad = q_sym.eval_grad_usize(&x_0);
+
+    let mut x = x_0;
+    let mut direction = x.mapv(|x_i| 1.0 - 2.0 * x_i as f64);
+    let mut deltas = &direction * &grad + 0.5 * &q_diag;
+
+    loop {
+        // take the best improving single flip if there is one
+        let best_single = deltas
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, delta)| *delta < 0.0)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        if let Some((k, _)) = best_single {
+            flip_and_update_deltas(&q_sym, k, &mut x, &mut direction, &mut deltas);
+            continue;
+        }
+
+        // otherwise, find the best improving pair flip among the coupled pairs
+        let mut best_pair = None;
+        let mut best_delta = 0.0;
+        for (&s_ij, (i, j)) in &q_sym.q {
+            if i < j {
+                let delta = pair_flip_delta(&deltas, &direction, s_ij, i, j);
+                if delta < best_delta {
+                    best_delta = delta;
+                    best_pair = Some((i, j));
+                }
+            }
+        }
+
+        let Some((i, j)) = best_pair else {
+            break;
+        };
+
+        flip_and_update_deltas(&q_sym, i, &mut x, &mut direction, &mut deltas);
+        flip_and_update_deltas(&q_sym, j, &mut x, &mut direction, &mut deltas);
+    }
+
+    x
+}
+
+/// Walks from solution a to solution b, flipping one of the bits where they differ at each step, and returns the best
+/// solution encountered along the path, including both ends.
+///
+/// At each step, the remaining differing bit with the smallest change in the objective is flipped, even if every such
+/// flip makes the objective worse, so the walk always reaches b. The single flip deltas are updated incrementally, so
+/// each step only costs a pass over the differing bits and one column of the Hessian.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // generate two random points with x in {0, 1}^10
+/// let x_a = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+/// let x_b = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+///
+/// // find the best point on the path between them
+/// let x_sol = local_search::path_relink(&p, &x_a, &x_b);
+/// ```
+pub fn path_relink(qubo: &Qubo, a: &Array1<usize>, b: &Array1<usize>) -> Array1<usize> {
+    let q_sym = qubo.make_symmetric();
+    let q_diag = q_sym.q.diag().to_dense();
+    let grad = q_sym.eval_grad_usize(a);
+
+    let mut x = a.clone();
+    let mut direction = x.mapv(|x_i| 1.0 - 2.0 * x_i as f64);
+    let mut deltas = &direction * &grad + 0.5 * &q_diag;
+
+    let mut remaining = solution_diff(a, b);
+
+    let mut obj = qubo.eval_usize(a);
+    let mut best_x = x.clone();
+    let mut best_obj = obj;
+
+    while !remaining.is_empty() {
+        // pick the differing bit that is cheapest to flip
+        let mut best_k = 0;
+        for k in 1..remaining.len() {
+            if deltas[remaining[k]] < deltas[remaining[best_k]] {
+                best_k = k;
+            }
+        }
+        let i = remaining.swap_remove(best_k);
+
+        obj += deltas[i];
+        flip_and_update_deltas(&q_sym, i, &mut x, &mut direction, &mut deltas);
+
+        if obj < best_obj {
+            best_obj = obj;
+            best_x.assign(&x);
+        }
+    }
+
+    best_x
+}
+
+/// The result of a multi-start local search
+#[derive(Clone, Debug)]
+pub struct MultiStartResult {
+    /// The best local minimum found
+    pub solution: Array1<usize>,
+    /// The objective of the best local minimum
+    pub objective: f64,
+    /// The number of starting points that were searched
+    pub num_starts: usize,
+    /// The number of distinct local minima found, a rough measure of how rugged the landscape is
+    pub num_distinct_optima: usize,
+}
+
+/// Generates `num_starts` random binary starting points, runs a 1-opt local search on each, and returns the best
+/// local minimum found and its objective. At least one start is always performed.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // perform 1-opt local search from 20 random starting points
+/// let (x_sol, obj) = local_search::multi_start(&p, 20, &mut prng);
+/// ```
+pub fn multi_start<T: Algorithm>(
+    qubo: &Qubo,
+    num_starts: usize,
+    prng: &mut PRNG<T>,
+) -> (Array1<usize>, f64) {
+    let result = multi_start_summary(qubo, num_starts, prng);
+    (result.solution, result.objective)
+}
+
+/// Same as `multi_start`, but also reports how many distinct local minima were found.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // perform 1-opt local search from 20 random starting points
+/// let result = local_search::multi_start_summary(&p, 20, &mut prng);
+/// println!("found {} distinct local minima", result.num_distinct_optima);
+/// ```
+pub fn multi_start_summary<T: Algorithm>(
+    qubo: &Qubo,
+    num_starts: usize,
+    prng: &mut PRNG<T>,
+) -> MultiStartResult {
+    let num_starts = num_starts.max(1);
+    let starts = generate_random_binary_points(qubo.num_x(), num_starts, prng);
+
+    // run the local searches in parallel, as each start is independent
+    let optima: Vec<_> = starts
+        .into_par_iter()
+        .map(|x| local_search_1opt(qubo, x))
+        .collect();
+
+    let num_distinct_optima = optima.iter().collect::<HashSet<_>>().len();
+    let solution = get_best_point(qubo, &optima);
+    let objective = qubo.eval_usize(&solution);
+
+    MultiStartResult {
+        solution,
+        objective,
+        num_starts,
+        num_distinct_optima,
+    }
+}
+
+/// Runs `num_starts` 1-opt local searches from random starting points in parallel, and returns the best local minimum
+/// found and its objective. At least one start is always performed.
+///
+/// Each start draws its starting point from its own generator, whose seed is the start index-th draw of a generator
+/// seeded with `seed`. As no generator is shared between the threads, the result only depends on the seed and not on
+/// the thread scheduling.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // perform 1-opt local search from 20 random starting points, with a seed of 42
+/// let (x_sol, obj) = local_search::multi_start_parallel(&p, 20, 42);
+/// ```
+pub fn multi_start_parallel(qubo: &Qubo, num_starts: usize, seed: usize) -> (Array1<usize>, f64) {
+    let num_starts = num_starts.max(1);
+
+    // derive the seeds of the starts up front, so that each start owns its generator
+    let mut seed_prng = PRNG {
+        generator: JsfLarge::from(seed as u64),
+    };
+    let start_seeds = (0..num_starts)
+        .map(|_| seed_prng.gen_u64())
+        .collect::<Vec<u64>>();
+
+    // the optima are collected in the order of the starts, so ties are broken the same way on every run
+    let optima: Vec<_> = start_seeds
+        .into_par_iter()
+        .map(|start_seed| {
+            let mut prng = PRNG {
+                generator: JsfLarge::from(start_seed),
+            };
+            let x_0 = generate_random_binary_point(qubo.num_x(), &mut prng, 0.5);
+            local_search_1opt(qubo, x_0)
+        })
+        .collect();
+
+    let solution = get_best_point(qubo, &optima);
+    let objective = qubo.eval_usize(&solution);
+    (solution, objective)
+}
+
+/// Runs 1-opt local searches from random starting points until `stall_limit` starts in a row do not improve.
+///
+/// Returns the best local minimum along with the number of starts that were performed, so the effort can be gauged.
+///
+/// A start that lands in a local minimum that was already found can never improve, so the search also stops once the
+/// local minima stop being diverse. With a stall limit of zero a single start is performed.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::local_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // stop after 10 starts in a row without improvement
+/// let result = local_search::multi_start_adaptive(&p, &mut prng, 10);
+/// println!("performed {} starts", result.num_starts);
+/// ```
+pub fn multi_start_adaptive<T: Algorithm>(
+    qubo: &Qubo,
+    prng: &mut PRNG<T>,
+    stall_limit: usize,
+) -> MultiStartResult {
+    let x_0 = generate_random_binary_point(qubo.num_x(), prng, 0.5);
+    let mut solution = local_search_1opt(qubo, x_0);
+    let mut objective = qubo.eval_usize(&solution);
+
+    let mut optima = HashSet::from([solution.clone()]);
+    let mut num_starts = 1;
+    let mut num_stalled = 0;
+
+    while num_stalled < stall_limit {
+        let x_0 = generate_random_binary_point(qubo.num_x(), prng, 0.5);
+        let x = local_search_1opt(qubo, x_0);
+        let obj = qubo.eval_usize(&x);
+        num_starts += 1;
+
+        if obj < objective - 1e-10 {
+            solution.clone_from(&x);
+            objective = obj;
+            num_stalled = 0;
+        } else {
+            num_stalled += 1;
+        }
+
+        optima.insert(x);
+    }
+
+    MultiStartResult {
+        solution,
+        objective,
+        num_starts,
+        num_distinct_optima: optima.len(),
+    }
+}
+
 /// Given a QUBO and a fractional or integral initial point, run a gain search until the point converges or the step limit is hit.
 ///
 /// Example:
@@ -355,6 +907,205 @@ mod tests {
     use ndarray::Array1;
     use sprs::CsMat;
 
+    #[test]
+    fn test_pair_flip_delta() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(8, &mut prng, 0.6);
+        let q_sym = p.make_symmetric();
+        let q_diag = q_sym.q.diag().to_dense();
+
+        let xs = initial_points::generate_random_binary_points(p.num_x(), 10, &mut prng);
+        for x in &xs {
+            let direction = x.mapv(|x_i| 1.0 - 2.0 * x_i as f64);
+            let deltas = &direction * &q_sym.eval_grad_usize(x) + 0.5 * &q_diag;
+            let obj = p.eval_usize(x);
+
+            // compare against recomputing the objective for every pair
+            for i in 0..p.num_x() {
+                for j in (i + 1)..p.num_x() {
+                    let mut x_flip = x.clone();
+                    x_flip[i] = 1 - x_flip[i];
+                    x_flip[j] = 1 - x_flip[j];
+
+                    let s_ij = q_sym.q.get(i, j).copied().unwrap_or(0.0);
+                    let delta = pair_flip_delta(&deltas, &direction, s_ij, i, j);
+                    assert!((delta - (p.eval_usize(&x_flip) - obj)).abs() < 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_local_search_2opt() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+        let xs = initial_points::generate_random_binary_points(p.num_x(), 10, &mut prng);
+
+        for x_0 in &xs {
+            let x_2opt = local_search_2opt(&p, x_0.clone());
+            let obj = p.eval_usize(&x_2opt);
+            assert!(obj <= p.eval_usize(x_0));
+
+            // no single or pair flip should improve the local minimum
+            for i in 0..p.num_x() {
+                let mut x_i = x_2opt.clone();
+                x_i[i] = 1 - x_i[i];
+                assert!(p.eval_usize(&x_i) >= obj - 1e-10);
+
+                for j in (i + 1)..p.num_x() {
+                    let mut x_ij = x_i.clone();
+                    x_ij[j] = 1 - x_ij[j];
+                    assert!(p.eval_usize(&x_ij) >= obj - 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_local_search_1opt() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+        let xs = initial_points::generate_random_binary_points(p.num_x(), 10, &mut prng);
+
+        for x_0 in &xs {
+            let x_1opt = local_search_1opt(&p, x_0.clone());
+
+            // no single flip should improve the local minimum
+            let (_, objs) = local_search_utils::one_flip_objective(&p, &x_1opt);
+            assert!(objs.iter().all(|&delta| delta >= -1e-10));
+            assert!(p.eval_usize(&x_1opt) <= p.eval_usize(x_0));
+        }
+    }
+
+    #[test]
+    fn test_local_search_with_restarts() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+        let xs = initial_points::generate_random_binary_points(p.num_x(), 10, &mut prng);
+
+        for x_0 in &xs {
+            let x_1opt = local_search_1opt(&p, x_0.clone());
+
+            // without plateau steps this is plain 1-opt
+            let x_0_steps = local_search_with_restarts(&p, x_0.clone(), 0, &mut prng);
+            assert_eq!(x_0_steps, x_1opt);
+
+            // the first descent is the same as 1-opt, so the plateau steps can only improve on it
+            let x_plateau = local_search_with_restarts(&p, x_0.clone(), 50, &mut prng);
+            let (_, objs) = local_search_utils::one_flip_objective(&p, &x_plateau);
+            assert!(objs.iter().all(|&delta| delta >= -1e-10));
+            assert!(p.eval_usize(&x_plateau) <= p.eval_usize(&x_1opt) + 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_local_search_with_restarts_plateau() {
+        let mut prng = make_test_prng();
+
+        // the objective is -2 x_0 x_1, so every single flip from zero is sideways, and 1-opt stops there
+        let q = CsMat::csr_from_dense(ndarray::array![[0.0, -2.0], [-2.0, 0.0]].view(), 0.0);
+        let p = Qubo::new(q);
+        let x_0 = Array1::zeros(2);
+        assert_eq!(local_search_1opt(&p, x_0.clone()), x_0);
+
+        let x_plateau = local_search_with_restarts(&p, x_0, 5, &mut prng);
+        assert_eq!(x_plateau, Array1::<usize>::ones(2));
+
+        // a flat QUBO is all plateau, and the search still terminates
+        let flat = Qubo::new(CsMat::zero((5, 5)));
+        let x_flat = local_search_with_restarts(&flat, Array1::zeros(5), usize::MAX, &mut prng);
+        assert_eq!(x_flat, Array1::<usize>::zeros(5));
+    }
+
+    #[test]
+    fn test_multi_start() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+
+        let result = multi_start_summary(&p, 20, &mut prng);
+        assert_eq!(result.num_starts, 20);
+        assert!(result.num_distinct_optima >= 1 && result.num_distinct_optima <= 20);
+        assert!((p.eval_usize(&result.solution) - result.objective).abs() < 1e-10);
+
+        // with the same seed, the result should not depend on the thread scheduling
+        let (x, obj) = multi_start(&p, 20, &mut make_test_prng());
+        assert_eq!(x, result.solution);
+        assert!((obj - result.objective).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_multi_start_adaptive() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+
+        let result = multi_start_adaptive(&p, &mut prng, 10);
+        assert!(result.num_starts > 10);
+        assert!(result.num_distinct_optima <= result.num_starts);
+        assert!((p.eval_usize(&result.solution) - result.objective).abs() < 1e-10);
+        assert_eq!(
+            result.solution,
+            local_search_1opt(&p, result.solution.clone())
+        );
+
+        // with no stall allowed, only the first start is performed
+        let result = multi_start_adaptive(&p, &mut prng, 0);
+        assert_eq!(result.num_starts, 1);
+        assert_eq!(result.num_distinct_optima, 1);
+
+        // a QUBO with a single local minimum stalls right away
+        let p = Qubo::new_with_c(CsMat::eye(5), Array1::from_elem(5, -1.0));
+        let result = multi_start_adaptive(&p, &mut prng, 3);
+        assert_eq!(result.num_starts, 4);
+        assert_eq!(result.num_distinct_optima, 1);
+    }
+
+    #[test]
+    fn test_path_relink() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+        let xs = initial_points::generate_random_binary_points(p.num_x(), 10, &mut prng);
+
+        for pair in xs.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let x = path_relink(&p, a, b);
+
+            // the result is on the path, so it agrees with both ends wherever they agree
+            for i in 0..p.num_x() {
+                if a[i] == b[i] {
+                    assert_eq!(x[i], a[i]);
+                }
+            }
+
+            // both ends are on the path, so the result is at least as good as either
+            let obj = p.eval_usize(&x);
+            assert!(obj <= p.eval_usize(a) + 1e-10);
+            assert!(obj <= p.eval_usize(b) + 1e-10);
+        }
+
+        // relinking a point to itself does nothing
+        assert_eq!(path_relink(&p, &xs[0], &xs[0]), xs[0]);
+    }
+
+    #[test]
+    fn test_multi_start_parallel() {
+        let p = make_solver_qubo();
+
+        let (x, obj) = multi_start_parallel(&p, 20, 42);
+        assert!((p.eval_usize(&x) - obj).abs() < 1e-10);
+        assert_eq!(x, local_search_1opt(&p, x.clone()));
+
+        // the same seed gives the same result, no matter how the starts are scheduled
+        for _ in 0..5 {
+            let (x_again, obj_again) = multi_start_parallel(&p, 20, 42);
+            assert_eq!(x_again, x);
+            assert!((obj_again - obj).abs() < 1e-10);
+        }
+
+        // more starts can only find an equal or better minimum, as the first starts are shared
+        let (_, obj_more) = multi_start_parallel(&p, 40, 42);
+        assert!(obj_more <= obj + 1e-10);
+    }
+
     #[test]
     fn test_opt_criteria() {
         let p = make_solver_qubo();
@@ -3,8 +3,11 @@
 //! These include:
 //! - Lower Bound Function Proposed in pardalos1990
 //! - Lower Bound Function Proposed in Li2012 (Initial)
+//! - Roof Dual Lower Bound, as computed in boros2008
+//! - McCormick LP Relaxation Lower Bound
 
 use crate::qubo::Qubo;
+use crate::subproblemsolvers::clarabel_lp::solve_mccormick_lp;
 use ndarray::Array1;
 use std::collections::HashMap;
 
@@ -48,7 +51,7 @@ pub fn pardalos_rodgers_lower_bound(qubo: &Qubo, fixed_variables: &HashMap<usize
         }
     }
 
-    lower_bound
+    lower_bound + qubo.offset
 }
 
 /// Calculates an initial lower bound for a qubo, based on equation 15 of li2012
@@ -103,14 +106,281 @@ pub fn li_lower_bound(qubo: &Qubo, fixed_variables: &HashMap<usize, usize>) -> f
         }
     }
 
-    lower_bound
+    lower_bound + qubo.offset
+}
+
+/// Calculates a lower bound for a qubo from the McCormick linearization of the products over $[0, 1]^2$, with the fixed
+/// variables held at their values
+///
+/// Costs a linear program with a variable for every free variable and every product of free variables. It is often a
+/// weaker bound than the QP relaxation, but it does not need the QUBO to be convex, and it is always at least as tight
+/// as the bound of pardalos1990.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::lower_bound;
+/// use smolprng::{PRNG, JsfLarge};
+/// use std::collections::HashMap;
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+///
+/// let lower_bound = lower_bound::lp_bound(&p, &HashMap::new());
+/// ```
+pub fn lp_bound(qubo: &Qubo, fixed: &HashMap<usize, f64>) -> f64 {
+    solve_mccormick_lp(qubo, fixed).0
+}
+
+/// Calculates the roof dual lower bound of a qubo, and the variables that it proves are persistent, based on boros2008
+///
+/// The QUBO is written as a posiform, a constant plus a positive combination of the literals $x_i$ and
+/// $\bar{x}_i = 1 - x_i$, and the roof dual bound is the constant plus the maximum flow in the implication network of
+/// the posiform. Any literal that can still be reached from the source after the maximum flow is one in every optimal
+/// solution, so the variables are strongly persistent.
+///
+/// Costs a maximum flow on a network with 2n + 2 nodes and roughly twice as many arcs as nonzeros in the QUBO.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::lower_bound;
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+///
+/// let (lower_bound, persistent) = lower_bound::roof_dual(&p);
+/// ```
+pub fn roof_dual(qubo: &Qubo) -> (f64, HashMap<usize, usize>) {
+    let num_x = qubo.num_x();
+    let mut network = ImplicationNetwork::new(num_x);
+
+    // the linear terms, where the diagonal of the hessian is absorbed as x_i^2 = x_i
+    let mut linear = qubo.c.clone();
+    for i in 0..num_x {
+        linear[i] += 0.5 * qubo.q.get(i, i).unwrap_or(&0.0);
+    }
+
+    // with the symmetric form, the coefficient of x_i x_j for i < j is the (i, j) element
+    let q_sym = qubo.make_symmetric();
+    for (&value, (i, j)) in &q_sym.q {
+        if i >= j {
+            continue;
+        }
+
+        if value > 0.0 {
+            network.add_term(
+                ImplicationNetwork::pos(i),
+                ImplicationNetwork::pos(j),
+                value,
+            );
+        } else if value < 0.0 {
+            // b x_i x_j = b x_i + |b| x_i (1 - x_j)
+            linear[i] += value;
+            network.add_term(ImplicationNetwork::pos(i), network.neg(j), -value);
+        }
+    }
+
+    // a x_i = a + |a| (1 - x_i) if a is negative, linear terms are quadratic terms with the source literal
+    let mut constant = qubo.offset;
+    for i in 0..num_x {
+        if linear[i] > 0.0 {
+            network.add_term(ImplicationNetwork::pos(i), network.source(), linear[i]);
+        } else if linear[i] < 0.0 {
+            constant += linear[i];
+            network.add_term(network.neg(i), network.source(), -linear[i]);
+        }
+    }
+
+    let max_flow = network.max_flow();
+
+    // literals that are still reachable from the source are one in every optimal solution
+    let reachable = network.reachable_from_source();
+    let mut persistent = HashMap::new();
+    for i in 0..num_x {
+        if reachable[ImplicationNetwork::pos(i)] {
+            persistent.insert(i, 1);
+        } else if reachable[network.neg(i)] {
+            persistent.insert(i, 0);
+        }
+    }
+
+    (constant + max_flow, persistent)
+}
+
+/// The implication network of a posiform, with a node for each literal $x_i$ and $\bar{x}_i$, where the source is the
+/// literal $x_0 = 1$ and the sink is its complement
+struct ImplicationNetwork {
+    num_x: usize,
+    // the arcs are stored as (head, capacity, flow), and each arc at index k has its reverse arc at index k ^ 1
+    arcs: Vec<(usize, f64, f64)>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl ImplicationNetwork {
+    /// Tolerance for the residual capacity of an arc to be considered nonzero
+    const EPS: f64 = 1E-10;
+
+    fn new(num_x: usize) -> Self {
+        Self {
+            num_x,
+            arcs: Vec::new(),
+            adjacency: vec![Vec::new(); 2 * num_x + 2],
+        }
+    }
+
+    const fn pos(i: usize) -> usize {
+        i
+    }
+
+    const fn neg(&self, i: usize) -> usize {
+        self.num_x + i
+    }
+
+    const fn source(&self) -> usize {
+        2 * self.num_x
+    }
+
+    const fn sink(&self) -> usize {
+        2 * self.num_x + 1
+    }
+
+    const fn complement(&self, u: usize) -> usize {
+        if u < self.num_x {
+            u + self.num_x
+        } else if u < 2 * self.num_x {
+            u - self.num_x
+        } else if u == self.source() {
+            self.sink()
+        } else {
+            self.source()
+        }
+    }
+
+    fn add_arc(&mut self, tail: usize, head: usize, capacity: f64) {
+        self.adjacency[tail].push(self.arcs.len());
+        self.arcs.push((head, capacity, 0.0));
+        self.adjacency[head].push(self.arcs.len());
+        self.arcs.push((tail, 0.0, 0.0));
+    }
+
+    /// Adds the posiform term a * u * v, as the pair of arcs u -> !v and v -> !u, each with half the capacity
+    fn add_term(&mut self, u: usize, v: usize, a: f64) {
+        self.add_arc(u, self.complement(v), 0.5 * a);
+        self.add_arc(v, self.complement(u), 0.5 * a);
+    }
+
+    fn residual(&self, arc: usize) -> f64 {
+        let (_, capacity, flow) = self.arcs[arc];
+        capacity - flow
+    }
+
+    /// Computes the maximum flow from the source to the sink with Dinic's algorithm, and then averages the flow on
+    /// each pair of mirrored arcs so that the residual network is symmetric
+    fn max_flow(&mut self) -> f64 {
+        let mut total_flow = 0.0;
+
+        while let Some(levels) = self.levels() {
+            let mut next_arc = vec![0; self.adjacency.len()];
+            loop {
+                let pushed = self.augment(self.source(), f64::INFINITY, &levels, &mut next_arc);
+                if pushed <= Self::EPS {
+                    break;
+                }
+                total_flow += pushed;
+            }
+        }
+
+        // the arcs of each term are added together, so the mirror of arc 4k is arc 4k + 2
+        for k in (0..self.arcs.len()).step_by(4) {
+            let flow = 0.5 * (self.arcs[k].2 + self.arcs[k + 2].2);
+            self.arcs[k].2 = flow;
+            self.arcs[k + 1].2 = -flow;
+            self.arcs[k + 2].2 = flow;
+            self.arcs[k + 3].2 = -flow;
+        }
+
+        total_flow
+    }
+
+    /// Computes the distance of every node from the source in the residual network, if the sink can be reached
+    fn levels(&self) -> Option<Vec<usize>> {
+        let mut levels = vec![usize::MAX; self.adjacency.len()];
+        let mut queue = std::collections::VecDeque::new();
+        levels[self.source()] = 0;
+        queue.push_back(self.source());
+
+        while let Some(u) = queue.pop_front() {
+            for &arc in &self.adjacency[u] {
+                let head = self.arcs[arc].0;
+                if levels[head] == usize::MAX && self.residual(arc) > Self::EPS {
+                    levels[head] = levels[u] + 1;
+                    queue.push_back(head);
+                }
+            }
+        }
+
+        match levels[self.sink()] {
+            usize::MAX => None,
+            _ => Some(levels),
+        }
+    }
+
+    /// Pushes flow along a path in the level graph from u to the sink, and returns the amount of flow pushed
+    fn augment(&mut self, u: usize, limit: f64, levels: &[usize], next_arc: &mut [usize]) -> f64 {
+        if u == self.sink() {
+            return limit;
+        }
+
+        while next_arc[u] < self.adjacency[u].len() {
+            let arc = self.adjacency[u][next_arc[u]];
+            let head = self.arcs[arc].0;
+
+            if levels[head] == levels[u] + 1 && self.residual(arc) > Self::EPS {
+                let pushed = self.augment(head, limit.min(self.residual(arc)), levels, next_arc);
+                if pushed > Self::EPS {
+                    self.arcs[arc].2 += pushed;
+                    self.arcs[arc ^ 1].2 -= pushed;
+                    return pushed;
+                }
+            }
+
+            next_arc[u] += 1;
+        }
+
+        0.0
+    }
+
+    /// Finds the nodes that can be reached from the source in the residual network
+    fn reachable_from_source(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.adjacency.len()];
+        let mut stack = vec![self.source()];
+        reachable[self.source()] = true;
+
+        while let Some(u) = stack.pop() {
+            for &arc in &self.adjacency[u] {
+                let head = self.arcs[arc].0;
+                if !reachable[head] && self.residual(arc) > Self::EPS {
+                    reachable[head] = true;
+                    stack.push(head);
+                }
+            }
+        }
+
+        reachable
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::lower_bound::{li_lower_bound, pardalos_rodgers_lower_bound};
+    use crate::lower_bound::{li_lower_bound, lp_bound, pardalos_rodgers_lower_bound, roof_dual};
     use crate::qubo::Qubo;
-    use crate::tests::make_solver_qubo;
+    use crate::tests::{get_min_obj, make_solver_qubo, make_test_prng};
     use ndarray::Array1;
     use sprs::TriMat;
     use std::collections::HashMap;
@@ -210,6 +480,67 @@ mod tests {
         assert_eq!(li_lb, -2.5);
     }
 
+    #[test]
+    fn test_roof_dual_brute_force() {
+        let mut prng = make_test_prng();
+
+        for _ in 0..10 {
+            let p = Qubo::make_random_qubo(10, &mut prng, 0.4);
+            let (lower_bound, persistent) = roof_dual(&p);
+
+            // find all of the optimal solutions by enumeration
+            let points: Vec<_> = (0..(1usize << p.num_x()))
+                .map(|k| Array1::from_shape_fn(p.num_x(), |i| (k >> i) & 1))
+                .collect();
+            let opt = get_min_obj(&p, &points);
+
+            assert!(lower_bound <= opt + 1E-8);
+
+            // the persistent variables must agree with every optimal solution
+            for x in points.iter().filter(|x| p.eval_usize(x) <= opt + 1E-8) {
+                for (&i, &value) in &persistent {
+                    assert_eq!(x[i], value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lp_bound() {
+        let mut prng = make_test_prng();
+
+        for _ in 0..5 {
+            let p = Qubo::make_random_qubo(8, &mut prng, 0.5);
+            let opt = (0..(1usize << p.num_x()))
+                .map(|k| p.eval_usize(&Array1::from_shape_fn(p.num_x(), |i| (k >> i) & 1)))
+                .fold(f64::INFINITY, f64::min);
+
+            // the LP bound sits between the bound of pardalos1990 and the optimum
+            let bound = lp_bound(&p, &HashMap::new());
+            assert!(bound <= opt + 1E-6);
+            assert!(pardalos_rodgers_lower_bound(&p, &HashMap::new()) <= bound + 1E-6);
+        }
+    }
+
+    #[test]
+    fn test_roof_dual_submodular() {
+        // with only nonpositive couplings the roof dual is tight
+        let mut q = TriMat::new((3, 3));
+        q.add_triplet(0, 1, -2.0);
+        q.add_triplet(1, 2, -2.0);
+        q.add_triplet(2, 2, 1.0);
+
+        let c = Array1::from_vec(vec![-1.0, 0.5, 0.0]);
+        let qubo = Qubo::new_with_c(q.to_csr(), c);
+
+        let (lower_bound, persistent) = roof_dual(&qubo);
+
+        // the unique optimal solution is x = [1, 1, 1] with an objective of -2
+        assert!((lower_bound - -2.0).abs() < 1E-10);
+        assert_eq!(persistent.len(), 3);
+        assert!(persistent.values().all(|&x_i| x_i == 1));
+    }
+
     #[test]
     fn test_lower_bound_qubo_problem() {
         let p = make_solver_qubo();
@@ -0,0 +1,141 @@
+//! This module contains the node selection strategies of the branch and bound solver
+//!
+//! The node selection strategy decides which of the open nodes is processed next, these include:
+//! - Best Bound, the node with the lowest lower bound
+//! - Depth First, the most recently created node
+//! - Breadth First, the least recently created node
+
+use crate::branch_node::QuboBBNode;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Enum for the node selection strategies of the branch and bound solver
+///
+/// Best bound typically proves optimality with the fewest nodes, but it keeps a wide frontier of open nodes, so it uses
+/// the most memory. Depth first only keeps the nodes along the current path and their siblings, so it uses the least
+/// memory and tends to find feasible solutions quickly, but it can spend a long time in poor subtrees. Breadth first
+/// is mostly useful for comparison, as it has both the memory usage of best bound and a poor bound improvement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeSelectionStrategy {
+    DepthFirst,
+    BestBound,
+    BreadthFirst,
+}
+
+/// The set of open nodes of the branch and bound tree, stored in the order given by the node selection strategy
+pub enum NodeQueue {
+    DepthFirst(Vec<QuboBBNode>),
+    BestBound(BinaryHeap<QuboBBNode>),
+    BreadthFirst(VecDeque<QuboBBNode>),
+}
+
+impl NodeQueue {
+    /// Creates an empty node queue for the given node selection strategy
+    pub const fn new(strategy: NodeSelectionStrategy) -> Self {
+        match strategy {
+            NodeSelectionStrategy::DepthFirst => Self::DepthFirst(Vec::new()),
+            NodeSelectionStrategy::BestBound => Self::BestBound(BinaryHeap::new()),
+            NodeSelectionStrategy::BreadthFirst => Self::BreadthFirst(VecDeque::new()),
+        }
+    }
+
+    /// Adds a node to the queue
+    pub fn push(&mut self, node: QuboBBNode) {
+        match self {
+            Self::DepthFirst(stack) => stack.push(node),
+            Self::BestBound(heap) => heap.push(node),
+            Self::BreadthFirst(queue) => queue.push_back(node),
+        }
+    }
+
+    /// Adds the two children of a branched node, so that the first child is taken from the queue before the second
+    ///
+    /// Under best bound the children have the same lower bound, so which one is taken first is up to the heap.
+    pub fn push_children(&mut self, first: QuboBBNode, second: QuboBBNode) {
+        match self {
+            Self::DepthFirst(stack) => {
+                stack.push(second);
+                stack.push(first);
+            }
+            Self::BestBound(heap) => {
+                heap.push(first);
+                heap.push(second);
+            }
+            Self::BreadthFirst(queue) => {
+                queue.push_back(first);
+                queue.push_back(second);
+            }
+        }
+    }
+
+    /// Removes the next node to process from the queue
+    pub fn pop(&mut self) -> Option<QuboBBNode> {
+        match self {
+            Self::DepthFirst(stack) => stack.pop(),
+            Self::BestBound(heap) => heap.pop(),
+            Self::BreadthFirst(queue) => queue.pop_front(),
+        }
+    }
+
+    /// Returns the number of open nodes
+    pub fn len(&self) -> usize {
+        match self {
+            Self::DepthFirst(stack) => stack.len(),
+            Self::BestBound(heap) => heap.len(),
+            Self::BreadthFirst(queue) => queue.len(),
+        }
+    }
+
+    /// Checks if there are no open nodes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the open nodes, in no particular order
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &QuboBBNode> + '_> {
+        match self {
+            Self::DepthFirst(stack) => Box::new(stack.iter()),
+            Self::BestBound(heap) => Box::new(heap.iter()),
+            Self::BreadthFirst(queue) => Box::new(queue.iter()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::branch_node::QuboBBNode;
+    use crate::node_selection::{NodeQueue, NodeSelectionStrategy};
+    use std::collections::HashMap;
+
+    fn make_node(lower_bound: f64) -> QuboBBNode {
+        QuboBBNode {
+            lower_bound,
+            ..QuboBBNode::root(1, HashMap::new())
+        }
+    }
+
+    fn pop_order(strategy: NodeSelectionStrategy) -> Vec<f64> {
+        let mut queue = NodeQueue::new(strategy);
+        for lower_bound in [2.0, 1.0, 3.0] {
+            queue.push(make_node(lower_bound));
+        }
+
+        assert_eq!(queue.len(), 3);
+        std::iter::from_fn(|| queue.pop().map(|node| node.lower_bound)).collect()
+    }
+
+    #[test]
+    fn test_node_selection_order() {
+        assert_eq!(
+            pop_order(NodeSelectionStrategy::BestBound),
+            vec![1.0, 2.0, 3.0]
+        );
+        assert_eq!(
+            pop_order(NodeSelectionStrategy::DepthFirst),
+            vec![3.0, 1.0, 2.0]
+        );
+        assert_eq!(
+            pop_order(NodeSelectionStrategy::BreadthFirst),
+            vec![2.0, 1.0, 3.0]
+        );
+    }
+}
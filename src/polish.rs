@@ -0,0 +1,170 @@
+//! This module contains polishing heuristics, that improve an integral solution, such as one found by B&B, by
+//! searching a neighborhood of it that is given by a relaxation
+//!
+//! Currently the following heuristics are implemented:
+//! - RINS, relaxation induced neighborhood search, which exactly solves the subproblem over the variables where the
+//!   incumbent and the relaxation disagree
+
+use crate::branchbound::BBSolver;
+use crate::exact::{brute_force, MAX_BRUTE_FORCE_VARIABLES};
+use crate::qubo::Qubo;
+use crate::solver_options::SolverOptions;
+use ndarray::Array1;
+use std::collections::HashMap;
+
+/// The largest distance between a relaxed value and the incumbent at which the two are taken to agree
+const AGREEMENT_TOLERANCE: f64 = 1E-6;
+
+/// Polishes the incumbent with RINS, fixing the variables where the incumbent and the relaxation agree, and exactly
+/// solving the QUBO over the variables where they disagree
+///
+/// The residual QUBO is solved by brute force if it has at most `MAX_BRUTE_FORCE_VARIABLES` variables, and otherwise
+/// by B&B on its convex symmetric form, starting from the incumbent. The incumbent is a point of the residual QUBO, so
+/// the polished solution is never worse than the incumbent, and the incumbent is returned if there is no improvement.
+///
+/// # Panics
+///
+/// Will panic if the incumbent or the relaxation does not have one entry for each variable of the QUBO.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::polish::rins;
+/// use ndarray::Array1;
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(20, &mut prng, 0.3);
+///
+/// // the relaxation agrees with the incumbent on the first half of the variables
+/// let incumbent = Array1::zeros(20);
+/// let relaxation = Array1::from_shape_fn(20, |i| if i < 10 { 0.0 } else { 0.5 });
+/// let x = rins(&p, &incumbent, &relaxation);
+/// assert!(p.eval_usize(&x) <= p.eval_usize(&incumbent));
+/// ```
+pub fn rins(qubo: &Qubo, incumbent: &Array1<usize>, relaxation: &Array1<f64>) -> Array1<usize> {
+    let num_x = qubo.num_x();
+    assert_eq!(
+        incumbent.len(),
+        num_x,
+        "The incumbent must have {num_x} variables"
+    );
+    assert_eq!(
+        relaxation.len(),
+        num_x,
+        "The relaxation must have {num_x} variables"
+    );
+
+    // fix the variables where the incumbent and the relaxation agree
+    let fixed = incumbent
+        .iter()
+        .zip(relaxation)
+        .enumerate()
+        .filter(|(_, (&x_i, &r_i))| (x_i as f64 - r_i).abs() <= AGREEMENT_TOLERANCE)
+        .map(|(i, (&x_i, _))| (i, x_i))
+        .collect::<HashMap<usize, usize>>();
+
+    if fixed.len() == num_x {
+        return incumbent.clone();
+    }
+
+    // the k-th variable of the residual QUBO is the k-th free variable
+    let free = (0..num_x)
+        .filter(|i| !fixed.contains_key(i))
+        .collect::<Vec<usize>>();
+    let residual = qubo.fix_and_reduce(&fixed);
+    let start = free
+        .iter()
+        .map(|&i| incumbent[i])
+        .collect::<Array1<usize>>();
+    let x_residual = solve_residual(residual, start);
+
+    let mut x = incumbent.clone();
+    for (&i, &x_i) in free.iter().zip(&x_residual) {
+        x[i] = x_i;
+    }
+
+    // the B&B search can stop at its time limit, so only keep the solution if it is an improvement
+    if qubo.eval_usize(&x) < qubo.eval_usize(incumbent) {
+        x
+    } else {
+        incumbent.clone()
+    }
+}
+
+/// Exactly solves the residual QUBO of RINS, by brute force if it is small enough and by B&B otherwise
+fn solve_residual(residual: Qubo, start: Array1<usize>) -> Array1<usize> {
+    if residual.num_x() <= MAX_BRUTE_FORCE_VARIABLES {
+        return brute_force(&residual).0;
+    }
+
+    let mut options = SolverOptions::new();
+    options.verbose = 0;
+    options.initial_solution = Some(start);
+
+    // the convex symmetric form is convex by construction, so the solver does not check it again
+    let residual = residual.make_symmetric().convex_symmetric_form();
+    let mut solver = BBSolver::new_assume_convex(residual, options)
+        .expect("the convex symmetric form of a QUBO is symmetric");
+    solver.solve().solution
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::branchbound::BBSolver;
+    use crate::exact::brute_force;
+    use crate::polish::rins;
+    use crate::qubo::Qubo;
+    use crate::solver_options::SolverOptions;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_rins() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(14, &mut prng, 0.5);
+        let incumbent = Array1::from_shape_fn(14, |i| i % 2);
+
+        // a relaxation that agrees everywhere leaves nothing to search
+        let relaxation = incumbent.mapv(|x_i| x_i as f64);
+        assert_eq!(rins(&p, &incumbent, &relaxation), incumbent);
+
+        // a relaxation that agrees nowhere searches the whole QUBO
+        let relaxation = Array1::from_elem(14, 0.5);
+        let (_, opt) = brute_force(&p);
+        assert!((p.eval_usize(&rins(&p, &incumbent, &relaxation)) - opt).abs() < 1E-10);
+
+        // otherwise the agreeing variables are kept, and the rest are optimal given them
+        let relaxation = Array1::from_shape_fn(14, |i| if i < 6 { (i % 2) as f64 } else { 0.3 });
+        let x = rins(&p, &incumbent, &relaxation);
+        let fixed = (0..6)
+            .map(|i| (i, i % 2))
+            .collect::<HashMap<usize, usize>>();
+        let (_, opt_fixed) = brute_force(&p.fix_and_reduce(&fixed));
+
+        assert!((0..6).all(|i| x[i] == incumbent[i]));
+        assert!((p.eval_usize(&x) - opt_fixed).abs() < 1E-10);
+        assert!(p.eval_usize(&x) <= p.eval_usize(&incumbent));
+    }
+
+    #[test]
+    fn test_rins_branch_and_bound() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(30, &mut prng, 0.1);
+
+        // the residual is too large for brute force, so it is solved with B&B
+        let incumbent = Array1::zeros(30);
+        let x = rins(&p, &incumbent, &Array1::from_elem(30, 0.5));
+
+        let mut options = SolverOptions::new();
+        options.verbose = 0;
+        let mut solver =
+            BBSolver::new(p.make_symmetric().convex_symmetric_form(), options).unwrap();
+        let result = solver.solve();
+
+        assert!((p.eval_usize(&x) - p.eval_usize(&result.solution)).abs() < 1E-6);
+    }
+}
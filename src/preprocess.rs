@@ -3,10 +3,12 @@ use crate::persistence::compute_iterative_persistence;
 ///
 /// Currently the following features are implemented:
 /// - Iterative persistence
+/// - Persistency based variable fixing
+/// - Elimination of low degree variables
 use crate::qubo::Qubo;
 use ndarray::Array1;
 use sprs::TriMat;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// This is the main entry point for preprocessing
 pub fn preprocess_qubo(
@@ -40,6 +42,33 @@ pub fn preprocess_qubo(
     fixed_variables
 }
 
+/// Finds the variables that are fixed by first order persistency, as a presolve step before branch and bound
+///
+/// If the smallest possible change in the objective from setting x_i = 1 over all the other variables is positive,
+/// then x_i = 0 in every optimal solution, and if the largest possible change is negative, then x_i = 1. This is
+/// repeated with the fixed variables until no more variables can be fixed. The returned assignments can be used
+/// directly as the fixed variables of the solver options.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::preprocess;
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+///
+/// let fixed_variables = preprocess::fix_persistent(&p);
+/// ```
+pub fn fix_persistent(qubo: &Qubo) -> HashMap<usize, usize> {
+    // the persistence bounds assume a symmetric QUBO, and are tightest when the diagonal is moved into the linear term
+    let qubo_shift = shift_qubo(&qubo.make_symmetric());
+
+    compute_iterative_persistence(&qubo_shift, &HashMap::new(), qubo_shift.num_x())
+}
+
 /// This function is used to get the effect of the fixed variables on the linear term, we want to
 /// avoid generating copies of the Qubo object
 ///
@@ -111,7 +140,7 @@ pub fn fix_no_effect_variables(qubo: &Qubo) -> HashMap<usize, usize> {
 }
 
 /// Creates a new QUBO where the diagonal elements are zeroed out and the linear term is adjusted
-/// accordingly
+/// accordingly, the offset is unchanged
 pub fn shift_qubo(qubo: &Qubo) -> Qubo {
     let mut new_q = TriMat::new((qubo.num_x(), qubo.num_x()));
     let mut new_c = qubo.c.clone();
@@ -124,17 +153,289 @@ pub fn shift_qubo(qubo: &Qubo) -> Qubo {
         }
     }
 
-    Qubo::new_with_c(new_q.to_csr(), new_c)
+    Qubo {
+        q: new_q.to_csr(),
+        c: new_c,
+        offset: qubo.offset,
+    }
+}
+
+/// A rule to reconstruct the value of a variable removed by `eliminate_low_degree`
+///
+/// The variable is set to its best response to its neighbors, so $x_i = 1$ if and only if
+/// $a_i + \sum_j b_{ij} x_j < 0$. Each neighbor is either kept in the reduced QUBO or eliminated after this variable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Elimination {
+    /// The eliminated variable, in the original QUBO
+    pub variable: usize,
+    /// The linear coefficient $a_i$ of the variable when it was eliminated
+    pub linear: f64,
+    /// The neighbors of the variable when it was eliminated, as (j, $b_{ij}$)
+    pub neighbors: Vec<(usize, f64)>,
+}
+
+impl Elimination {
+    /// The best value of the eliminated variable, given the values of its neighbors in a full solution
+    pub fn best_response(&self, x: &Array1<usize>) -> usize {
+        let delta = self.linear
+            + self
+                .neighbors
+                .iter()
+                .map(|&(j, b_ij)| b_ij * x[j] as f64)
+                .sum::<f64>();
+
+        usize::from(delta < 0.0)
+    }
+}
+
+/// Eliminates the variables that interact with at most two other variables, and returns the reduced QUBO along with
+/// the rules to reconstruct the eliminated variables
+///
+/// If $x_i$ has the linear coefficient $a_i$ and interacts only with $x_j$ and $x_k$, then its best value is
+/// $x_i = 1$ exactly when $a_i + b_{ij} x_j + b_{ik} x_k < 0$, and the best objective over $x_i$ is
+/// $\min(0, a_i + b_{ij} x_j + b_{ik} x_k)$. As any function of two binary variables is quadratic, this is added to
+/// the QUBO exactly as a constant, linear terms on $x_j$ and $x_k$, and a coupling between them. Variables with fewer
+/// neighbors are the same with the missing coefficients set to zero, so a variable with no neighbors is fixed by the
+/// sign of its linear term. Removing a variable never raises the degree of its neighbors, so this is repeated until
+/// every variable has a degree of at least three, which eliminates every variable of a tree or of a cycle.
+///
+/// The reduced QUBO is over the kept variables in their original order, and its objective at any point is the best
+/// objective of the original QUBO with the kept variables at those values, so an optimum of the reduced QUBO gives an
+/// optimum of the original with `reconstruct_eliminated`.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::preprocess::{eliminate_low_degree, reconstruct_eliminated};
+/// use smolprng::{PRNG, JsfLarge};
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.05);
+///
+/// let (p_reduced, eliminations) = eliminate_low_degree(&p);
+/// let x_reduced = ndarray::Array1::zeros(p_reduced.num_x());
+/// let x = reconstruct_eliminated(&x_reduced, &eliminations);
+/// assert!(p.eval_usize(&x) <= p_reduced.eval_usize(&x_reduced) + 1E-10);
+/// ```
+pub fn eliminate_low_degree(qubo: &Qubo) -> (Qubo, Vec<Elimination>) {
+    let num_x = qubo.num_x();
+
+    // as x_i^2 = x_i the diagonal is linear, and the coefficient of each product x_i x_j is stored in both rows
+    let mut linear = qubo.c.clone();
+    let mut couplings = vec![BTreeMap::<usize, f64>::new(); num_x];
+    for (&value, (i, j)) in &qubo.q {
+        if i == j {
+            linear[i] += 0.5 * value;
+        } else {
+            *couplings[i].entry(j).or_insert(0.0) += 0.5 * value;
+            *couplings[j].entry(i).or_insert(0.0) += 0.5 * value;
+        }
+    }
+    for row in &mut couplings {
+        row.retain(|_, b_ij| *b_ij != 0.0);
+    }
+
+    let mut offset = qubo.offset;
+    let mut is_eliminated = vec![false; num_x];
+    let mut eliminations = Vec::new();
+    let mut candidates = (0..num_x)
+        .filter(|&i| couplings[i].len() <= 2)
+        .collect::<Vec<usize>>();
+
+    while let Some(i) = candidates.pop() {
+        if is_eliminated[i] || couplings[i].len() > 2 {
+            continue;
+        }
+
+        let a_i = linear[i];
+        let neighbors = std::mem::take(&mut couplings[i])
+            .into_iter()
+            .collect::<Vec<(usize, f64)>>();
+        for &(j, _) in &neighbors {
+            couplings[j].remove(&i);
+        }
+
+        // the best objective over x_i, for each assignment of its neighbors
+        let b_ij = neighbors.first().map_or(0.0, |&(_, b)| b);
+        let b_ik = neighbors.get(1).map_or(0.0, |&(_, b)| b);
+        let f_00 = a_i.min(0.0);
+        let f_10 = (a_i + b_ij).min(0.0);
+        let f_01 = (a_i + b_ik).min(0.0);
+        let f_11 = (a_i + b_ij + b_ik).min(0.0);
+
+        offset += f_00;
+        if let Some(&(j, _)) = neighbors.first() {
+            linear[j] += f_10 - f_00;
+        }
+        if let [(j, _), (k, _)] = neighbors[..] {
+            linear[k] += f_01 - f_00;
+
+            let b_jk = f_11 - f_10 - f_01 + f_00;
+            if b_jk != 0.0 {
+                let coupling = couplings[j].entry(k).or_insert(0.0);
+                *coupling += b_jk;
+                if *coupling == 0.0 {
+                    couplings[j].remove(&k);
+                    couplings[k].remove(&j);
+                } else {
+                    let coupling = *coupling;
+                    couplings[k].insert(j, coupling);
+                }
+            }
+        }
+
+        // the neighbors may now have a low enough degree to be eliminated
+        candidates.extend(
+            neighbors
+                .iter()
+                .map(|&(j, _)| j)
+                .filter(|&j| couplings[j].len() <= 2),
+        );
+
+        is_eliminated[i] = true;
+        eliminations.push(Elimination {
+            variable: i,
+            linear: a_i,
+            neighbors,
+        });
+    }
+
+    // relabel the kept variables in their original order
+    let kept = (0..num_x)
+        .filter(|&i| !is_eliminated[i])
+        .collect::<Vec<usize>>();
+    let mut index = vec![0; num_x];
+    for (k, &i) in kept.iter().enumerate() {
+        index[i] = k;
+    }
+
+    // the coupling is in both rows, which the factor of 0.5 in the objective undoes
+    let mut q = TriMat::new((kept.len(), kept.len()));
+    for (k, &i) in kept.iter().enumerate() {
+        for (&j, &b_ij) in &couplings[i] {
+            q.add_triplet(k, index[j], b_ij);
+        }
+    }
+    let c = kept.iter().map(|&i| linear[i]).collect::<Array1<f64>>();
+
+    (
+        Qubo {
+            q: q.to_csr(),
+            c,
+            offset,
+        },
+        eliminations,
+    )
+}
+
+/// Expands a solution of the reduced QUBO from `eliminate_low_degree` to a solution of the original QUBO, by setting
+/// the eliminated variables to their best responses in the reverse order of elimination
+pub fn reconstruct_eliminated(
+    x_reduced: &Array1<usize>,
+    eliminations: &[Elimination],
+) -> Array1<usize> {
+    let num_x = x_reduced.len() + eliminations.len();
+    let mut is_eliminated = vec![false; num_x];
+    for elimination in eliminations {
+        is_eliminated[elimination.variable] = true;
+    }
+
+    let mut x = Array1::zeros(num_x);
+    for (i, &x_i) in (0..num_x)
+        .filter(|&i| !is_eliminated[i])
+        .zip(x_reduced.iter())
+    {
+        x[i] = x_i;
+    }
+
+    // the neighbors of each variable were either kept or eliminated after it, so they are already set
+    for elimination in eliminations.iter().rev() {
+        x[elimination.variable] = elimination.best_response(&x);
+    }
+
+    x
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::preprocess::preprocess_qubo;
+    use crate::exact::brute_force;
+    use crate::initial_points::generate_random_binary_points;
+    use crate::preprocess::{
+        eliminate_low_degree, fix_persistent, preprocess_qubo, reconstruct_eliminated,
+    };
     use crate::qubo::Qubo;
+    use crate::tests::make_test_prng;
     use ndarray::Array1;
     use sprs::CsMat;
     use std::collections::HashMap;
 
+    #[test]
+    fn test_fix_persistent() {
+        // x_0 has a large positive linear term, so it is 0 in every optimal solution, which then forces x_1 = 1 as
+        // its only remaining coupling is gone
+        let rows = vec![0, 1, 1, 2];
+        let cols = vec![1, 2, 1, 2];
+        let values = vec![-2.0, 1.0, 2.0, -2.0];
+        let c = vec![3.0, -2.5, 0.5];
+        let p = Qubo::from_vec(rows, cols, values, c, 3);
+
+        let fixed = fix_persistent(&p);
+        assert_eq!(fixed.get(&0), Some(&0));
+        assert_eq!(fixed.get(&1), Some(&1));
+
+        // check against the optimal solution found by enumeration
+        let opt = (0..8usize)
+            .map(|k| Array1::from_shape_fn(3, |i| (k >> i) & 1))
+            .min_by(|a, b| p.eval_usize(a).total_cmp(&p.eval_usize(b)))
+            .unwrap();
+
+        for (&i, &value) in &fixed {
+            assert_eq!(opt[i], value);
+        }
+    }
+
+    #[test]
+    fn test_eliminate_low_degree() {
+        let mut prng = make_test_prng();
+        for sparsity in [0.05, 0.1, 0.2] {
+            let p = Qubo::make_random_qubo(16, &mut prng, sparsity);
+            let (p_reduced, eliminations) = eliminate_low_degree(&p);
+            assert_eq!(p_reduced.num_x() + eliminations.len(), p.num_x());
+
+            // the reconstruction of the reduced optimum is an optimum of the original
+            let (_, opt) = brute_force(&p);
+            let (x_reduced, reduced_opt) = brute_force(&p_reduced);
+            let x = reconstruct_eliminated(&x_reduced, &eliminations);
+            assert!((reduced_opt - opt).abs() < 1E-10);
+            assert!((p.eval_usize(&x) - opt).abs() < 1E-10);
+
+            // at any point, the reduced objective is the objective of the reconstruction
+            for x_reduced in generate_random_binary_points(p_reduced.num_x(), 10, &mut prng) {
+                let x = reconstruct_eliminated(&x_reduced, &eliminations);
+                assert!((p.eval_usize(&x) - p_reduced.eval_usize(&x_reduced)).abs() < 1E-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_eliminate_low_degree_cycle() {
+        // a cycle of frustrated couplings is eliminated entirely, leaving the optimum as the offset
+        let num_x = 6;
+        let rows = (0..num_x).collect::<Vec<usize>>();
+        let cols = (0..num_x).map(|i| (i + 1) % num_x).collect::<Vec<usize>>();
+        let p = Qubo::from_vec(rows, cols, vec![3.0; num_x], vec![-1.0; num_x], num_x);
+
+        let (p_reduced, eliminations) = eliminate_low_degree(&p);
+        assert_eq!(p_reduced.num_x(), 0);
+
+        let (_, opt) = brute_force(&p);
+        let x = reconstruct_eliminated(&Array1::zeros(0), &eliminations);
+        assert!((p_reduced.offset - opt).abs() < 1E-10);
+        assert!((p.eval_usize(&x) - opt).abs() < 1E-10);
+    }
+
     #[test]
     fn test_preprocess_qubo_1() {
         let eye = CsMat::eye(3);
@@ -10,7 +10,6 @@ use crate::{kopt, local_search};
 use smolprng::{JsfLarge, PRNG};
 
 use crate::branchbound::BBSolver;
-use crate::branchbound_utils::get_current_time;
 use crate::preprocess::preprocess_qubo;
 use crate::solver_options::SolverOptions;
 
@@ -526,22 +525,21 @@ pub fn solve_branch_bound(
 
     options.fixed_variables = fixed_variables;
 
-    let mut solver = BBSolver::new(p, options);
+    let mut solver = BBSolver::new(p, options)
+        .map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))?;
 
     // if we have a warm start, use it
     if let Some(x) = warm_start {
         solver.warm_start(Array1::<usize>::from(x));
     }
 
-    let (x, obj) = solver.solve();
-
-    let time_elapse = get_current_time() - solver.time_start;
+    let result = solver.solve();
 
     Ok((
-        x.to_vec(),
-        obj,
-        time_elapse,
-        solver.nodes_visited,
+        result.solution.to_vec(),
+        result.objective,
+        result.time,
+        result.nodes_visited,
         solver.nodes_processed,
     ))
 }
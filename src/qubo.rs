@@ -2,29 +2,171 @@
 //!
 //! The QUBO struct uses a sparse representation of the QUBO matrix, and is stored in CSR order, it is not assumed to be symmetrical.
 
-use ndarray::Array1;
+use crate::generators::{max_cut_qubo, Graph};
+use crate::ising::IsingModel;
+use ndarray::{Array1, Array2};
 use ndarray_linalg::{Eigh, UPLO};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
+use sprs::linalg::reverse_cuthill_mckee;
 use sprs::{CsMat, TriMat};
+use std::collections::HashMap;
+use std::fmt;
 use std::io::BufRead;
+use std::io::Read;
 use std::io::Write;
+use std::path::Path;
 
 use smolprng::Algorithm;
 use smolprng::PRNG;
 
-/// The QUBO struct, which contains the QUBO matrix and the linear coefficients. With the following form:
+/// Errors that can be generated while reading a QUBO from a file
+#[derive(Debug)]
+pub enum QuboError {
+    /// The file could not be opened or read
+    Io(std::io::Error),
+    /// The file is not in the expected format
+    Parse(String),
+    /// The file is well-formed, but does not describe a problem that can be represented as a QUBO
+    Unsupported(String),
+}
+
+impl fmt::Display for QuboError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "IO error: {err}"),
+            Self::Parse(msg) => write!(f, "Parse error: {msg}"),
+            Self::Unsupported(msg) => write!(f, "Unsupported problem: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for QuboError {}
+
+impl From<std::io::Error> for QuboError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The distribution of the coefficients of a randomly generated QUBO
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoeffDist {
+    /// Uniformly distributed on [-a, a]
+    Uniform(f64),
+    /// Standard normal distributed
+    Normal,
+    /// Uniformly distributed on the integers in [-k, k]
+    Integer(u64),
+}
+
+impl CoeffDist {
+    /// Draws a single coefficient from the distribution
+    pub fn sample<T: Algorithm>(self, prng: &mut PRNG<T>) -> f64 {
+        match self {
+            // written so that a = 0.5 gives exactly gen_f64() - 0.5
+            Self::Uniform(a) => 2.0 * a * (prng.gen_f64() - 0.5),
+            Self::Normal => prng.normal(),
+            Self::Integer(k) => (prng.gen_u64() % (2 * k + 1)) as f64 - k as f64,
+        }
+    }
+}
+
+/// The QUBO struct, which contains the QUBO matrix, the linear coefficients and a constant offset. With the following
+/// form:
 ///
-/// $$ \min_{x\in \{0,1\}^n} 0.5 x^T Q x + c^Tx $$
+/// $$ \min_{x\in \{0,1\}^n} 0.5 x^T Q x + c^Tx + \text{offset} $$
+///
+/// With the `serde` feature, the QUBO can be serialized, where the Hessian is stored as a list of triplets.
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "QuboTriplets", try_from = "QuboTriplets")
+)]
 pub struct Qubo {
     /// The Hessian of the QUBO problem
     pub q: CsMat<f64>,
     /// The linear term of the QUBO problem
     pub c: Array1<f64>,
+    /// The constant term of the QUBO problem, this does not change the arg-min but is part of the objective
+    pub offset: f64,
+}
+
+/// The serialized form of a QUBO, the Hessian as triplets in CSR order and the dense linear term
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct QuboTriplets {
+    num_x: usize,
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    values: Vec<f64>,
+    c: Vec<f64>,
+    #[serde(default)]
+    offset: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<Qubo> for QuboTriplets {
+    fn from(qubo: Qubo) -> Self {
+        let (rows, cols, values, c, num_x) = qubo.to_vec();
+        Self {
+            num_x,
+            rows,
+            cols,
+            values,
+            c,
+            offset: qubo.offset,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<QuboTriplets> for Qubo {
+    type Error = String;
+
+    fn try_from(triplets: QuboTriplets) -> Result<Self, Self::Error> {
+        let num_x = triplets.num_x;
+
+        // check the data is consistent before building the matrix, as out of range indices would panic
+        if triplets.rows.len() != triplets.values.len()
+            || triplets.cols.len() != triplets.values.len()
+        {
+            return Err("the triplets do not have the same length".to_string());
+        }
+
+        if triplets.c.len() != num_x {
+            return Err(format!(
+                "expected {num_x} linear coefficients, found {}",
+                triplets.c.len()
+            ));
+        }
+
+        if triplets
+            .rows
+            .iter()
+            .chain(&triplets.cols)
+            .any(|&k| k >= num_x)
+        {
+            return Err(format!(
+                "a triplet index is out of range for {num_x} variables"
+            ));
+        }
+
+        let mut qubo = Self::from_vec(
+            triplets.rows,
+            triplets.cols,
+            triplets.values,
+            triplets.c,
+            num_x,
+        );
+        qubo.offset = triplets.offset;
+        Ok(qubo)
+    }
 }
 
 impl Qubo {
-    /// Generate a new QUBO struct from a sparse matrix, assumed that the linear coefficients are zero
+    /// Generate a new QUBO struct from a sparse matrix, assumed that the linear coefficients and the offset are zero
     ///
     /// Example to create a QUBO from a sparse Q matrix:
     /// ```rust
@@ -39,10 +181,11 @@ impl Qubo {
         Self {
             q,
             c: Array1::<f64>::zeros(num_vars),
+            offset: 0.0,
         }
     }
 
-    /// Generate a new QUBO struct from a sparse matrix and a dense vector of linear coefficients
+    /// Generate a new QUBO struct from a sparse matrix and a dense vector of linear coefficients, with a zero offset
     ///
     /// Example to create a QUBO from a sparse Q matrix and a dense c vector:
     /// ```rust
@@ -55,7 +198,7 @@ impl Qubo {
     /// let p = Qubo::new_with_c(q, c);
     /// ```
     pub const fn new_with_c(q: CsMat<f64>, c: Array1<f64>) -> Self {
-        Self { q, c }
+        Self { q, c, offset: 0.0 }
     }
 
     /// Generate a QUBO struct from the list format
@@ -109,6 +252,65 @@ impl Qubo {
         (i, j, q, c, self.num_x())
     }
 
+    /// Generate a QUBO struct from a dense Hessian and a dense vector of linear coefficients, where the exact zeros of
+    /// the Hessian are not stored
+    ///
+    /// Example to create the QUBO $x_0^2 - 2 x_0 x_1 + x_1 + x_2$ from dense arrays:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::array;
+    ///
+    /// let q = array![[2.0, -1.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.0, 0.0]];
+    /// let c = array![0.0, 1.0, 1.0];
+    /// let p = Qubo::from_dense(q, c);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if q is not square, or if the length of c is not the number of rows of q.
+    pub fn from_dense(q: Array2<f64>, c: Array1<f64>) -> Self {
+        let (rows, cols) = q.dim();
+        assert_eq!(
+            rows, cols,
+            "the Hessian must be square, found {rows}x{cols}"
+        );
+        assert_eq!(
+            c.len(),
+            rows,
+            "expected {rows} linear coefficients, found {}",
+            c.len()
+        );
+
+        let mut q_mat = TriMat::<f64>::new((rows, cols));
+        for ((i, j), &value) in q.indexed_iter() {
+            if value != 0.0 {
+                q_mat.add_triplet(i, j, value);
+            }
+        }
+
+        Self::new_with_c(q_mat.to_csr(), c)
+    }
+
+    /// Returns the dense Hessian and a copy of the linear coefficients, the inverse of `from_dense`. The offset is not
+    /// included.
+    ///
+    /// This materializes all $n^2$ entries of the Hessian, so it is meant for inspecting small QUBOs and for dense
+    /// linear algebra routines. For a large QUBO, this can use far more memory than the sparse form, e.g. 8 GB for
+    /// 32,000 variables.
+    ///
+    /// Example of getting the dense form of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use sprs::CsMat;
+    ///
+    /// let p = Qubo::new(CsMat::<f64>::eye(3));
+    /// let (q, c) = p.to_dense();
+    /// assert_eq!(q[[1, 1]], 1.0);
+    /// ```
+    pub fn to_dense(&self) -> (Array2<f64>, Array1<f64>) {
+        (self.q.to_dense(), self.c.clone())
+    }
+
     /// Generate a random QUBO struct with a given number of variables, sparsity, and PRNG. This function is deterministic.
     ///
     /// Example to create a random QUBO with 10 variables and a sparsity of 0.5:
@@ -125,22 +327,45 @@ impl Qubo {
     /// let p = Qubo::make_random_qubo(n, &mut prng, sparsity);
     /// ```
     pub fn make_random_qubo<T: Algorithm>(num_x: usize, prng: &mut PRNG<T>, sparsity: f64) -> Self {
+        Self::make_random_qubo_with_dist(num_x, prng, sparsity, CoeffDist::Uniform(0.5))
+    }
+
+    /// Generate a random QUBO struct with a given number of variables, sparsity, and PRNG, where the coefficients of
+    /// both the Hessian and the linear term are drawn from the given distribution. This function is deterministic.
+    ///
+    /// Example to create a random QUBO with 10 variables and integer coefficients in [-5, 5]:
+    /// ```rust
+    /// use hercules::qubo::{CoeffDist, Qubo};
+    /// use smolprng::*;
+    ///
+    /// let mut prng = PRNG {
+    ///    generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo_with_dist(10, &mut prng, 0.5, CoeffDist::Integer(5));
+    /// ```
+    pub fn make_random_qubo_with_dist<T: Algorithm>(
+        num_x: usize,
+        prng: &mut PRNG<T>,
+        sparsity: f64,
+        dist: CoeffDist,
+    ) -> Self {
         // generate an empty sparse matrix in Triplet format
         let mut q = TriMat::<f64>::new((num_x, num_x));
 
-        // given a probability of sparsity, add a random uniform variable [-.5, .5] to the sparse matrix at element (i,j)
+        // given a probability of sparsity, add a random variable to the sparse matrix at element (i,j)
         for i in 0..num_x {
             for j in i..num_x {
                 if prng.gen_f64() < sparsity {
-                    q.add_triplet(i, j, prng.gen_f64() - 0.5f64);
+                    q.add_triplet(i, j, dist.sample(prng));
                 }
             }
         }
 
-        // generate a dense vector of random uniform variables [-.5, .5] for the linear coefficients
+        // generate a dense vector of random variables for the linear coefficients
         let mut c = Array1::<f64>::zeros(num_x);
         for i in 0..num_x {
-            c[i] = prng.gen_f64() - 0.5f64;
+            c[i] = dist.sample(prng);
         }
 
         Self::new_with_c(q.to_csr(), c)
@@ -163,7 +388,7 @@ impl Qubo {
     /// ```
     pub fn eval(&self, x: &Array1<f64>) -> f64 {
         let temp = &self.q * x;
-        0.5 * x.dot(&temp) + self.c.dot(x)
+        0.5 * x.dot(&temp) + self.c.dot(x) + self.offset
     }
 
     pub fn eval_usize(&self, x: &Array1<usize>) -> f64 {
@@ -171,9 +396,12 @@ impl Qubo {
         self.eval(&x_f64)
     }
 
-    /// Return the number of variables in the QUBO
+    /// Given a binary point, x, calculate the objective function value of the QUBO without converting x to floats
     ///
-    /// Example of getting the number of variables in a QUBO:
+    /// As x is binary, the objective is the sum of the terms where all the included variables are one, so the terms
+    /// are accumulated directly from the sparse matrix.
+    ///
+    /// Example of calculating the objective function value of a QUBO at a binary point:
     /// ```rust
     /// use hercules::qubo::Qubo;
     /// use ndarray::Array1;
@@ -182,292 +410,376 @@ impl Qubo {
     /// let q = CsMat::<f64>::eye(3);
     /// let c = Array1::<f64>::zeros(3);
     /// let p = Qubo::new_with_c(q, c);
+    /// let x_0 = Array1::from_vec(vec![1, 0, 1]);
     ///
-    /// let num_x = p.num_x();
+    /// let obj = p.eval_binary(&x_0);
     /// ```
-    pub fn num_x(&self) -> usize {
-        self.q.cols()
+    pub fn eval_binary(&self, x: &Array1<usize>) -> f64 {
+        // only the terms with x_i = x_j = 1 are included, so the stored vectors of the variables that are zero are
+        // skipped, and inside of a vector multiplying by the binary value is cheaper than branching on it
+        let mut quad_term = 0.0;
+        for (outer, inner_vec) in self.q.outer_iterator().enumerate() {
+            if x[outer] == 1 {
+                quad_term += inner_vec
+                    .iter()
+                    .map(|(inner, &value)| value * x[inner] as f64)
+                    .sum::<f64>();
+            }
+        }
+
+        let mut linear_term = 0.0;
+        for (i, &value) in self.c.iter().enumerate() {
+            if x[i] == 1 {
+                linear_term += value;
+            }
+        }
+
+        0.5 * quad_term + linear_term + self.offset
     }
 
-    /// Given an initial point, x, calculate the gradient of the QUBO (at that point)
+    /// Given a batch of binary points, calculate the objective function value of the QUBO at each of them
     ///
-    /// Example of calculating the gradient of a QUBO:
+    /// Each point is evaluated with `eval_binary`, so no floating point copy of the points is made, and the points are
+    /// evaluated in parallel over the batch.
+    ///
+    /// Example of evaluating a population of points:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use hercules::initial_points::generate_random_binary_points;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///   generator: JsfLarge::default(),
+    /// };
+    /// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+    /// let xs = generate_random_binary_points(p.num_x(), 20, &mut prng);
+    ///
+    /// let objs = p.eval_many(&xs);
+    /// ```
+    pub fn eval_many(&self, xs: &[Array1<usize>]) -> Vec<f64> {
+        xs.par_iter().map(|x| self.eval_binary(x)).collect()
+    }
+
+    /// Given a binary point, x, calculate the exact change in the objective from flipping $x_i$
+    ///
+    /// With $d_i = 1 - 2x_i$ the direction of the flip, the change in the objective is
+    ///
+    /// $$\Delta_i = d_i \left(c_i + \frac{1}{2}Q_{ii} + \frac{1}{2}\sum_{k \neq i} (Q_{ik} + Q_{ki}) x_k\right)$$
+    ///
+    /// The Hessian must be symmetric, as in the form from `make_symmetric`, so that $Q_{ki} = Q_{ik}$ and only the
+    /// outer view of i is read, which is row i of a CSR matrix and column i of a CSC matrix. So the cost is proportional
+    /// to the nonzeros in row i, rather than a full evaluation of the objective. For a Hessian that is not symmetric,
+    /// the result is not the change in the objective.
+    ///
+    /// Example of calculating the change in objective from a flip:
     /// ```rust
     /// use hercules::qubo::Qubo;
     /// use ndarray::Array1;
     /// use sprs::CsMat;
     ///
     /// let q = CsMat::<f64>::eye(3);
-    /// let c = Array1::<f64>::zeros(3);
+    /// let c = Array1::<f64>::from_vec(vec![-1.0, 0.0, 1.0]);
     /// let p = Qubo::new_with_c(q, c);
+    /// let x_0 = Array1::from_vec(vec![1, 0, 1]);
     ///
-    /// let x_0 = Array1::from_vec(vec![1.0, 0.0, 1.0]);
-    /// let grad = p.eval_grad(&x_0);
+    /// // flipping x_0 to zero removes the -1.0 + 0.5 contribution of x_0
+    /// let delta = p.flip_delta(&x_0, 0);
     /// ```
-    pub fn eval_grad(&self, x: &Array1<f64>) -> Array1<f64> {
-        // takes the gradient of the QUBO at x, does not assume that the QUBO is symmetric
-        0.5 * (&self.q * x + &self.q.transpose_view() * x) + &self.c
-    }
+    pub fn flip_delta(&self, x: &Array1<usize>, i: usize) -> f64 {
+        // with a symmetric Hessian, Q_ik + Q_ki is twice the term in the outer view
+        let (diagonal, coupling) = self.outer_diagonal_and_coupling(x, i);
 
-    pub fn eval_grad_usize(&self, x: &Array1<usize>) -> Array1<f64> {
-        // takes the gradient of the QUBO at x, does not assume that the QUBO is symmetric
-        let x_f64 = x.mapv(|x| x as f64);
-        self.eval_grad(&x_f64)
+        let direction = 1.0 - 2.0 * x[i] as f64;
+        direction * (self.c[i] + 0.5 * diagonal + coupling)
     }
 
-    /// Computes the optimal solution of the relaxed QUBO problem where x* = \alpha. From Boros2007.
+    /// Given a binary point, x, calculate the contribution of $x_i$ to the objective, which is zero if $x_i = 0$ and
+    /// otherwise its linear term plus half of each of its interactions with the other variables that are one
     ///
-    /// Assuming all variables take the same value, find the minimizing value of alpha
-    /// \alpha = \argmax_{\lambda \in [0, 1]} \lambda(\sum_i c_i + \lambda \sum_i \sum_j q_{ij}
+    /// $$m_i = x_i \left(c_i + \frac{1}{2}Q_{ii} + \frac{1}{4}\sum_{k \neq i} (Q_{ik} + Q_{ki}) x_k\right)$$
     ///
-    /// Example of calculating the optimal solution of the relaxed QUBO problem:
+    /// Each interaction is split evenly between its two variables, so the contributions of all the variables sum to the
+    /// objective minus the offset. Unlike `flip_delta`, this is the current contribution and not the change from
+    /// flipping the variable.
+    ///
+    /// Example of finding the variable that contributes the most to the objective:
     /// ```rust
     /// use hercules::qubo::Qubo;
-    /// use ndarray::Array1;
-    /// use sprs::CsMat;
+    /// use smolprng::{PRNG, JsfLarge};
+    /// use hercules::initial_points;
     ///
-    /// let q = CsMat::<f64>::eye(3);
-    /// let c = Array1::<f64>::zeros(3);
-    /// let p = Qubo::new_with_c(q, c);
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
     ///
-    /// let alpha = p.alpha();
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+    ///
+    /// let worst = (0..p.num_x()).max_by(|&i, &j| p.marginal(&x_0, i).total_cmp(&p.marginal(&x_0, j)));
     /// ```
-    pub fn alpha(&self) -> f64 {
-        // find sum of all elements of q
-        let q_sum = self.q.data().iter().sum::<f64>();
-        let c_sum = self.c.sum();
+    pub fn marginal(&self, x: &Array1<usize>, i: usize) -> f64 {
+        if x[i] == 0 {
+            return 0.0;
+        }
 
-        // in the case of q_sum == 0
-        if q_sum == 0.0 {
-            return match c_sum > 0.0 {
-                true => 1.0,
-                false => 0.0,
-            };
+        let (diagonal, coupling) = self.diagonal_and_coupling(x, i);
+        self.c[i] + 0.5 * diagonal + 0.25 * coupling
+    }
+
+    /// Helper function to find $Q_{ii}$ and $\sum_{k \neq i} (Q_{ik} + Q_{ki}) x_k$ at a binary point
+    fn diagonal_and_coupling(&self, x: &Array1<usize>, i: usize) -> (f64, f64) {
+        let (diagonal, mut coupling) = self.outer_diagonal_and_coupling(x, i);
+
+        // the terms in the other direction, which are in the outer views of the variables that are set to one
+        for (k, &x_k) in x.iter().enumerate() {
+            if k != i && x_k == 1 {
+                if let Some(&value) = self.q.get_outer_inner(k, i) {
+                    coupling += value;
+                }
+            }
         }
 
-        // solve for the optimal solution of the 1D relaxed QUBO problem
-        let alpha = -0.5 * c_sum / q_sum;
+        (diagonal, coupling)
+    }
 
-        // return the optimal solution within bounds
-        alpha.clamp(0.0, 1.0)
+    /// Helper function to find $Q_{ii}$ and the sum of the terms in the outer view of i at the variables that are one,
+    /// which is $\sum_{k \neq i} Q_{ik} x_k$ for CSR or $\sum_{k \neq i} Q_{ki} x_k$ for CSC
+    fn outer_diagonal_and_coupling(&self, x: &Array1<usize>, i: usize) -> (f64, f64) {
+        let mut diagonal = 0.0;
+        let mut coupling = 0.0;
+
+        if let Some(outer) = self.q.outer_view(i) {
+            for (k, &value) in outer.iter() {
+                if k == i {
+                    diagonal = value;
+                } else if x[k] == 1 {
+                    coupling += value;
+                }
+            }
+        }
+
+        (diagonal, coupling)
     }
 
-    /// Computes computes rho, the starting point heuristic from Boros2007
+    /// Iterates over the single flip neighbors of a binary point, x, yielding each variable index along with the
+    /// change in the objective from flipping it. The gradient is computed once, so each neighbor is then O(1), with
     ///
-    /// Example of calculating rho:
+    /// $$\Delta_i = (1 - 2x_i) \nabla f(x)_i + 0.5 Q_{ii}$$
+    ///
+    /// Example of finding the best single flip:
     /// ```rust
     /// use hercules::qubo::Qubo;
-    /// use ndarray::Array1;
-    /// use sprs::CsMat;
+    /// use smolprng::{PRNG, JsfLarge};
+    /// use hercules::initial_points;
     ///
-    /// let q = CsMat::<f64>::eye(3);
-    /// let c = Array1::<f64>::zeros(3);
-    /// let p = Qubo::new_with_c(q, c);
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
     ///
-    /// let rho = p.rho();
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+    ///
+    /// let best_flip = p.neighbors(&x_0).min_by(|a, b| a.1.total_cmp(&b.1));
     /// ```
-    pub fn rho(&self) -> f64 {
-        // calculate the terms q+,q-,c+,and c- from boros2007
-        let q_plus: f64 = self.q.data().iter().filter(|x| **x > 0.0).sum();
-        let q_minus: f64 = self.q.data().iter().filter(|x| **x < 0.0).sum();
-        let c_plus: f64 = self.c.iter().filter(|x| **x > 0.0).sum();
-        let c_minus: f64 = self.c.iter().filter(|x| **x < 0.0).sum();
-
-        let p = q_plus + c_plus;
-        let n = q_minus + c_minus;
+    pub fn neighbors<'a>(
+        &'a self,
+        x: &'a Array1<usize>,
+    ) -> impl Iterator<Item = (usize, f64)> + 'a {
+        let grad = self.eval_grad_usize(x);
+        let q_diag = self.q.diag().to_dense();
 
-        let rho = p / (p - n);
-        rho
+        (0..self.num_x()).map(move |i| {
+            let direction = 1.0 - 2.0 * x[i] as f64;
+            (i, direction * grad[i] + 0.5 * q_diag[i])
+        })
     }
 
-    /// Computes the complexity of the QUBO problem.
+    /// Calculates a lower and upper bound on the objective over all binary points, by summing the smallest and largest
+    /// possible contribution of each term independently. These bounds are loose, but only cost a pass over the
+    /// nonzeros.
     ///
-    /// The complexity is defined as the number of non-zero elements in the QUBO matrix plus the number of non-zero elements in the linear coefficients.
+    /// As $x_i^2 = x_i$ for binary x, the diagonal of Q is combined with the linear term, so that each variable is
+    /// counted exactly once as $(c_i + 0.5 Q_{ii}) x_i$. Every off-diagonal entry contributes $0.5 Q_{ij} x_i x_j$.
     ///
-    /// Example of calculating the complexity of a QUBO:
+    /// Example of calculating the trivial bounds of a QUBO:
     /// ```rust
     /// use hercules::qubo::Qubo;
-    /// use ndarray::Array1;
-    /// use sprs::CsMat;
+    /// use smolprng::{PRNG, JsfLarge};
     ///
-    /// let q = CsMat::<f64>::eye(3);
-    /// let c = Array1::<f64>::zeros(3);
-    /// let p = Qubo::new_with_c(q, c);
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
     ///
-    /// let complexity = p.complexity(); //(Array of size 3)
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let (lower_bound, upper_bound) = p.trivial_bounds();
     /// ```
-    pub fn complexity(&self) -> Array1<usize> {
-        // set up a zeroed buffer
-        let mut w = Array1::<usize>::zeros(self.num_x());
+    pub fn trivial_bounds(&self) -> (f64, f64) {
+        let mut linear = self.c.clone();
+        let mut lower_bound = self.offset;
+        let mut upper_bound = self.offset;
 
-        // for each nonzero Q_ij, increment the corresponding w_i and w_j
-        for (value, (i, j)) in &self.q {
-            if *value != 0.0f64 {
-                w[i] += 1;
-                w[j] += 1;
+        for (&value, (i, j)) in &self.q {
+            if i == j {
+                linear[i] += 0.5 * value;
+            } else {
+                lower_bound += (0.5 * value).min(0.0);
+                upper_bound += (0.5 * value).max(0.0);
             }
         }
 
-        // for each nonzero c_i, increment the corresponding w_i
-        for (i, value) in self.c.iter().enumerate() {
-            if *value != 0.0f64 {
-                w[i] += 1;
-            }
+        for &value in &linear {
+            lower_bound += value.min(0.0);
+            upper_bound += value.max(0.0);
         }
 
-        w
+        (lower_bound, upper_bound)
     }
 
-    /// Writes the QUBO to a file in the ORL problem format
+    /// Returns the number of stored nonzero entries of the Hessian
     ///
-    /// Example of writing a QUBO to a file:
+    /// Example of getting the number of nonzeros of a QUBO:
     /// ```rust
     /// use hercules::qubo::Qubo;
-    /// use smolprng::{PRNG, JsfLarge};
+    /// use sprs::CsMat;
     ///
-    /// let mut prng = PRNG {
-    ///   generator: JsfLarge::default(),
-    /// };
-    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.01);
-    /// p.write_qubo("test.qubo");
+    /// let p = Qubo::new(CsMat::<f64>::eye(3));
+    /// assert_eq!(p.nnz(), 3);
     /// ```
+    pub fn nnz(&self) -> usize {
+        self.q.nnz()
+    }
+
+    /// Returns the fraction of the entries of the Hessian that are stored, $\text{nnz} / n^2$, which is zero for a
+    /// QUBO with no variables
     ///
-    /// # Panics
+    /// Example of getting the density of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use sprs::CsMat;
     ///
-    /// Will panics if it is not possible to write to the file.
-    pub fn write_qubo(&self, filename: &str) {
-        // open the file, create file writer
-        let file = std::fs::File::create(filename).unwrap();
-        let mut writer = std::io::BufWriter::new(file);
-
-        // write the number of variables
-        writeln!(writer, "{}", self.num_x()).unwrap();
-
-        // for every nonzero Q_ij, write the indices and the value
-        for (value, (i, j)) in &self.q {
-            writeln!(writer, "{i} {j} {value}").unwrap();
+    /// let p = Qubo::new(CsMat::<f64>::eye(4));
+    /// assert_eq!(p.density(), 0.25);
+    /// ```
+    pub fn density(&self) -> f64 {
+        let num_x = self.num_x();
+        if num_x == 0 {
+            return 0.0;
         }
 
-        // for every nonzero c_i, write the index and the value
-        for i in 0..self.num_x() {
-            let value = self.c[i];
-            if value != 0.0 {
-                writeln!(writer, "{i} {value}").unwrap();
-            }
-        }
+        self.nnz() as f64 / (num_x * num_x) as f64
     }
 
-    /// Reads a QUBO from a file in the ORL problem format
+    /// Calculates the degree of each variable, the number of other variables it has a nonzero quadratic interaction
+    /// with.
     ///
-    /// Example of reading a QUBO from a file:
+    /// The diagonal is not counted, as $x_i^2 = x_i$ makes $Q_{ii}$ a linear term rather than an interaction. The
+    /// interaction between $x_i$ and $x_j$ is $0.5(Q_{ij} + Q_{ji})$, so a pair is counted once if either entry is
+    /// stored, and not at all if the two entries cancel.
+    ///
+    /// Example of finding the degrees of the variables of a QUBO:
     /// ```rust
     /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
     ///
-    /// let p = Qubo::read_qubo("test.qubo");
-    /// ```
-    ///
-    /// # Panics
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
     ///
-    /// Will panic if there is not a file at the given filename in the .qubo format.
-    #[must_use]
-    pub fn read_qubo(filename: &str) -> Self {
-        // open the file
-        let file = std::fs::File::open(filename).unwrap();
-        let mut reader = std::io::BufReader::new(file);
-
-        // get the number of variables
-        let mut line = String::new();
-        reader.read_line(&mut line).unwrap();
-        let num_x = line.trim().parse::<usize>().unwrap();
-
-        line = String::new();
-        // set up the sparse matrix and dense vector
-        let mut q = TriMat::<f64>::new((num_x, num_x));
-        let mut c = Array1::<f64>::zeros(num_x);
-
-        // read the file
-        while reader.read_line(&mut line).unwrap() > 0 {
-            let row_data: Vec<_> = line.split_whitespace().collect();
-
-            // we add to the column vector if there are only two elements
-            if row_data.len() == 2 {
-                let i = row_data[0].parse::<usize>().unwrap();
-                let value = row_data[1].parse::<f64>().unwrap();
-                c[i] = value;
-            }
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let degrees = p.variable_degrees();
+    /// ```
+    pub fn variable_degrees(&self) -> Array1<usize> {
+        let q_sym = self.make_symmetric();
+        let mut degrees = Array1::<usize>::zeros(self.num_x());
 
-            // otherwise, we add to the sparse matrix
-            if row_data.len() == 3 {
-                let i = row_data[0].parse::<usize>().unwrap();
-                let j = row_data[1].parse::<usize>().unwrap();
-                let value = row_data[2].parse::<f64>().unwrap();
-                q.add_triplet(i, j, value);
+        for (&value, (i, j)) in &q_sym.q {
+            if i != j && value != 0.0 {
+                degrees[i] += 1;
             }
-
-            // reset the line
-            line = String::new();
         }
 
-        Self::new_with_c(q.to_csr(), c)
+        degrees
     }
 
-    /// Generates a Symmetric QUBO from the current QUBO
+    /// Iterates over the quadratic terms of the QUBO as (i, j, $Q_{ij}$), where each term contributes
+    /// $0.5 Q_{ij} x_i x_j$ to the objective.
     ///
-    /// Example of making a QUBO symmetric:
+    /// Every stored entry of the Hessian is returned once, in its storage order, which is by row for the QUBOs made by
+    /// this crate. Entries that are not returned are zero. The Hessian is not assumed to be symmetric, so $Q_{ij}$ and $Q_{ji}$ can both be
+    /// returned.
+    ///
+    /// Example of summing the quadratic terms of a QUBO:
     /// ```rust
     /// use hercules::qubo::Qubo;
     /// use smolprng::{PRNG, JsfLarge};
     ///
-    /// // make a random number generator
     /// let mut prng = PRNG {
     ///     generator: JsfLarge::default(),
     /// };
     ///
-    /// // make a random QUBO of 50 variables with an approximate sparsity of 0.1
     /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
-    ///
-    /// // make a symmetric QUBO from this QUBO
-    /// let p_sym = p.make_symmetric();
+    /// let total = p.quadratic_terms().map(|(_, _, value)| value).sum::<f64>();
     /// ```
-    #[must_use]
-    pub fn make_symmetric(&self) -> Self {
-        let mut tri_q = TriMat::<f64>::new((self.num_x(), self.num_x()));
-
-        let c = self.c.clone();
-
-        for (&value, (i, j)) in &self.q {
-            if i == j {
-                tri_q.add_triplet(i, j, value);
-            } else {
-                tri_q.add_triplet(j, i, 0.5 * value);
-                tri_q.add_triplet(i, j, 0.5 * value);
-            }
-        }
+    pub fn quadratic_terms(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.q.iter().map(|(&value, (i, j))| (i, j, value))
+    }
 
-        Self::new_with_c(tri_q.to_csr(), c)
+    /// Iterates over the nonzero linear terms of the QUBO as (i, $c_i$), in order of the variables, where each term
+    /// contributes $c_i x_i$ to the objective
+    ///
+    /// Example of finding the variables with a negative linear term:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let negative = p.linear_terms().filter(|&(_, value)| value < 0.0).count();
+    /// ```
+    pub fn linear_terms(&self) -> impl Iterator<Item = (usize, f64)> + '_ {
+        self.c
+            .iter()
+            .enumerate()
+            .filter(|(_, &value)| value != 0.0)
+            .map(|(i, &value)| (i, value))
     }
 
-    /// Convexifies the QUBO problem by modifying the Hessian and linear coefficients,rendering a convex problem.
+    /// Returns the linear term of the QUBO, c, as a dense vector with one entry for each variable
     ///
-    /// Currently, assume that the required factor,'s' is known.
+    /// Example of getting the linear term of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::CsMat;
     ///
-    /// $$\frac{1}{2}x^TQx + c^Tx = \frac{1}{2}x^T(Q + sI)x + c^Tx - 0.5s^Tx$$
-    #[must_use]
-    pub fn make_diag_transform(&self, s: f64) -> Self {
-        // generate the scaled (sparse) identity matrix
-        let mut s_eye_tri = TriMat::<f64>::new((self.num_x(), self.num_x()));
-        for i in 0..self.num_x() {
-            s_eye_tri.add_triplet(i, i, s);
-        }
-        let s_eye = s_eye_tri.to_csr();
+    /// let p = Qubo::new_with_c(CsMat::<f64>::eye(3), Array1::from_vec(vec![1.0, 0.0, -2.0]));
+    /// assert_eq!(p.linear()[2], -2.0);
+    /// ```
+    pub const fn linear(&self) -> &Array1<f64> {
+        &self.c
+    }
 
-        Self::new_with_c(&self.q + &s_eye, self.c.clone() - 0.5 * s)
+    /// Returns the number of variables with a nonzero linear term
+    ///
+    /// Example of counting the nonzero linear terms of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::CsMat;
+    ///
+    /// let p = Qubo::new_with_c(CsMat::<f64>::eye(3), Array1::from_vec(vec![1.0, 0.0, -2.0]));
+    /// assert_eq!(p.num_nonzero_linear(), 2);
+    /// ```
+    pub fn num_nonzero_linear(&self) -> usize {
+        self.linear_terms().count()
     }
 
-    /// Calculates the eigenvalues of the QUBO Hessian matrix this is a somewhat expensive operation.
-    /// Converts the QUBO to a dense matrix and then calculates the eigenvalues. Assume that the QUBO is symmetric.
+    /// Return the number of variables in the QUBO
     ///
-    /// Example of calculating the eigenvalues of a QUBO:
+    /// Example of getting the number of variables in a QUBO:
     /// ```rust
     /// use hercules::qubo::Qubo;
     /// use ndarray::Array1;
@@ -477,258 +789,3089 @@ impl Qubo {
     /// let c = Array1::<f64>::zeros(3);
     /// let p = Qubo::new_with_c(q, c);
     ///
-    /// let eigs = p.hess_eigenvalues();
+    /// let num_x = p.num_x();
     /// ```
-    ///
-    /// # Panics
-    ///  If the eigenvalue calculation fails, The only panic annotation from the ndarray_linalg crate is when the matrix is non-square
-    pub fn hess_eigenvalues(&self) -> Array1<f64> {
-        let q_dense = self.q.to_dense();
-        let (eigs, _) = q_dense.eigh(UPLO::Upper).unwrap();
-        eigs
+    pub fn num_x(&self) -> usize {
+        self.q.cols()
     }
 
-    /// Creates the convex symmetric form of the QUBO problem. This is an exact operation, and results in a convex symmetric matrix that is equivalent to the original QUBO.
-    #[must_use]
-    pub fn convex_symmetric_form(&self) -> Self {
-        // make the QUBO symmetric
-        let p_sym = self.make_symmetric();
-
-        // calculate the eigenvalues of the QUBO
-        let eigs = p_sym.hess_eigenvalues();
-
-        // find the minimum eigenvalue, create a factor that scales the minimum eigenvalue to 1
-        let min_eig = eigs.iter().fold(f64::INFINITY, |acc, &x| x.min(acc));
-        let s = 0.001 - min_eig;
+    /// Given an initial point, x, calculate the gradient of the QUBO (at that point)
+    ///
+    /// Example of calculating the gradient of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::CsMat;
+    ///
+    /// let q = CsMat::<f64>::eye(3);
+    /// let c = Array1::<f64>::zeros(3);
+    /// let p = Qubo::new_with_c(q, c);
+    ///
+    /// let x_0 = Array1::from_vec(vec![1.0, 0.0, 1.0]);
+    /// let grad = p.eval_grad(&x_0);
+    /// ```
+    pub fn eval_grad(&self, x: &Array1<f64>) -> Array1<f64> {
+        // takes the gradient of the QUBO at x, does not assume that the QUBO is symmetric
+        0.5 * (&self.q * x + &self.q.transpose_view() * x) + &self.c
+    }
 
-        // make the QUBO convex
-        p_sym.make_diag_transform(s)
+    pub fn eval_grad_usize(&self, x: &Array1<usize>) -> Array1<f64> {
+        // takes the gradient of the QUBO at x, does not assume that the QUBO is symmetric
+        let x_f64 = x.mapv(|x| x as f64);
+        self.eval_grad(&x_f64)
     }
 
-    /// Creates the Hessian only equivalent form of the QUBO. Where the linear term is adsorbed into
-    /// The Hessian matrix. This is an exact operation, and generates an equivalent form.
+    /// Computes the optimal solution of the relaxed QUBO problem where x* = \alpha. From Boros2007.
     ///
-    /// $$ 0.5 x^T Q x + c^Tx = 0.5 x^T (Q + 2diag(c)) x $$
-    // pub fn hessian_only_form(&self) -> Self {
-    //
-    // }
-
-    /// Checks if the QUBO is symmetric
-    pub fn is_symmetric(&self) -> bool {
-        let error_margin = f64::EPSILON;
+    /// Assuming all variables take the same value, find the minimizing value of alpha
+    /// \alpha = \argmax_{\lambda \in [0, 1]} \lambda(\sum_i c_i + \lambda \sum_i \sum_j q_{ij}
+    ///
+    /// Example of calculating the optimal solution of the relaxed QUBO problem:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::CsMat;
+    ///
+    /// let q = CsMat::<f64>::eye(3);
+    /// let c = Array1::<f64>::zeros(3);
+    /// let p = Qubo::new_with_c(q, c);
+    ///
+    /// let alpha = p.alpha();
+    /// ```
+    pub fn alpha(&self) -> f64 {
+        // find sum of all elements of q
+        let q_sum = self.q.data().iter().sum::<f64>();
+        let c_sum = self.c.sum();
 
-        for (&q_ij, (i, j)) in &self.q {
-            let q_ji = self.q.get(j, i);
-            match q_ji {
-                Some(&q_ji) => {
-                    if (q_ij - q_ji).abs() > error_margin {
-                        return false;
-                    }
-                }
-                None => {
-                    return false;
-                }
-            }
+        // in the case of q_sum == 0
+        if q_sum == 0.0 {
+            return match c_sum > 0.0 {
+                true => 1.0,
+                false => 0.0,
+            };
         }
 
-        true
-    }
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-    use crate::initial_points::generate_random_binary_points;
-    use crate::tests::{make_solver_qubo, make_test_prng};
-    use ndarray::Array1;
-    use sprs::CsMat;
-
-    #[test]
-    fn test_qubo_new() {
-        let q = CsMat::<f64>::eye(10);
-        let p = Qubo::new(q);
+        // solve for the optimal solution of the 1D relaxed QUBO problem
+        let alpha = -0.5 * c_sum / q_sum;
 
-        assert_eq!(p.num_x(), 10);
-        assert_eq!(p.q.nnz(), 10);
-        assert_eq!(p.c.len(), 10);
+        // return the optimal solution within bounds
+        alpha.clamp(0.0, 1.0)
     }
 
-    #[test]
-    fn test_qubo_new_with_c() {
-        let q = CsMat::<f64>::eye(10);
-        let c = Array1::<f64>::zeros(10);
-        let p = Qubo::new_with_c(q, c);
+    /// Computes computes rho, the starting point heuristic from Boros2007
+    ///
+    /// Example of calculating rho:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::CsMat;
+    ///
+    /// let q = CsMat::<f64>::eye(3);
+    /// let c = Array1::<f64>::zeros(3);
+    /// let p = Qubo::new_with_c(q, c);
+    ///
+    /// let rho = p.rho();
+    /// ```
+    pub fn rho(&self) -> f64 {
+        // calculate the terms q+,q-,c+,and c- from boros2007
+        let q_plus: f64 = self.q.data().iter().filter(|x| **x > 0.0).sum();
+        let q_minus: f64 = self.q.data().iter().filter(|x| **x < 0.0).sum();
+        let c_plus: f64 = self.c.iter().filter(|x| **x > 0.0).sum();
+        let c_minus: f64 = self.c.iter().filter(|x| **x < 0.0).sum();
 
-        assert_eq!(p.num_x(), 10);
-        assert_eq!(p.q.nnz(), 10);
-        assert_eq!(p.c.len(), 10);
+        let p = q_plus + c_plus;
+        let n = q_minus + c_minus;
+
+        let rho = p / (p - n);
+        rho
     }
 
-    #[test]
+    /// Computes the complexity of the QUBO problem.
+    ///
+    /// The complexity is defined as the number of non-zero elements in the QUBO matrix plus the number of non-zero elements in the linear coefficients.
+    ///
+    /// Example of calculating the complexity of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::CsMat;
+    ///
+    /// let q = CsMat::<f64>::eye(3);
+    /// let c = Array1::<f64>::zeros(3);
+    /// let p = Qubo::new_with_c(q, c);
+    ///
+    /// let complexity = p.complexity(); //(Array of size 3)
+    /// ```
+    pub fn complexity(&self) -> Array1<usize> {
+        // set up a zeroed buffer
+        let mut w = Array1::<usize>::zeros(self.num_x());
+
+        // for each nonzero Q_ij, increment the corresponding w_i and w_j
+        for (value, (i, j)) in &self.q {
+            if *value != 0.0f64 {
+                w[i] += 1;
+                w[j] += 1;
+            }
+        }
+
+        // for each nonzero c_i, increment the corresponding w_i
+        for (i, value) in self.c.iter().enumerate() {
+            if *value != 0.0f64 {
+                w[i] += 1;
+            }
+        }
+
+        w
+    }
+
+    /// Writes the QUBO to a file in the ORL problem format, which has no constant term, so the offset is not written
+    ///
+    /// Example of writing a QUBO to a file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///   generator: JsfLarge::default(),
+    /// };
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.01);
+    /// p.write_qubo("test.qubo");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panics if it is not possible to write to the file.
+    pub fn write_qubo(&self, filename: &str) {
+        // open the file, create file writer
+        let file = std::fs::File::create(filename).unwrap();
+        let mut writer = std::io::BufWriter::new(file);
+
+        // write the number of variables
+        writeln!(writer, "{}", self.num_x()).unwrap();
+
+        // for every nonzero Q_ij, write the indices and the value
+        for (value, (i, j)) in &self.q {
+            writeln!(writer, "{i} {j} {value}").unwrap();
+        }
+
+        // for every nonzero c_i, write the index and the value
+        for i in 0..self.num_x() {
+            let value = self.c[i];
+            if value != 0.0 {
+                writeln!(writer, "{i} {value}").unwrap();
+            }
+        }
+    }
+
+    /// Reads a QUBO from a file in the ORL problem format
+    ///
+    /// Example of reading a QUBO from a file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    ///
+    /// let p = Qubo::read_qubo("test.qubo");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there is not a file at the given filename in the .qubo format.
+    #[must_use]
+    pub fn read_qubo(filename: &str) -> Self {
+        // open the file
+        let file = std::fs::File::open(filename).unwrap();
+        let mut reader = std::io::BufReader::new(file);
+
+        // get the number of variables
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let num_x = line.trim().parse::<usize>().unwrap();
+
+        line = String::new();
+        // set up the sparse matrix and dense vector
+        let mut q = TriMat::<f64>::new((num_x, num_x));
+        let mut c = Array1::<f64>::zeros(num_x);
+
+        // read the file
+        while reader.read_line(&mut line).unwrap() > 0 {
+            let row_data: Vec<_> = line.split_whitespace().collect();
+
+            // we add to the column vector if there are only two elements
+            if row_data.len() == 2 {
+                let i = row_data[0].parse::<usize>().unwrap();
+                let value = row_data[1].parse::<f64>().unwrap();
+                c[i] = value;
+            }
+
+            // otherwise, we add to the sparse matrix
+            if row_data.len() == 3 {
+                let i = row_data[0].parse::<usize>().unwrap();
+                let j = row_data[1].parse::<usize>().unwrap();
+                let value = row_data[2].parse::<f64>().unwrap();
+                q.add_triplet(i, j, value);
+            }
+
+            // reset the line
+            line = String::new();
+        }
+
+        Self::new_with_c(q.to_csr(), c)
+    }
+
+    /// Reads a QUBO from a file in the QPLIB format
+    ///
+    /// Only problems with binary variables and no constraints are supported. QPLIB uses 1-based indices and only
+    /// stores the lower triangle of the (symmetric) Hessian, so the indices are shifted to be 0-based and the
+    /// off-diagonal terms are mirrored. Maximization problems are negated, and the objective constant is the offset.
+    ///
+    /// Example of reading a QUBO from a QPLIB file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use std::path::Path;
+    ///
+    /// let p = Qubo::read_qplib(Path::new("QPLIB_3506.qplib"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can not be read, is not in the QPLIB format, or describes a problem with
+    /// non-binary variables or constraints.
+    pub fn read_qplib(path: &Path) -> Result<Self, QuboError> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+
+        // strip the comments and empty lines, so that we only have the data left
+        let mut lines = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            let data = line.split('#').next().unwrap_or_default().trim();
+            if !data.is_empty() {
+                lines.push(data.to_string());
+            }
+        }
+        let mut lines = lines.iter().map(String::as_str);
+
+        // the first line is the problem name, which we do not need
+        next_qplib_line(&mut lines, "problem name")?;
+
+        // the problem type is a three letter code of the objective, variable, and constraint types
+        let problem_type = next_qplib_line(&mut lines, "problem type")?.to_uppercase();
+        let type_codes: Vec<char> = problem_type.chars().collect();
+        if type_codes.len() != 3 {
+            return Err(QuboError::Parse(format!(
+                "invalid problem type {problem_type}"
+            )));
+        }
+        if type_codes[1] != 'B' {
+            return Err(QuboError::Unsupported(format!(
+                "problem type {problem_type} does not have binary variables"
+            )));
+        }
+        if !matches!(type_codes[2], 'N' | 'B') {
+            return Err(QuboError::Unsupported(format!(
+                "problem type {problem_type} has constraints"
+            )));
+        }
+
+        // if it is a maximization problem, then we negate the objective
+        let sense = next_qplib_line(&mut lines, "objective sense")?.to_lowercase();
+        let scale = match sense.as_str() {
+            "minimize" => 1.0,
+            "maximize" => -1.0,
+            _ => return Err(QuboError::Parse(format!("invalid objective sense {sense}"))),
+        };
+
+        let num_x: usize = next_qplib_value(&mut lines, "number of variables")?;
+
+        // read the quadratic terms, these are only the lower triangle of the hessian
+        let num_q: usize = next_qplib_value(&mut lines, "number of quadratic terms")?;
+        let mut q = TriMat::<f64>::new((num_x, num_x));
+
+        for _ in 0..num_q {
+            let mut tokens = next_qplib_line(&mut lines, "quadratic term")?.split_whitespace();
+            let i = one_based_index(parse_token(tokens.next(), "quadratic term")?, num_x)?;
+            let j = one_based_index(parse_token(tokens.next(), "quadratic term")?, num_x)?;
+            let value: f64 = parse_token(tokens.next(), "quadratic term")?;
+
+            q.add_triplet(i, j, scale * value);
+            if i != j {
+                q.add_triplet(j, i, scale * value);
+            }
+        }
+
+        // read the linear terms, which are given as a default value and then the non-default values
+        let default_c: f64 = next_qplib_value(&mut lines, "default linear term")?;
+        let mut c = Array1::<f64>::from_elem(num_x, scale * default_c);
+
+        let num_c: usize = next_qplib_value(&mut lines, "number of linear terms")?;
+        for _ in 0..num_c {
+            let mut tokens = next_qplib_line(&mut lines, "linear term")?.split_whitespace();
+            let i = one_based_index(parse_token(tokens.next(), "linear term")?, num_x)?;
+            let value: f64 = parse_token(tokens.next(), "linear term")?;
+            c[i] = scale * value;
+        }
+
+        let offset: f64 = next_qplib_value(&mut lines, "objective constant")?;
+
+        Ok(Self {
+            q: q.to_csr(),
+            c,
+            offset: scale * offset,
+        })
+    }
+
+    /// Reads a graph in the DIMACS edge list format, and creates the QUBO of its max-cut problem
+    ///
+    /// Lines starting with `c` are comments, the problem line is `p edge n m` for a graph with n nodes and m edges,
+    /// and each edge is written as `e u v [w]` with 1-based node indices. If the weight is omitted, it is taken to be
+    /// one. The optimal objective of the QUBO is the negative of the maximum cut, see `generators::max_cut_qubo`.
+    ///
+    /// Example of reading a max-cut QUBO from a DIMACS file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use std::path::Path;
+    ///
+    /// let p = Qubo::from_dimacs_maxcut(Path::new("g05_60.0.mc"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can not be read, if there is no problem line before the edges, or if an edge
+    /// can not be parsed or refers to a node that does not exist.
+    pub fn from_dimacs_maxcut(path: &Path) -> Result<Self, QuboError> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+
+        let mut graph = None;
+        let mut num_edges = 0;
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+
+            match tokens.next() {
+                // empty lines and comments are skipped
+                None | Some("c") => {}
+                Some("p") => {
+                    // the format name, such as edge, is not needed
+                    tokens.next();
+                    let num_nodes: usize = parse_token(tokens.next(), "number of nodes")?;
+                    num_edges = parse_token(tokens.next(), "number of edges")?;
+                    graph = Some(Graph::new(num_nodes));
+                }
+                Some("e") => {
+                    let Some(graph) = graph.as_mut() else {
+                        return Err(QuboError::Parse("edge before the problem line".to_string()));
+                    };
+                    let u: usize = parse_token(tokens.next(), "edge")?;
+                    let v: usize = parse_token(tokens.next(), "edge")?;
+                    let weight = match tokens.next() {
+                        Some(token) => parse_token(Some(token), "edge weight")?,
+                        None => 1.0,
+                    };
+
+                    graph.add_edge(
+                        one_based_index(u, graph.num_nodes)?,
+                        one_based_index(v, graph.num_nodes)?,
+                        weight,
+                    );
+                }
+                Some(token) => {
+                    return Err(QuboError::Parse(format!("unknown line type {token}")));
+                }
+            }
+        }
+
+        let graph = graph.ok_or_else(|| QuboError::Parse("missing problem line".to_string()))?;
+
+        if graph.edges.len() != num_edges {
+            return Err(QuboError::Parse(format!(
+                "expected {num_edges} edges, found {}",
+                graph.edges.len()
+            )));
+        }
+
+        Ok(max_cut_qubo(&graph))
+    }
+
+    /// Reads a weighted Max-2-SAT instance in the DIMACS WCNF format, and creates the QUBO whose objective at each
+    /// binary point is the total weight of the clauses it violates
+    ///
+    /// Lines starting with `c` are comments. In the classic format the problem line is `p wcnf n m [top]`, and each
+    /// clause is written as `w l_1 ... l_k 0`, where the literals are 1-based variable indices that are negative for
+    /// negated variables, and a clause with a weight of at least top is hard. The newer format has no problem line,
+    /// and hard clauses start with `h` in place of the weight. Violating a hard clause costs one more than violating
+    /// every soft clause, so the optimum satisfies the hard clauses if that is possible. Only clauses of at most two
+    /// literals can be reduced to a QUBO, where a clause of one or two literals is violated if
+    ///
+    /// $$ (1 - l_1)(1 - l_2) = 1 $$
+    ///
+    /// with $l = x_i$ or $l = 1 - x_i$, and an empty clause is always violated, so it adds its weight to the offset.
+    ///
+    /// Example of reading a Max-2-SAT QUBO from a WCNF file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use std::path::Path;
+    ///
+    /// let p = Qubo::from_wcnf(Path::new("instance.wcnf"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can not be read or parsed, if a literal refers to a variable outside of the
+    /// problem line, if the number of clauses does not match the problem line, or if a clause has more than two
+    /// literals.
+    pub fn from_wcnf(path: &Path) -> Result<Self, QuboError> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+
+        // the problem line is optional, and gives the number of variables, clauses and the weight of hard clauses
+        let mut problem: Option<(usize, usize, Option<f64>)> = None;
+        let mut clauses: Vec<(Option<f64>, Vec<i64>)> = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+
+            let weight = match tokens.next() {
+                // empty lines and comments are skipped
+                None | Some("c") => continue,
+                Some("p") => {
+                    if tokens.next() != Some("wcnf") {
+                        return Err(QuboError::Parse("expected a wcnf problem line".to_string()));
+                    }
+                    let num_x = parse_token(tokens.next(), "number of variables")?;
+                    let num_clauses = parse_token(tokens.next(), "number of clauses")?;
+                    let top = match tokens.next() {
+                        Some(token) => Some(parse_token(Some(token), "top weight")?),
+                        None => None,
+                    };
+                    problem = Some((num_x, num_clauses, top));
+                    continue;
+                }
+                Some("h") => None,
+                Some(token) => {
+                    let weight: f64 = parse_token(Some(token), "clause weight")?;
+                    let top = problem.and_then(|(_, _, top)| top);
+                    top.is_none_or(|top| weight < top).then_some(weight)
+                }
+            };
+
+            // the literals are terminated by a zero
+            let mut literals = Vec::new();
+            loop {
+                match parse_token::<i64>(tokens.next(), "clause")? {
+                    0 => break,
+                    literal => literals.push(literal),
+                }
+            }
+
+            if literals.len() > 2 {
+                return Err(QuboError::Unsupported(format!(
+                    "clause with {} literals, only clauses of at most 2 literals can be reduced to a QUBO",
+                    literals.len()
+                )));
+            }
+
+            clauses.push((weight, literals));
+        }
+
+        // without a problem line, the number of variables is the largest variable in a clause
+        let num_x = match problem {
+            Some((num_x, num_clauses, _)) => {
+                if clauses.len() != num_clauses {
+                    return Err(QuboError::Parse(format!(
+                        "expected {num_clauses} clauses, found {}",
+                        clauses.len()
+                    )));
+                }
+                num_x
+            }
+            None => clauses
+                .iter()
+                .flat_map(|(_, literals)| literals)
+                .map(|literal| literal.unsigned_abs() as usize)
+                .max()
+                .unwrap_or(0),
+        };
+
+        // violating a hard clause must cost more than violating all the soft clauses
+        let hard_weight = 1.0
+            + clauses
+                .iter()
+                .filter_map(|(weight, _)| *weight)
+                .sum::<f64>();
+
+        max_2sat_qubo(num_x, &clauses, hard_weight)
+    }
+
+    /// Reads a QUBO from a Matrix Market file of the Hessian, and optionally a plaintext file of the linear term
+    ///
+    /// The Hessian must be in the coordinate format, with real, integer or pattern entries, the latter being taken to
+    /// be one. The first line is the banner `%%MatrixMarket matrix coordinate real symmetric`, lines starting with `%`
+    /// are comments, then the size line `n n nnz` is followed by each entry as `i j value` with 1-based indices. If the
+    /// banner is `symmetric`, then only one triangle is stored and the off-diagonal entries are mirrored. The linear
+    /// term file is a list of n whitespace separated values, and is taken to be zero if no file is given.
+    ///
+    /// Example of reading a QUBO from a Matrix Market file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use std::path::Path;
+    ///
+    /// let p = Qubo::from_matrix_market(Path::new("bcsstk01.mtx"), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a file can not be read, if the banner is not for a real coordinate matrix that is
+    /// general or symmetric, if the matrix is not square, or if an entry or the linear term can not be parsed.
+    pub fn from_matrix_market(q_path: &Path, c_path: Option<&Path>) -> Result<Self, QuboError> {
+        let file = std::fs::File::open(q_path)?;
+        let mut lines = std::io::BufReader::new(file).lines();
+
+        // the banner gives the format of the matrix
+        let banner = lines
+            .next()
+            .transpose()?
+            .ok_or_else(|| QuboError::Parse("missing banner".to_string()))?;
+        let banner = banner.to_lowercase();
+        let tokens = banner.split_whitespace().collect::<Vec<_>>();
+
+        if tokens.len() != 5 || tokens[0] != "%%matrixmarket" || tokens[1] != "matrix" {
+            return Err(QuboError::Parse(format!("invalid banner {banner}")));
+        }
+        if tokens[2] != "coordinate" {
+            return Err(QuboError::Unsupported(format!("{} format", tokens[2])));
+        }
+        let is_pattern = match tokens[3] {
+            "real" | "integer" => false,
+            "pattern" => true,
+            field => return Err(QuboError::Unsupported(format!("{field} entries"))),
+        };
+        let is_symmetric = match tokens[4] {
+            "general" => false,
+            "symmetric" => true,
+            symmetry => return Err(QuboError::Unsupported(format!("{symmetry} matrix"))),
+        };
+
+        let mut q = None;
+        let mut num_entries = 0;
+        let mut entries_read = 0;
+
+        for line in lines {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+
+            // empty lines and comments are skipped
+            if line.starts_with('%') || line.trim().is_empty() {
+                continue;
+            }
+
+            let Some(q) = q.as_mut() else {
+                // the first line after the comments is the size of the matrix
+                let num_rows: usize = parse_token(tokens.next(), "number of rows")?;
+                let num_cols: usize = parse_token(tokens.next(), "number of columns")?;
+                num_entries = parse_token(tokens.next(), "number of entries")?;
+
+                if num_rows != num_cols {
+                    return Err(QuboError::Unsupported(format!(
+                        "{num_rows} by {num_cols} matrix is not square"
+                    )));
+                }
+
+                q = Some(TriMat::<f64>::new((num_rows, num_cols)));
+                continue;
+            };
+
+            let num_x = q.rows();
+            let i = one_based_index(parse_token(tokens.next(), "entry")?, num_x)?;
+            let j = one_based_index(parse_token(tokens.next(), "entry")?, num_x)?;
+            let value = if is_pattern {
+                1.0
+            } else {
+                parse_token(tokens.next(), "entry value")?
+            };
+
+            q.add_triplet(i, j, value);
+            if is_symmetric && i != j {
+                q.add_triplet(j, i, value);
+            }
+            entries_read += 1;
+        }
+
+        let q = q.ok_or_else(|| QuboError::Parse("missing size line".to_string()))?;
+
+        if entries_read != num_entries {
+            return Err(QuboError::Parse(format!(
+                "expected {num_entries} entries, found {entries_read}"
+            )));
+        }
+
+        let num_x = q.rows();
+        let c = match c_path {
+            Some(c_path) => {
+                let c = std::fs::read_to_string(c_path)?
+                    .lines()
+                    .filter(|line| !line.starts_with('%'))
+                    .flat_map(str::split_whitespace)
+                    .map(|token| parse_token(Some(token), "linear term"))
+                    .collect::<Result<Vec<f64>, QuboError>>()?;
+
+                if c.len() != num_x {
+                    return Err(QuboError::Parse(format!(
+                        "expected {num_x} linear terms, found {}",
+                        c.len()
+                    )));
+                }
+
+                Array1::from_vec(c)
+            }
+            None => Array1::zeros(num_x),
+        };
+
+        Ok(Self::new_with_c(q.to_csr(), c))
+    }
+
+    /// Reads the first problem of a file in the BQP format of the OR-Library, see `from_orlib_bqp_all`
+    ///
+    /// Example of reading a QUBO from an OR-Library file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use std::path::Path;
+    ///
+    /// let p = Qubo::from_orlib_bqp(Path::new("bqp50.txt"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can not be read, is not in the BQP format, or does not contain any problems.
+    pub fn from_orlib_bqp(path: &Path) -> Result<Self, QuboError> {
+        Self::from_orlib_bqp_all(path)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| QuboError::Parse("the file does not contain any problems".to_string()))
+    }
+
+    /// Reads every problem of a file in the BQP format of the OR-Library (Beasley)
+    ///
+    /// The first value is the number of problems, then each problem is given as the number of variables n and the
+    /// number of nonzeros, followed by each nonzero as `i j value` with 1-based indices. The problems maximize
+    /// $\sum_{ij} q_{ij} x_i x_j$ over a symmetric matrix, where each off-diagonal pair is only listed once. So the
+    /// QUBOs are negated, and the optimal objective of each QUBO is the negative of the best known value. The values
+    /// are read as whitespace separated tokens, so the line breaks do not matter.
+    ///
+    /// Example of reading all the QUBOs from an OR-Library file:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use std::path::Path;
+    ///
+    /// let problems = Qubo::from_orlib_bqp_all(Path::new("bqp50.txt"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can not be read, if a value can not be parsed, if an index is out of bounds,
+    /// or if the file ends before all of the problems are read.
+    pub fn from_orlib_bqp_all(path: &Path) -> Result<Vec<Self>, QuboError> {
+        let data = std::fs::read_to_string(path)?;
+        let mut tokens = data.split_whitespace();
+
+        let num_problems: usize = parse_token(tokens.next(), "number of problems")?;
+        (0..num_problems)
+            .map(|_| parse_orlib_bqp_problem(&mut tokens))
+            .collect()
+    }
+
+    /// Writes the QUBO in the sparse text format, where the objective is $\sum_{ij} w_{ij} x_i x_j$
+    ///
+    /// The first line is the number of variables and the number of nonzeros, then each nonzero is written as
+    /// `i j value` with 0-based indices. As $x_i^2 = x_i$ for binary variables, the diagonal of the Hessian and the
+    /// linear term are combined and written as `i i value`. The format has no constant term, so the offset is not
+    /// written.
+    ///
+    /// Example of writing a QUBO in the sparse format:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///   generator: JsfLarge::default(),
+    /// };
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.01);
+    ///
+    /// let file = std::fs::File::create("test.sparse").unwrap();
+    /// p.write_sparse(std::io::BufWriter::new(file)).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if it is not possible to write to the writer.
+    pub fn write_sparse(&self, mut writer: impl Write) -> std::io::Result<()> {
+        // the diagonal terms are the linear terms, plus the diagonal of the hessian, as x_i^2 = x_i
+        let mut diag = self.c.clone();
+        let mut entries = Vec::new();
+
+        for (&value, (i, j)) in &self.q {
+            if i == j {
+                diag[i] += 0.5 * value;
+            } else if value != 0.0 {
+                entries.push((i, j, 0.5 * value));
+            }
+        }
+
+        for (i, &value) in diag.iter().enumerate() {
+            if value != 0.0 {
+                entries.push((i, i, value));
+            }
+        }
+
+        // write the header, and then each of the nonzeros
+        writeln!(writer, "{} {}", self.num_x(), entries.len())?;
+        for (i, j, value) in entries {
+            writeln!(writer, "{i} {j} {value}")?;
+        }
+
+        writer.flush()
+    }
+
+    /// Reads a QUBO in the sparse text format, as written by `write_sparse`
+    ///
+    /// The diagonal terms are read into the linear term, so the generated QUBO has a zero diagonal but has the same
+    /// objective at every binary point.
+    ///
+    /// Example of reading a QUBO in the sparse format:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    ///
+    /// let file = std::fs::File::open("test.sparse").unwrap();
+    /// let p = Qubo::read_sparse(file).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the reader fails, or if the data is not in the sparse format.
+    pub fn read_sparse(reader: impl std::io::Read) -> Result<Self, QuboError> {
+        let reader = std::io::BufReader::new(reader);
+        let mut lines = reader.lines();
+
+        // read the header with the number of variables and nonzeros
+        let header = lines
+            .next()
+            .ok_or_else(|| QuboError::Parse("missing header".to_string()))??;
+        let mut tokens = header.split_whitespace();
+        let num_x: usize = parse_token(tokens.next(), "number of variables")?;
+        let num_entries: usize = parse_token(tokens.next(), "number of nonzeros")?;
+
+        // the header is not trusted with any allocation, so the nonzeros are collected as they are read and the QUBO is
+        // only built once their count matches the header
+        let mut entries = Vec::new();
+
+        for line in lines {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+
+            // skip over any blank lines
+            let Some(first) = tokens.next() else {
+                continue;
+            };
+
+            let i: usize = parse_token(Some(first), "nonzero")?;
+            let j: usize = parse_token(tokens.next(), "nonzero")?;
+            let value: f64 = parse_token(tokens.next(), "nonzero")?;
+
+            if i >= num_x || j >= num_x {
+                return Err(QuboError::Parse(format!(
+                    "index ({i}, {j}) is out of bounds"
+                )));
+            }
+
+            entries.push((i, j, value));
+        }
+
+        if entries.len() != num_entries {
+            return Err(QuboError::Parse(format!(
+                "expected {num_entries} nonzeros, found {}",
+                entries.len()
+            )));
+        }
+
+        let mut q = TriMat::<f64>::new((num_x, num_x));
+        let mut c = Array1::<f64>::zeros(num_x);
+
+        // the diagonal is the linear term, and the off-diagonal terms are scaled to match 0.5 x^TQx
+        for (i, j, value) in entries {
+            if i == j {
+                c[i] += value;
+            } else {
+                q.add_triplet(i, j, 2.0 * value);
+            }
+        }
+
+        Ok(Self::new_with_c(q.to_csr(), c))
+    }
+
+    /// Writes the QUBO in the compact binary format, which is much faster to read than the text formats
+    ///
+    /// The layout is little endian, and starts with the magic bytes `HERCQUBO` and a `u32` version. Then comes the
+    /// number of variables as a `u64`, a `u8` that is 0 for CSR and 1 for CSC storage, the number of nonzeros as a
+    /// `u64`, the index pointers and indices of the compressed Hessian as `u64`s, its values as `f64`s, and finally the
+    /// linear term and the offset as `f64`s. The Hessian is written as it is stored, so reading it back gives identical
+    /// matrices.
+    ///
+    /// Example of writing a QUBO in the binary format:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///   generator: JsfLarge::default(),
+    /// };
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.01);
+    ///
+    /// let file = std::fs::File::create("test.qubo.bin").unwrap();
+    /// p.write_bin(file).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if it is not possible to write to the writer.
+    pub fn write_bin(&self, writer: impl Write) -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(writer);
+
+        writer.write_all(BIN_MAGIC)?;
+        writer.write_all(&BIN_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.num_x() as u64).to_le_bytes())?;
+        writer.write_all(&[u8::from(self.q.is_csc())])?;
+        writer.write_all(&(self.q.nnz() as u64).to_le_bytes())?;
+
+        for &index in self.q.indptr().raw_storage() {
+            writer.write_all(&(index as u64).to_le_bytes())?;
+        }
+        for &index in self.q.indices() {
+            writer.write_all(&(index as u64).to_le_bytes())?;
+        }
+        for &value in self.q.data().iter().chain(&self.c).chain([&self.offset]) {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        writer.flush()
+    }
+
+    /// Reads a QUBO in the compact binary format, as written by `write_bin`
+    ///
+    /// Example of reading a QUBO in the binary format:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    ///
+    /// let file = std::fs::File::open("test.qubo.bin").unwrap();
+    /// let p = Qubo::read_bin(file).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the reader fails or ends early, if the magic bytes do not match, if the version is not
+    /// supported, or if the compressed arrays do not describe a valid matrix.
+    pub fn read_bin(reader: impl std::io::Read) -> Result<Self, QuboError> {
+        let mut reader = std::io::BufReader::new(reader);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != BIN_MAGIC {
+            return Err(QuboError::Parse("missing magic bytes".to_string()));
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != BIN_VERSION {
+            return Err(QuboError::Unsupported(format!(
+                "binary format version {version}"
+            )));
+        }
+
+        let num_x = read_bin_usize(&mut reader)?;
+        let mut storage = [0u8; 1];
+        reader.read_exact(&mut storage)?;
+        let nnz = read_bin_usize(&mut reader)?;
+
+        let indptr = read_bin_array(&mut reader, num_x.saturating_add(1), read_bin_usize)?;
+        let indices = read_bin_array(&mut reader, nnz, read_bin_usize)?;
+        let data = read_bin_array(&mut reader, nnz, read_bin_f64)?;
+        let c = read_bin_array(&mut reader, num_x, read_bin_f64)?;
+        let offset = read_bin_f64(&mut reader)?;
+
+        // the arrays are checked to be a valid compressed matrix, so a corrupted file can not give a broken QUBO
+        let shape = (num_x, num_x);
+        let q = match storage[0] {
+            0 => CsMat::try_new(shape, indptr, indices, data),
+            1 => CsMat::try_new_csc(shape, indptr, indices, data),
+            other => return Err(QuboError::Parse(format!("invalid storage order {other}"))),
+        }
+        .map_err(|(_, _, _, err)| QuboError::Parse(format!("invalid matrix: {err}")))?;
+
+        Ok(Self {
+            q,
+            c: Array1::from_vec(c),
+            offset,
+        })
+    }
+
+    /// Generates a Symmetric QUBO from the current QUBO
+    ///
+    /// Example of making a QUBO symmetric:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// // make a random number generator
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// // make a random QUBO of 50 variables with an approximate sparsity of 0.1
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    ///
+    /// // make a symmetric QUBO from this QUBO
+    /// let p_sym = p.make_symmetric();
+    /// ```
+    #[must_use]
+    pub fn make_symmetric(&self) -> Self {
+        let mut tri_q = TriMat::<f64>::new((self.num_x(), self.num_x()));
+
+        let c = self.c.clone();
+
+        for (&value, (i, j)) in &self.q {
+            if i == j {
+                tri_q.add_triplet(i, j, value);
+            } else {
+                tri_q.add_triplet(j, i, 0.5 * value);
+                tri_q.add_triplet(i, j, 0.5 * value);
+            }
+        }
+
+        Self {
+            q: tri_q.to_csr(),
+            c,
+            offset: self.offset,
+        }
+    }
+
+    /// Makes the QUBO symmetric in place, by replacing the Hessian with $(Q + Q^T)/2$. This does not change the
+    /// objective, and applying it to a symmetric QUBO leaves it unchanged.
+    ///
+    /// Example of symmetrizing a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let mut p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// p.symmetrize();
+    /// assert!(p.is_symmetric());
+    /// ```
+    pub fn symmetrize(&mut self) {
+        let q_t = self.q.transpose_view().to_csr();
+        self.q = (&self.q + &q_t).map(|&value| 0.5 * value);
+    }
+
+    /// Multiplies the Hessian, the linear term and the offset by a factor, for a positive factor the arg-min is
+    /// unchanged and the objective is scaled by the same factor.
+    ///
+    /// Example of scaling a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use sprs::CsMat;
+    ///
+    /// let mut p = Qubo::new(CsMat::<f64>::eye(3));
+    /// p.scale(2.0);
+    /// ```
+    pub fn scale(&mut self, factor: f64) {
+        self.q.map_inplace(|&value| factor * value);
+        self.c.mapv_inplace(|value| factor * value);
+        self.offset *= factor;
+    }
+
+    /// Divides all coefficients of the QUBO by the largest absolute coefficient, so that they are in [-1, 1]. Returns
+    /// the factor that was divided out, so the objective of the original QUBO is the factor times the new objective.
+    /// If all the coefficients are zero, then the QUBO is left unchanged and the factor is 1.
+    ///
+    /// Example of normalizing a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let mut p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let factor = p.normalize();
+    /// ```
+    pub fn normalize(&mut self) -> f64 {
+        let max_abs = self
+            .q
+            .data()
+            .iter()
+            .chain(self.c.iter())
+            .fold(0.0, |acc: f64, &value| acc.max(value.abs()));
+
+        if max_abs == 0.0 {
+            return 1.0;
+        }
+
+        self.scale(1.0 / max_abs);
+        max_abs
+    }
+
+    /// Removes the quadratic entries with an absolute value below the threshold, and returns the number of entries that
+    /// were removed.
+    ///
+    /// This changes the objective slightly, at a point x by at most half of the sum of the absolute values of the
+    /// removed entries with $x_i = x_j = 1$, but it reduces the memory of the Hessian and the cost of each evaluation.
+    /// It is useful for numerically derived QUBOs, which often have many negligible coefficients. The linear term is
+    /// not changed.
+    ///
+    /// Example of removing the small entries of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let mut p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let num_dropped = p.drop_small(0.1);
+    /// ```
+    pub fn drop_small(&mut self, threshold: f64) -> usize {
+        let mut q = TriMat::<f64>::new((self.num_x(), self.num_x()));
+
+        for (&value, (i, j)) in &self.q {
+            if value.abs() >= threshold {
+                q.add_triplet(i, j, value);
+            }
+        }
+
+        let num_dropped = self.q.nnz() - q.nnz();
+        self.q = q.to_csr();
+        num_dropped
+    }
+
+    /// Adds the quadratic penalty of the linear equality constraint $\sum_i a_i x_i = b$ to the QUBO, where `coeffs`
+    /// are the pairs $(i, a_i)$ and `rhs` is b.
+    ///
+    /// As $x_i^2 = x_i$ for binary variables, the square expands into quadratic, linear and constant terms
+    ///
+    /// $$w\left(\sum_i a_i x_i - b\right)^2 = w\sum_{i \neq j} a_i a_j x_i x_j + w\sum_i (a_i^2 - 2 b a_i) x_i + w b^2$$
+    ///
+    /// The constant is added to the offset, so the objective increases by exactly the penalty, which is zero exactly
+    /// when the constraint is satisfied.
+    ///
+    /// Example of adding the cardinality constraint $x_0 + x_1 + x_2 = 2$:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use sprs::CsMat;
+    ///
+    /// let mut p = Qubo::new(CsMat::<f64>::eye(3));
+    /// p.add_equality_penalty(&[(0, 1.0), (1, 1.0), (2, 1.0)], 2.0, 10.0);
+    /// ```
+    pub fn add_equality_penalty(&mut self, coeffs: &[(usize, f64)], rhs: f64, weight: f64) {
+        let mut q = TriMat::<f64>::new((self.num_x(), self.num_x()));
+
+        for (k, &(i, a_i)) in coeffs.iter().enumerate() {
+            self.c[i] += weight * (a_i * a_i - 2.0 * rhs * a_i);
+
+            for &(j, a_j) in &coeffs[(k + 1)..] {
+                if i == j {
+                    // a repeated variable, so the cross term is linear, counted once for each order
+                    self.c[i] += 2.0 * weight * a_i * a_j;
+                } else {
+                    // the cross term is split between Q_ij and Q_ji, with the factor of 0.5 in the objective
+                    q.add_triplet(i, j, 2.0 * weight * a_i * a_j);
+                    q.add_triplet(j, i, 2.0 * weight * a_i * a_j);
+                }
+            }
+        }
+
+        self.q = &self.q + &q.to_csr();
+        self.offset += weight * rhs * rhs;
+    }
+
+    /// Adds the penalty that exactly one of the variables is one, which is the equality penalty of $\sum_i x_i = 1$.
+    ///
+    /// A feasible assignment has no penalty and an infeasible one with k active variables has a penalty of
+    /// $w(k - 1)^2 \geq w$.
+    ///
+    /// Example of adding a one-hot constraint over three variables:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use sprs::CsMat;
+    ///
+    /// let mut p = Qubo::new(CsMat::<f64>::eye(3));
+    /// p.add_one_hot(&[0, 1, 2], 10.0);
+    /// ```
+    pub fn add_one_hot(&mut self, vars: &[usize], weight: f64) {
+        let coeffs = vars
+            .iter()
+            .map(|&i| (i, 1.0))
+            .collect::<Vec<(usize, f64)>>();
+        self.add_equality_penalty(&coeffs, 1.0, weight);
+    }
+
+    /// Adds the penalty that at most one of the variables is one, which is w times the number of active pairs
+    ///
+    /// $$w\sum_{i < j} x_i x_j$$
+    ///
+    /// A feasible assignment has no penalty and an infeasible one with k active variables has a penalty of
+    /// $w k (k - 1) / 2 \geq w$.
+    ///
+    /// Example of adding an at most one constraint over three variables:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use sprs::CsMat;
+    ///
+    /// let mut p = Qubo::new(CsMat::<f64>::eye(3));
+    /// p.add_at_most_one(&[0, 1, 2], 10.0);
+    /// ```
+    pub fn add_at_most_one(&mut self, vars: &[usize], weight: f64) {
+        let mut q = TriMat::<f64>::new((self.num_x(), self.num_x()));
+
+        for (k, &i) in vars.iter().enumerate() {
+            for &j in &vars[(k + 1)..] {
+                // the pair term is split between Q_ij and Q_ji, with the factor of 0.5 in the objective
+                q.add_triplet(i, j, weight);
+                q.add_triplet(j, i, weight);
+            }
+        }
+
+        self.q = &self.q + &q.to_csr();
+    }
+
+    /// Creates the QUBO with the negated coefficients, -Q, -c and -offset, so that minimizing it maximizes the original
+    /// QUBO.
+    ///
+    /// Example of turning a maximization problem into a minimization problem:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let p_min = p.negate();
+    /// ```
+    #[must_use]
+    pub fn negate(&self) -> Self {
+        Self {
+            q: self.q.map(|&value| -value),
+            c: self.c.mapv(|value| -value),
+            offset: -self.offset,
+        }
+    }
+
+    /// Substitutes $x_i = 1 - y_i$ for every variable, and returns the QUBO over y, where the constant of the
+    /// substitution is added to the offset. For every point, the objective of the original QUBO at x is the objective
+    /// of the new QUBO at $y = 1 - x$.
+    ///
+    /// $$\frac{1}{2}(1 - y)^TQ(1 - y) + c^T(1 - y) = \frac{1}{2}y^TQy - \left(c + \frac{1}{2}(Q + Q^T)1\right)^Ty + \frac{1}{2}1^TQ1 + c^T1$$
+    ///
+    /// Example of complementing the variables of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let p_complement = p.complement_variables();
+    /// ```
+    #[must_use]
+    pub fn complement_variables(&self) -> Self {
+        let mut c = self.c.mapv(|value| -value);
+        let mut offset = self.offset + self.c.sum();
+
+        for (&value, (i, j)) in &self.q {
+            c[i] -= 0.5 * value;
+            c[j] -= 0.5 * value;
+            offset += 0.5 * value;
+        }
+
+        Self {
+            q: self.q.clone(),
+            c,
+            offset,
+        }
+    }
+
+    /// Fixes a subset of the variables, and returns the QUBO over the remaining free variables, where the constant
+    /// contribution of the fixed variables is added to the offset. The free variables keep their relative order, so the
+    /// k-th variable of the reduced QUBO is the k-th variable that is not fixed.
+    ///
+    /// For any assignment of the free variables, the objective of the original QUBO with the fixings is the objective
+    /// of the reduced QUBO.
+    ///
+    /// Example of reducing a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    ///
+    /// // fix x_0 = 1 and x_1 = 0, leaving a QUBO over 48 variables
+    /// let fixed = HashMap::from([(0, 1), (1, 0)]);
+    /// let p_reduced = p.fix_and_reduce(&fixed);
+    /// ```
+    #[must_use]
+    pub fn fix_and_reduce(&self, fixed: &HashMap<usize, usize>) -> Self {
+        // map the free variables to their index in the reduced problem
+        let mut free_index = vec![None; self.num_x()];
+        let mut num_free = 0;
+        for (i, index) in free_index.iter_mut().enumerate() {
+            if !fixed.contains_key(&i) {
+                *index = Some(num_free);
+                num_free += 1;
+            }
+        }
+
+        let mut q = TriMat::<f64>::new((num_free, num_free));
+        let mut c = Array1::<f64>::zeros(num_free);
+        let mut offset = self.offset;
+
+        for (i, &c_i) in self.c.iter().enumerate() {
+            match (free_index[i], fixed.get(&i)) {
+                (Some(k), _) => c[k] += c_i,
+                (None, Some(&x_i)) => offset += c_i * x_i as f64,
+                (None, None) => {}
+            }
+        }
+
+        for (&value, (i, j)) in &self.q {
+            match (free_index[i], free_index[j]) {
+                // both free, so the term stays quadratic
+                (Some(k), Some(l)) => q.add_triplet(k, l, value),
+                // one free, so the term becomes linear if the fixed variable is one
+                (Some(k), None) => c[k] += 0.5 * value * fixed[&j] as f64,
+                (None, Some(l)) => c[l] += 0.5 * value * fixed[&i] as f64,
+                // both fixed, so the term is a constant
+                (None, None) => offset += 0.5 * value * (fixed[&i] * fixed[&j]) as f64,
+            }
+        }
+
+        Self {
+            q: q.to_csr(),
+            c,
+            offset,
+        }
+    }
+
+    /// Returns the QUBO over a subset of the variables, with every other variable held at a fixed background value,
+    /// along with the original index of each variable of the sub-QUBO. The constant contribution of the background is
+    /// added to the offset.
+    ///
+    /// The variables are sorted and deduplicated, so the k-th variable of the sub-QUBO is the k-th smallest index in
+    /// `vars`. Variables that are in neither `vars` nor `fixed` are taken to be zero, and the background values can be
+    /// fractional. For any assignment of the subset, the objective of the original QUBO at the combined point is the
+    /// objective of the sub-QUBO.
+    ///
+    /// Example of extracting a neighborhood:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    ///
+    /// // the QUBO over x_3, x_7 and x_9, with x_0 = 1 and the rest of the variables at zero
+    /// let fixed = HashMap::from([(0, 1.0)]);
+    /// let (p_sub, members) = p.induced_subproblem(&[9, 3, 7], &fixed);
+    /// assert_eq!(members, vec![3, 7, 9]);
+    /// ```
+    pub fn induced_subproblem(
+        &self,
+        vars: &[usize],
+        fixed: &HashMap<usize, f64>,
+    ) -> (Self, Vec<usize>) {
+        let mut members = vars.to_vec();
+        members.sort_unstable();
+        members.dedup();
+
+        // map the members to their index in the sub-QUBO
+        let mut local_index = vec![None; self.num_x()];
+        for (k, &i) in members.iter().enumerate() {
+            local_index[i] = Some(k);
+        }
+        let background = |i: usize| fixed.get(&i).copied().unwrap_or(0.0);
+
+        let num_members = members.len();
+        let mut q = TriMat::<f64>::new((num_members, num_members));
+        let mut c = Array1::<f64>::zeros(num_members);
+        let mut offset = self.offset;
+
+        for (i, &c_i) in self.c.iter().enumerate() {
+            match local_index[i] {
+                Some(k) => c[k] += c_i,
+                None => offset += c_i * background(i),
+            }
+        }
+
+        for (&value, (i, j)) in &self.q {
+            match (local_index[i], local_index[j]) {
+                // both in the subset, so the term stays quadratic
+                (Some(k), Some(l)) => q.add_triplet(k, l, value),
+                // one in the subset, so the term becomes linear in it
+                (Some(k), None) => c[k] += 0.5 * value * background(j),
+                (None, Some(l)) => c[l] += 0.5 * value * background(i),
+                // both in the background, so the term is a constant
+                (None, None) => offset += 0.5 * value * background(i) * background(j),
+            }
+        }
+
+        let sub_qubo = Self {
+            q: q.to_csr(),
+            c,
+            offset,
+        };
+        (sub_qubo, members)
+    }
+
+    /// Convexifies the QUBO problem by modifying the Hessian and linear coefficients,rendering a convex problem.
+    ///
+    /// Currently, assume that the required factor,'s' is known.
+    ///
+    /// $$\frac{1}{2}x^TQx + c^Tx = \frac{1}{2}x^T(Q + sI)x + c^Tx - 0.5s^Tx$$
+    #[must_use]
+    pub fn make_diag_transform(&self, s: f64) -> Self {
+        // generate the scaled (sparse) identity matrix
+        let mut s_eye_tri = TriMat::<f64>::new((self.num_x(), self.num_x()));
+        for i in 0..self.num_x() {
+            s_eye_tri.add_triplet(i, i, s);
+        }
+        let s_eye = s_eye_tri.to_csr();
+
+        Self {
+            q: &self.q + &s_eye,
+            c: self.c.clone() - 0.5 * s,
+            offset: self.offset,
+        }
+    }
+
+    /// Calculates the eigenvalues of the QUBO Hessian matrix this is a somewhat expensive operation.
+    /// Converts the QUBO to a dense matrix and then calculates the eigenvalues. Assume that the QUBO is symmetric.
+    ///
+    /// Example of calculating the eigenvalues of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::CsMat;
+    ///
+    /// let q = CsMat::<f64>::eye(3);
+    /// let c = Array1::<f64>::zeros(3);
+    /// let p = Qubo::new_with_c(q, c);
+    ///
+    /// let eigs = p.hess_eigenvalues();
+    /// ```
+    ///
+    /// # Panics
+    ///  If the eigenvalue calculation fails, The only panic annotation from the ndarray_linalg crate is when the matrix is non-square
+    pub fn hess_eigenvalues(&self) -> Array1<f64> {
+        let q_dense = self.q.to_dense();
+        let (eigs, _) = q_dense.eigh(UPLO::Upper).unwrap();
+        eigs
+    }
+
+    /// Creates the convex symmetric form of the QUBO problem. This is an exact operation, and results in a convex symmetric matrix that is equivalent to the original QUBO.
+    #[must_use]
+    pub fn convex_symmetric_form(&self) -> Self {
+        // make the QUBO symmetric
+        let p_sym = self.make_symmetric();
+
+        // calculate the eigenvalues of the QUBO
+        let eigs = p_sym.hess_eigenvalues();
+
+        // find the minimum eigenvalue, create a factor that scales the minimum eigenvalue to 1
+        let min_eig = eigs.iter().fold(f64::INFINITY, |acc, &x| x.min(acc));
+        let s = 0.001 - min_eig;
+
+        // make the QUBO convex
+        p_sym.make_diag_transform(s)
+    }
+
+    /// Shifts the diagonal of the symmetric form of the QUBO just enough to make it convex, and returns the convex QUBO
+    /// along with the shift.
+    ///
+    /// As $x_i^2 = x_i$ at binary points, adding s to the diagonal and subtracting s/2 from c does not change the
+    /// objective at any binary point, but it raises every eigenvalue of the Hessian by s. The shift is the negative of
+    /// the smallest eigenvalue of the symmetric form plus a margin relative to the largest eigenvalue, or zero if it is
+    /// already convex, so unlike `convex_symmetric_form` the relaxation is kept as tight as the shift allows. The margin
+    /// keeps the result convex under the rounding error of the eigenvalue calculation, even for large or badly scaled
+    /// Hessians. This computes the eigenvalues of the dense Hessian, so it is a somewhat expensive operation.
+    ///
+    /// Example of convexifying a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let (p_convex, shift) = p.convexify();
+    /// assert!(p_convex.is_convex());
+    /// assert!(shift > 0.0);
+    /// ```
+    pub fn convexify(&self) -> (Self, f64) {
+        let p_sym = self.make_symmetric();
+
+        let eigs = p_sym.hess_eigenvalues();
+        let min_eig = eigs.iter().fold(f64::INFINITY, |acc, &eig| acc.min(eig));
+        let max_abs_eig = eigs.iter().fold(0.0, |acc: f64, &eig| acc.max(eig.abs()));
+
+        // the margin is well above the rounding error of the eigenvalues, but small enough to keep the relaxation tight
+        let margin = CONVEXIFY_MARGIN * max_abs_eig.max(1.0);
+        let shift = if min_eig < 0.0 { margin - min_eig } else { 0.0 };
+
+        (p_sym.make_diag_transform(shift), shift)
+    }
+
+    /// Creates the Hessian only equivalent form of the QUBO. Where the linear term is adsorbed into
+    /// The Hessian matrix. This is an exact operation, and generates an equivalent form.
+    ///
+    /// $$ 0.5 x^T Q x + c^Tx = 0.5 x^T (Q + 2diag(c)) x $$
+    // pub fn hessian_only_form(&self) -> Self {
+    //
+    // }
+
+    /// Checks if the QUBO is symmetric
+    pub fn is_symmetric(&self) -> bool {
+        let error_margin = f64::EPSILON;
+
+        for (&q_ij, (i, j)) in &self.q {
+            let q_ji = self.q.get(j, i);
+            match q_ji {
+                Some(&q_ji) => {
+                    if (q_ij - q_ji).abs() > error_margin {
+                        return false;
+                    }
+                }
+                None => {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Checks if two QUBOs have the same size, and their Q matrices, c vectors, and offsets agree entrywise to within
+    /// `tol`. This compares the stored Q matrices, not their symmetric forms, and an entry that is missing from one
+    /// sparse matrix is compared as zero, so explicit zeros do not change the result.
+    ///
+    /// Example:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let (i, j, q, c, num_x) = p.to_vec();
+    /// assert!(p.approx_eq(&Qubo::from_vec(i, j, q, c, num_x), 1E-10));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, tol: f64) -> bool {
+        if self.num_x() != other.num_x() || self.q.shape() != other.q.shape() {
+            return false;
+        }
+
+        if (self.offset - other.offset).abs() > tol {
+            return false;
+        }
+
+        if self
+            .c
+            .iter()
+            .zip(other.c.iter())
+            .any(|(a, b)| (a - b).abs() > tol)
+        {
+            return false;
+        }
+
+        // every stored entry of each matrix must match the same entry of the other, or zero if it is not stored
+        let is_covered_by = |a: &CsMat<f64>, b: &CsMat<f64>| {
+            a.iter()
+                .all(|(&a_ij, (i, j))| (a_ij - b.get(i, j).unwrap_or(&0.0)).abs() <= tol)
+        };
+
+        is_covered_by(&self.q, &other.q) && is_covered_by(&other.q, &self.q)
+    }
+
+    /// Checks if the QUBO is convex, which is when the symmetric part of the Hessian is positive semidefinite. This
+    /// computes the eigenvalues of the dense Hessian, so it is a somewhat expensive operation.
+    ///
+    /// Example of checking the convexity of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// assert!(p.convex_symmetric_form().is_convex());
+    /// ```
+    pub fn is_convex(&self) -> bool {
+        // small negative eigenvalues are allowed to account for the error in the eigenvalue calculation
+        let error_margin = 1E-10;
+
+        self.make_symmetric()
+            .hess_eigenvalues()
+            .iter()
+            .all(|&eig| eig >= -error_margin)
+    }
+
+    /// Estimates the condition number of the symmetric part of the Hessian, as the ratio of its spectral radius to its
+    /// smallest eigenvalue, without forming the dense matrix
+    ///
+    /// The estimate is negative if the smallest eigenvalue is negative, so the QUBO is not convex, and very large or
+    /// infinite if the Hessian is close to singular, which are both cases that the relaxations of the B&B solver do
+    /// not handle well. The spectral radius is found by power iteration, and the smallest eigenvalue by power iteration
+    /// on the Hessian shifted by the spectral radius, so this is only an estimate, but it is cheap even for large
+    /// sparse problems.
+    ///
+    /// Example of estimating the condition number of a QUBO:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// assert!(p.condition_estimate() < 0.0);
+    /// assert!(p.convex_symmetric_form().condition_estimate() > 0.0);
+    /// ```
+    pub fn condition_estimate(&self) -> f64 {
+        let q_sym = self.make_symmetric().q;
+
+        // the iterates of power iteration grow by the spectral radius, even if both signs of it are eigenvalues
+        let (radius, _) = Self::power_iteration(&q_sym, 0.0);
+        if radius == 0.0 {
+            return f64::INFINITY;
+        }
+
+        // all the eigenvalues of radius * I - Q are nonnegative, and the largest of them is radius - min_eig
+        let (_, shifted_max) = Self::power_iteration(&q_sym, radius);
+        let min_eig = radius - shifted_max;
+
+        radius / min_eig
+    }
+
+    /// Runs power iteration on $\sigma I - Q$ from a fixed starting vector, and returns the norm of the last product
+    /// and the Rayleigh quotient of the last iterate
+    fn power_iteration(q: &CsMat<f64>, sigma: f64) -> (f64, f64) {
+        const ITERATIONS: usize = 500;
+
+        // a fixed starting vector, which is unlikely to be orthogonal to the dominant eigenvector
+        let mut v = Array1::from_shape_fn(q.rows(), |i| 1.0 + 1.0 / (i + 1) as f64);
+        let v_norm = v.iter().map(|v_i| v_i * v_i).sum::<f64>().sqrt();
+        v /= v_norm;
+
+        let mut norm = 0.0;
+        let mut rayleigh = 0.0;
+        for _ in 0..ITERATIONS {
+            let w = sigma * &v - &(q * &v);
+            rayleigh = v
+                .iter()
+                .zip(w.iter())
+                .map(|(v_i, w_i)| v_i * w_i)
+                .sum::<f64>();
+            norm = w.iter().map(|w_i| w_i * w_i).sum::<f64>().sqrt();
+
+            // the iterate is in the null space, so the dominant eigenvalue is zero
+            if norm == 0.0 {
+                break;
+            }
+            v = w / norm;
+        }
+
+        (norm, rayleigh)
+    }
+
+    /// Expresses the QUBO as a max-cut problem, on a graph with one node for each variable and an auxiliary node with
+    /// index `num_x`. Returns the graph and an offset, such that for every cut with the auxiliary node on the zero side
+    ///
+    /// $$ 0.5 x^T Q x + c^Tx = \text{offset} - \text{cut}(x) $$
+    ///
+    /// where $x_i$ is one if node i is on the other side. As a cut and its complement have the same weight, a cut with
+    /// the auxiliary node on the one side is mapped to x by complementing it first. So the maximum cut gives the
+    /// optimal objective. The edge weights can be negative.
+    ///
+    /// With $b_{ij} = 0.5(Q_{ij} + Q_{ji})$ and $a_i = c_i + 0.5 Q_{ii}$, an edge (i, j) of weight $b_{ij}/2$ accounts
+    /// for the quadratic term, and adds $-b_{ij}/2$ to the linear terms of both ends. Each variable is then connected
+    /// to the auxiliary node with weight $-a_i - \sum_j b_{ij}/2$, so that the linear terms are matched. The offset
+    /// is the objective at the zero vector, whose cut is empty.
+    ///
+    /// Example of converting a QUBO to a max-cut problem:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let (graph, offset) = p.to_maxcut();
+    /// ```
+    pub fn to_maxcut(&self) -> (Graph, f64) {
+        let num_x = self.num_x();
+        let q_sym = self.make_symmetric();
+        let mut graph = Graph::new(num_x + 1);
+
+        // the weights of the edges to the auxiliary node, starting from the linear terms
+        let mut aux_weights = -(&self.c + &(0.5 * &q_sym.q.diag().to_dense()));
+
+        for (&b_ij, (i, j)) in &q_sym.q {
+            if i < j && b_ij != 0.0 {
+                graph.add_edge(i, j, 0.5 * b_ij);
+                aux_weights[i] -= 0.5 * b_ij;
+                aux_weights[j] -= 0.5 * b_ij;
+            }
+        }
+
+        for (i, &weight) in aux_weights.iter().enumerate() {
+            if weight != 0.0 {
+                graph.add_edge(i, num_x, weight);
+            }
+        }
+
+        let offset = self.eval_usize(&Array1::zeros(num_x));
+        (graph, offset)
+    }
+
+    /// Combines two QUBOs over disjoint sets of variables, where the variables of self come first, followed by the
+    /// variables of other. The Hessian is block diagonal and the offsets are added, so the objective at a combined
+    /// point is the sum of the objectives of the two parts.
+    ///
+    /// Example of combining two QUBOs:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p_1 = Qubo::make_random_qubo(10, &mut prng, 0.3);
+    /// let p_2 = Qubo::make_random_qubo(20, &mut prng, 0.1);
+    /// let p = p_1.direct_sum(&p_2);
+    /// assert_eq!(p.num_x(), 30);
+    /// ```
+    #[must_use]
+    pub fn direct_sum(&self, other: &Self) -> Self {
+        let shift = self.num_x();
+        let num_x = shift + other.num_x();
+
+        let mut q = TriMat::<f64>::new((num_x, num_x));
+        for (&value, (i, j)) in &self.q {
+            q.add_triplet(i, j, value);
+        }
+        for (&value, (i, j)) in &other.q {
+            q.add_triplet(i + shift, j + shift, value);
+        }
+
+        let c = self
+            .c
+            .iter()
+            .chain(other.c.iter())
+            .copied()
+            .collect::<Array1<f64>>();
+
+        Self {
+            q: q.to_csr(),
+            c,
+            offset: self.offset + other.offset,
+        }
+    }
+
+    /// Reorders the variables with the reverse Cuthill-McKee algorithm, which reduces the bandwidth of the Hessian so
+    /// that the nonzeros are close to the diagonal. Returns the reordered QUBO and the permutation, where `perm[k]` is
+    /// the original index of the k-th variable of the reordered QUBO.
+    ///
+    /// A solution y of the reordered QUBO has the same objective as the original QUBO at $x_{perm[k]} = y_k$, see
+    /// `utils::unpermute`.
+    ///
+    /// Example of reordering a QUBO and mapping a solution back:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use hercules::utils;
+    /// use ndarray::Array1;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let (p_rcm, perm) = p.reorder_rcm();
+    ///
+    /// let y = Array1::ones(p_rcm.num_x());
+    /// let x = utils::unpermute(&y, &perm);
+    /// ```
+    pub fn reorder_rcm(&self) -> (Self, Vec<usize>) {
+        let num_x = self.num_x();
+
+        // the ordering needs a symmetric pattern, so that both orders of an interaction are seen
+        let perm = reverse_cuthill_mckee(self.make_symmetric().q.view())
+            .perm
+            .vec();
+
+        let mut new_index = vec![0; num_x];
+        for (k, &i) in perm.iter().enumerate() {
+            new_index[i] = k;
+        }
+
+        let mut q = TriMat::<f64>::new((num_x, num_x));
+        for (&value, (i, j)) in &self.q {
+            q.add_triplet(new_index[i], new_index[j], value);
+        }
+        let c = Array1::from_shape_fn(num_x, |k| self.c[perm[k]]);
+
+        let qubo = Self {
+            q: q.to_csr(),
+            c,
+            offset: self.offset,
+        };
+        (qubo, perm)
+    }
+
+    /// Converts the QUBO to the equivalent Ising model, with the substitution $x = (1 + s)/2$. This is an exact
+    /// operation, in that the energy of the Ising model at s is the objective of the QUBO at x.
+    ///
+    /// $$ 0.5 x^T Q x + c^Tx = s^T J s + h^Ts + \text{offset} $$
+    ///
+    /// Example of converting a QUBO to an Ising model:
+    /// ```rust
+    /// use hercules::qubo::Qubo;
+    /// use smolprng::{PRNG, JsfLarge};
+    ///
+    /// let mut prng = PRNG {
+    ///     generator: JsfLarge::default(),
+    /// };
+    ///
+    /// let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+    /// let ising = p.to_ising();
+    /// ```
+    pub fn to_ising(&self) -> IsingModel {
+        let mut j = TriMat::<f64>::new((self.num_x(), self.num_x()));
+        let mut h = 0.5 * &self.c;
+        let mut offset = self.offset + 0.5 * self.c.sum();
+
+        for (&value, (row, col)) in &self.q {
+            // as s_i^2 = 1, the diagonal terms only contribute to the linear fields and the offset
+            if row == col {
+                h[row] += 0.25 * value;
+                offset += 0.25 * value;
+            } else {
+                j.add_triplet(row, col, 0.125 * value);
+                h[row] += 0.125 * value;
+                h[col] += 0.125 * value;
+                offset += 0.125 * value;
+            }
+        }
+
+        IsingModel::new(h, j.to_csr(), offset)
+    }
+
+    /// Converts an Ising model to the equivalent QUBO, with the substitution $s = 2x - 1$. The constant terms of the
+    /// substitution are the offset of the QUBO, see `IsingModel::qubo_offset`, so the objective of the QUBO at x is the
+    /// energy of the Ising model at s.
+    ///
+    /// Example of converting an Ising model to a QUBO:
+    /// ```rust
+    /// use hercules::ising::IsingModel;
+    /// use hercules::qubo::Qubo;
+    /// use ndarray::Array1;
+    /// use sprs::TriMat;
+    ///
+    /// let mut j = TriMat::new((2, 2));
+    /// j.add_triplet(0, 1, 1.0);
+    /// let h = Array1::zeros(2);
+    /// let ising = IsingModel::new(h, j.to_csr(), 0.0);
+    ///
+    /// let p = Qubo::from_ising(&ising);
+    /// ```
+    pub fn from_ising(ising: &IsingModel) -> Self {
+        let num_x = ising.num_x();
+        let mut q = TriMat::<f64>::new((num_x, num_x));
+        let mut c = 2.0 * &ising.h;
+
+        for (&value, (row, col)) in &ising.j {
+            // as s_i^2 = 1, the diagonal terms only contribute to the offset
+            if row != col {
+                q.add_triplet(row, col, 8.0 * value);
+                c[row] -= 2.0 * value;
+                c[col] -= 2.0 * value;
+            }
+        }
+
+        Self {
+            q: q.to_csr(),
+            c,
+            offset: ising.qubo_offset(),
+        }
+    }
+}
+
+/// Helper function to get the next data line of a QPLIB file
+fn next_qplib_line<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    section: &str,
+) -> Result<&'a str, QuboError> {
+    lines
+        .next()
+        .ok_or_else(|| QuboError::Parse(format!("unexpected end of file, expected {section}")))
+}
+
+/// Helper function to parse the next data line of a QPLIB file, when it contains a single value
+fn next_qplib_value<'a, T: std::str::FromStr>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    section: &str,
+) -> Result<T, QuboError> {
+    let line = next_qplib_line(lines, section)?;
+    parse_token(line.split_whitespace().next(), section)
+}
+
+/// Helper function to parse a single whitespace separated token of a text file, where `section` names it in the error
+fn parse_token<T: std::str::FromStr>(token: Option<&str>, section: &str) -> Result<T, QuboError> {
+    token
+        .and_then(|t| t.parse::<T>().ok())
+        .ok_or_else(|| QuboError::Parse(format!("could not parse {section}")))
+}
+
+/// Helper function to reduce the clauses of a weighted Max-2-SAT instance to a QUBO, whose objective is the total weight
+/// of the violated clauses, where the hard clauses without a weight are given the hard weight
+fn max_2sat_qubo(
+    num_x: usize,
+    clauses: &[(Option<f64>, Vec<i64>)],
+    hard_weight: f64,
+) -> Result<Qubo, QuboError> {
+    let mut hessian = TriMat::<f64>::new((num_x, num_x));
+    let mut linear = Array1::<f64>::zeros(num_x);
+    let mut offset = 0.0;
+
+    for (weight, literals) in clauses {
+        let weight = weight.unwrap_or(hard_weight);
+
+        // each literal is false with an indicator of a + b x_i, so the clause is violated with their product
+        let terms = literals
+            .iter()
+            .map(|&literal| {
+                let index = one_based_index(literal.unsigned_abs() as usize, num_x)?;
+                Ok(if literal > 0 {
+                    (index, 1.0, -1.0)
+                } else {
+                    (index, 0.0, 1.0)
+                })
+            })
+            .collect::<Result<Vec<(usize, f64, f64)>, QuboError>>()?;
+
+        match terms[..] {
+            [] => offset += weight,
+            [(k, a_k, b_k)] => {
+                offset += weight * a_k;
+                linear[k] += weight * b_k;
+            }
+            [(i, a_i, b_i), (j, a_j, b_j)] => {
+                offset += weight * a_i * a_j;
+                linear[i] += weight * a_j * b_i;
+                linear[j] += weight * a_i * b_j;
+
+                // as x_i^2 = x_i, a repeated variable only has a linear term
+                if i == j {
+                    linear[i] += weight * b_i * b_j;
+                } else {
+                    hessian.add_triplet(i, j, weight * b_i * b_j);
+                    hessian.add_triplet(j, i, weight * b_i * b_j);
+                }
+            }
+            _ => unreachable!("longer clauses are rejected while reading"),
+        }
+    }
+
+    Ok(Qubo {
+        q: hessian.to_csr(),
+        c: linear,
+        offset,
+    })
+}
+
+/// Helper function to convert the 1-based indices of a text file to 0-based indices, checking they are in bounds
+fn one_based_index(index: usize, num_x: usize) -> Result<usize, QuboError> {
+    if index == 0 || index > num_x {
+        return Err(QuboError::Parse(format!("index {index} is out of bounds")));
+    }
+    Ok(index - 1)
+}
+
+/// Helper function to parse the next problem of an OR-Library BQP file, from the whitespace separated tokens
+fn parse_orlib_bqp_problem<'a>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<Qubo, QuboError> {
+    let num_x: usize = parse_token(tokens.next(), "number of variables")?;
+    let num_entries: usize = parse_token(tokens.next(), "number of nonzeros")?;
+
+    let mut q = TriMat::<f64>::new((num_x, num_x));
+    let mut c = Array1::<f64>::zeros(num_x);
+
+    for _ in 0..num_entries {
+        let i = one_based_index(parse_token(tokens.next(), "nonzero")?, num_x)?;
+        let j = one_based_index(parse_token(tokens.next(), "nonzero")?, num_x)?;
+        let value: f64 = parse_token(tokens.next(), "nonzero")?;
+
+        // the problem is negated, the diagonal is linear as x_i^2 = x_i, and -2 q_ij x_i x_j is split between Q_ij
+        // and Q_ji with the factor of 0.5 in the objective
+        if i == j {
+            c[i] -= value;
+        } else {
+            q.add_triplet(i, j, -2.0 * value);
+            q.add_triplet(j, i, -2.0 * value);
+        }
+    }
+
+    Ok(Qubo::new_with_c(q.to_csr(), c))
+}
+
+/// The margin that `convexify` adds to the shift, relative to the largest eigenvalue of the Hessian
+const CONVEXIFY_MARGIN: f64 = 1E-10;
+
+/// The magic bytes at the start of a QUBO in the binary format
+const BIN_MAGIC: &[u8; 8] = b"HERCQUBO";
+
+/// The version of the binary format that is written, and the only version that can be read
+const BIN_VERSION: u32 = 1;
+
+/// Helper function to read a little endian `u64` of the binary format as an index or a length
+fn read_bin_usize(reader: &mut impl std::io::Read) -> Result<usize, QuboError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    let value = u64::from_le_bytes(bytes);
+    usize::try_from(value).map_err(|_| QuboError::Parse(format!("{value} does not fit in usize")))
+}
+
+/// Helper function to read a little endian `f64` of the binary format
+fn read_bin_f64(reader: &mut impl std::io::Read) -> Result<f64, QuboError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+/// Helper function to read an array of the binary format, with the given length
+fn read_bin_array<R: std::io::Read, T>(
+    reader: &mut R,
+    len: usize,
+    read: impl Fn(&mut R) -> Result<T, QuboError>,
+) -> Result<Vec<T>, QuboError> {
+    // the length is not trusted with the allocation, as a corrupted file would then fail to allocate instead of ending
+    // early
+    let mut values = Vec::with_capacity(len.min(1 << 16));
+    for _ in 0..len {
+        values.push(read(reader)?);
+    }
+    Ok(values)
+}
+
+/// Incrementally builds a QUBO term by term, without having to touch the sparse matrix directly.
+///
+/// Repeated terms are accumulated rather than overwritten, and by default the off-diagonal terms are split evenly
+/// between (i, j) and (j, i) so that the resulting Hessian is symmetric. The size of the QUBO is the larger of the
+/// size given by `set_size` and the largest index seen.
+///
+/// Example of building the QUBO $0.5 x_0^2 - x_0 x_1 + 2 x_1$:
+/// ```rust
+/// use hercules::qubo::QuboBuilder;
+///
+/// let mut builder = QuboBuilder::new();
+/// builder.add_quadratic(0, 0, 1.0);
+/// builder.add_quadratic(0, 1, -2.0);
+/// builder.add_linear(1, 2.0);
+///
+/// let p = builder.build();
+/// ```
+#[derive(Clone, Default)]
+pub struct QuboBuilder {
+    /// The number of variables requested by the user
+    num_x: usize,
+    /// The accumulated quadratic terms in triplet form
+    q_terms: Vec<(usize, usize, f64)>,
+    /// The accumulated linear terms
+    c_terms: Vec<(usize, f64)>,
+    /// If the off-diagonal terms should be symmetrized
+    symmetrize: bool,
+}
+
+impl QuboBuilder {
+    /// Creates an empty builder that symmetrizes the off-diagonal terms
+    pub const fn new() -> Self {
+        Self {
+            num_x: 0,
+            q_terms: Vec::new(),
+            c_terms: Vec::new(),
+            symmetrize: true,
+        }
+    }
+
+    /// Sets the number of variables of the QUBO, this is grown if a term with a larger index is added
+    pub const fn set_size(&mut self, num_x: usize) -> &mut Self {
+        self.num_x = num_x;
+        self
+    }
+
+    /// Sets if the off-diagonal terms should be split between (i, j) and (j, i), defaults to true
+    pub const fn set_symmetrize(&mut self, symmetrize: bool) -> &mut Self {
+        self.symmetrize = symmetrize;
+        self
+    }
+
+    /// Adds value to the (i, j) element of the Hessian
+    pub fn add_quadratic(&mut self, i: usize, j: usize, value: f64) -> &mut Self {
+        self.q_terms.push((i, j, value));
+        self
+    }
+
+    /// Adds value to the i-th element of the linear term
+    pub fn add_linear(&mut self, i: usize, value: f64) -> &mut Self {
+        self.c_terms.push((i, value));
+        self
+    }
+
+    /// Returns the number of variables that the built QUBO will have
+    pub fn num_x(&self) -> usize {
+        let max_q = self.q_terms.iter().map(|&(i, j, _)| i.max(j) + 1).max();
+        let max_c = self.c_terms.iter().map(|&(i, _)| i + 1).max();
+
+        self.num_x.max(max_q.unwrap_or(0)).max(max_c.unwrap_or(0))
+    }
+
+    /// Generates the QUBO from the accumulated terms
+    pub fn build(&self) -> Qubo {
+        let num_x = self.num_x();
+
+        // set up the sparse matrix and dense vector
+        let mut q = TriMat::<f64>::new((num_x, num_x));
+        let mut c = Array1::<f64>::zeros(num_x);
+
+        // duplicate triplets are summed when converting to CSR
+        for &(i, j, value) in &self.q_terms {
+            if self.symmetrize && i != j {
+                q.add_triplet(i, j, 0.5 * value);
+                q.add_triplet(j, i, 0.5 * value);
+            } else {
+                q.add_triplet(i, j, value);
+            }
+        }
+
+        for &(i, value) in &self.c_terms {
+            c[i] += value;
+        }
+
+        Qubo::new_with_c(q.to_csr(), c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::exact::brute_force;
+    use crate::initial_points::generate_random_binary_points;
+    use crate::tests::{make_solver_qubo, make_test_prng};
+    use ndarray::Array1;
+    use sprs::CsMat;
+
+    #[test]
+    fn test_linear() {
+        let p = Qubo::new_with_c(CsMat::eye(4), Array1::from_vec(vec![0.0, 1.5, 0.0, -1.0]));
+
+        assert_eq!(p.linear(), &p.c);
+        assert_eq!(p.num_nonzero_linear(), 2);
+        assert_eq!(Qubo::new(CsMat::eye(4)).num_nonzero_linear(), 0);
+    }
+
+    #[test]
+    fn test_drop_small() {
+        let mut p = Qubo::from_vec(
+            vec![0, 1, 1, 2, 0],
+            vec![1, 0, 1, 2, 2],
+            vec![1e-8, 1e-8, 3.0, -1e-9, -2.0],
+            vec![1e-12, -1.0, 1.0],
+            3,
+        );
+        let p_original = p.clone();
+
+        assert_eq!(p.drop_small(1e-6), 3);
+        assert_eq!(p.nnz(), 2);
+
+        // the linear term is kept, and the objective only changes by the dropped entries
+        assert!((p.c[0] - 1e-12).abs() < 1e-20);
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| (k >> i) & 1);
+            assert!((p.eval_usize(&x) - p_original.eval_usize(&x)).abs() < 1e-7);
+        }
+
+        // nothing else is below the threshold
+        assert_eq!(p.drop_small(1e-6), 0);
+    }
+
+    #[test]
+    fn test_quadratic_and_linear_terms() {
+        let p = Qubo::from_vec(
+            vec![2, 0, 1, 0],
+            vec![0, 1, 1, 2],
+            vec![1.5, -2.0, 3.0, 0.5],
+            vec![0.0, -1.0, 2.0],
+            3,
+        );
+
+        let quadratic = p.quadratic_terms().collect::<Vec<_>>();
+        assert_eq!(
+            quadratic,
+            vec![(0, 1, -2.0), (0, 2, 0.5), (1, 1, 3.0), (2, 0, 1.5)]
+        );
+
+        let linear = p.linear_terms().collect::<Vec<_>>();
+        assert_eq!(linear, vec![(1, -1.0), (2, 2.0)]);
+
+        // rebuilding the QUBO from its terms gives the same objective
+        let mut builder = QuboBuilder::new();
+        for (i, j, value) in p.quadratic_terms() {
+            builder.add_quadratic(i, j, value);
+        }
+        for (i, value) in p.linear_terms() {
+            builder.add_linear(i, value);
+        }
+        let p_rebuilt = builder.build();
+
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| (k >> i) & 1);
+            assert!((p.eval_usize(&x) - p_rebuilt.eval_usize(&x)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_qubo_new() {
+        let q = CsMat::<f64>::eye(10);
+        let p = Qubo::new(q);
+
+        assert_eq!(p.num_x(), 10);
+        assert_eq!(p.q.nnz(), 10);
+        assert_eq!(p.c.len(), 10);
+    }
+
+    #[test]
+    fn test_qubo_new_with_c() {
+        let q = CsMat::<f64>::eye(10);
+        let c = Array1::<f64>::zeros(10);
+        let p = Qubo::new_with_c(q, c);
+
+        assert_eq!(p.num_x(), 10);
+        assert_eq!(p.q.nnz(), 10);
+        assert_eq!(p.c.len(), 10);
+    }
+
+    #[test]
     fn test_qubo_from_vec() {
         // Create a QUBO from a list of tuples:
-        let x = vec![0, 1, 2];
-        let y = vec![0, 1, 2];
-        let q = vec![1.0, 1.0, 1.0];
+        let x = vec![0, 1, 2];
+        let y = vec![0, 1, 2];
+        let q = vec![1.0, 1.0, 1.0];
+        let c = vec![0.0, 0.0, 0.0];
+        let num_x = 3;
+
+        // actually create the QUBO
+        let p = Qubo::from_vec(x, y, q, c, num_x);
+
+        // check that the QUBO was created correctly
+        assert_eq!(p.num_x(), 3);
+        assert_eq!(p.q.nnz(), 3);
+        assert_eq!(p.c.len(), 3);
+        assert_eq!(p.q, CsMat::<f64>::eye(3));
+    }
+
+    #[test]
+    fn test_qubo_from_dense() {
+        let q = ndarray::array![[2.0, -1.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.0, 0.0]];
+        let c = ndarray::array![0.0, 1.0, 1.0];
+        let p = Qubo::from_dense(q.clone(), c.clone());
+
+        // only the nonzeros are stored
+        assert_eq!(p.num_x(), 3);
+        assert_eq!(p.q.nnz(), 3);
+        assert_eq!(p.q.get(0, 1), Some(&-1.0));
+        assert_eq!(p.q.get(2, 2), None);
+
+        // the objective is the same as the dense form
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| ((k >> i) & 1) as f64);
+            let quad_term = q
+                .indexed_iter()
+                .map(|((i, j), &value)| value * x[i] * x[j])
+                .sum::<f64>();
+            let dense_obj = 0.5 * quad_term + c.dot(&x);
+            assert!((p.eval(&x) - dense_obj).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_qubo_to_dense() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(10, &mut prng, 0.3);
+        let (q, c) = p.to_dense();
+
+        // every entry is materialized, and converting back gives the same QUBO
+        assert_eq!(q.dim(), (10, 10));
+        for ((i, j), &value) in q.indexed_iter() {
+            let stored = p.q.get(i, j).copied().unwrap_or(0.0);
+            assert_eq!(value.to_bits(), stored.to_bits());
+        }
+        assert_eq!(c, p.c);
+
+        let p_round = Qubo::from_dense(q, c);
+        assert_eq!(p_round.q, p.q);
+    }
+
+    #[test]
+    #[should_panic(expected = "the Hessian must be square")]
+    fn test_qubo_from_dense_not_square() {
+        let _ = Qubo::from_dense(Array2::zeros((2, 3)), Array1::zeros(2));
+    }
+
+    #[test]
+    fn test_qubo_eval() {
+        let q = CsMat::<f64>::eye(3);
+        let c = Array1::<f64>::zeros(3);
+        let p = Qubo::new_with_c(q, c);
+        let x_0 = Array1::from_vec(vec![1.0, 0.0, 1.0]);
+        let obj = p.eval(&x_0);
+
+        assert_eq!(obj, 1.0);
+    }
+
+    #[test]
+    fn test_qubo_eval_grad() {
+        let q = CsMat::<f64>::eye(3);
+        let c = Array1::<f64>::zeros(3);
+        let p = Qubo::new_with_c(q, c);
+        let x_0 = Array1::from_vec(vec![1.0, 1.0, 1.0]);
+        let grad = p.eval_grad(&x_0);
+
+        assert_eq!(grad, Array1::from_vec(vec![1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_qubo_eval_grad_non_symmetric() {
+        // Create a QUBO from a list of tuples:
+        let x = vec![0];
+        let y = vec![2];
+        let q = vec![1.0];
         let c = vec![0.0, 0.0, 0.0];
         let num_x = 3;
 
         // actually create the QUBO
         let p = Qubo::from_vec(x, y, q, c, num_x);
+        let x_0 = Array1::from_vec(vec![1.0, 1.0, 1.0]);
+        let grad = p.eval_grad(&x_0);
 
-        // check that the QUBO was created correctly
-        assert_eq!(p.num_x(), 3);
-        assert_eq!(p.q.nnz(), 3);
-        assert_eq!(p.c.len(), 3);
-        assert_eq!(p.q, CsMat::<f64>::eye(3));
+        assert_eq!(grad, Array1::from_vec(vec![0.5, 0.0, 0.5]));
     }
 
     #[test]
-    fn test_qubo_eval() {
+    fn test_make_symmetric_from_symmetric() {
         let q = CsMat::<f64>::eye(3);
         let c = Array1::<f64>::zeros(3);
         let p = Qubo::new_with_c(q, c);
-        let x_0 = Array1::from_vec(vec![1.0, 0.0, 1.0]);
-        let obj = p.eval(&x_0);
+        let p_sym = p.make_symmetric();
+
+        assert_eq!(p_sym.q.nnz(), 3);
+        assert_eq!(p_sym.q, CsMat::<f64>::eye(3));
+    }
+
+    #[test]
+    fn test_make_symmetric_from_non_symmetric() {
+        // Create a QUBO from a list of tuples:
+        let x = vec![0, 0];
+        let y = vec![0, 2];
+        let q = vec![1.0, 1.0];
+        let c = vec![0.0, 0.0, 0.0];
+        let num_x = 3;
+        let p = Qubo::from_vec(x, y, q, c, num_x);
+
+        // make a symmetric QUBO from this QUBO
+        let p_sym = p.make_symmetric();
+
+        assert_eq!(p_sym.q.nnz(), 3);
+        assert_eq!(p_sym.q.get(0, 0), Some(&1.0f64));
+        assert_eq!(p_sym.q.get(0, 2), Some(&0.5f64));
+        assert_eq!(p_sym.q.get(2, 0), Some(&0.5f64));
+    }
+
+    #[test]
+    fn test_make_symmetric_from_non_symmetric_off_diagonal() {
+        // Create a QUBO from a list of tuples:
+        let x = vec![0, 0, 2];
+        let y = vec![0, 2, 0];
+        let q = vec![1.0, 1.5, 0.5];
+        let c = vec![0.0, 0.0, 0.0];
+        let num_x = 3;
+        let p = Qubo::from_vec(x, y, q, c, num_x);
+
+        // make a symmetric QUBO from this QUBO
+        let p_sym = p.make_symmetric();
+
+        assert_eq!(p_sym.q.nnz(), 3);
+        assert_eq!(p_sym.q.get(0, 0), Some(&1.0f64));
+        assert_eq!(p_sym.q.get(0, 2), Some(&1.0f64));
+        assert_eq!(p_sym.q.get(2, 0), Some(&1.0f64));
+    }
+
+    #[test]
+    fn read_write_consistency() {
+        // make a qubo and write it to a file
+        let mut prng = crate::tests::make_test_prng();
+        let p = Qubo::make_random_qubo(10, &mut prng, 0.1);
+        Qubo::write_qubo(&p, "test.qubo");
+
+        // now read it back in
+        let q = Qubo::read_qubo("test.qubo");
+
+        // check that the two are the same
+        assert_eq!(p.q, q.q);
+        assert_eq!(p.c, q.c);
+        assert_eq!(p.num_x(), q.num_x());
+        assert_eq!(p.q.nnz(), q.q.nnz())
+    }
+
+    #[test]
+    fn large_scale_write_qubo() {
+        // make a large qubo instance and write it to file
+        let mut prng = crate::tests::make_test_prng();
+        let p = Qubo::make_random_qubo(1000, &mut prng, 0.01);
+        Qubo::write_qubo(&p, "test_large.qubo");
+
+        // read it back in
+        let q = Qubo::read_qubo("test_large.qubo");
+
+        // check that the two are the same
+        assert_eq!(p.q, q.q);
+        assert_eq!(p.c, q.c);
+        assert_eq!(p.num_x(), q.num_x());
+        assert_eq!(p.q.nnz(), q.q.nnz())
+    }
+
+    #[test]
+    fn test_is_symmetric_on_symmetric() {
+        let q = CsMat::<f64>::eye(3);
+        let c = Array1::<f64>::zeros(3);
+        let p = Qubo::new_with_c(q, c);
+        assert_eq!(p.is_symmetric(), true);
+    }
+
+    #[test]
+    fn test_is_symmetric_on_not_symmetric() {
+        // Create a QUBO from a list of tuples:
+        let x = vec![0, 0];
+        let y = vec![0, 2];
+        let q = vec![1.0, 1.0];
+        let c = vec![0.0, 0.0, 0.0];
+        let num_x = 3;
+        let p = Qubo::from_vec(x, y, q, c, num_x);
+
+        // make a symmetric QUBO from this QUBO
+        let p_sym = p.make_symmetric();
+
+        assert_eq!(p_sym.is_symmetric(), true);
+        assert_eq!(p.is_symmetric(), false);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(20, &mut prng, 0.3);
+        assert!(p.approx_eq(&p, 0.0));
+
+        // adding explicit zeros changes the sparsity pattern, but not the QUBO
+        let (mut rows, mut cols, mut values, c, num_x) = p.to_vec();
+        rows.extend([0, 19]);
+        cols.extend([19, 0]);
+        values.extend([0.0, 0.0]);
+        let mut p_zeros = Qubo::from_vec(rows, cols, values, c, num_x);
+        p_zeros.q = p_zeros.q.to_csc();
+        assert!(p.approx_eq(&p_zeros, 1E-12));
+        assert!(p_zeros.approx_eq(&p, 1E-12));
+
+        // small changes are within the tolerance, and large changes are not
+        let mut p_scaled = p.clone();
+        p_scaled.scale(1.0 + 1E-12);
+        assert!(p.approx_eq(&p_scaled, 1E-8));
+        p_scaled.scale(2.0);
+        assert!(!p.approx_eq(&p_scaled, 1E-8));
+
+        let mut p_offset = p.clone();
+        p_offset.offset = 1.0;
+        assert!(!p.approx_eq(&p_offset, 1E-8));
+
+        // the symmetric form has the same objective, but a different Q
+        assert!(!p.approx_eq(&p.make_symmetric(), 1E-8));
+        assert!(!p.approx_eq(&Qubo::make_random_qubo(10, &mut prng, 0.3), 1E-8));
+    }
+
+    #[test]
+    fn test_symmetrize() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(20, &mut prng, 0.3);
+
+        let mut p_sym = p.clone();
+        p_sym.symmetrize();
+        assert!(p_sym.is_symmetric());
+
+        // the objective is unchanged at random binary points
+        let xs = generate_random_binary_points(p.num_x(), 20, &mut prng);
+        for x in &xs {
+            assert!((p.eval_usize(x) - p_sym.eval_usize(x)).abs() < 1e-10);
+        }
+
+        // symmetrizing again does nothing
+        let mut p_twice = p_sym.clone();
+        p_twice.symmetrize();
+        assert_eq!(p_twice.q, p_sym.q);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(20, &mut prng, 0.3);
+
+        let mut p_normal = p.clone();
+        let factor = p_normal.normalize();
+
+        // every coefficient is now in [-1, 1], with at least one at the boundary
+        let max_abs = p_normal
+            .q
+            .data()
+            .iter()
+            .chain(p_normal.c.iter())
+            .fold(0.0, |acc: f64, &value| acc.max(value.abs()));
+        assert!((max_abs - 1.0).abs() < 1e-10);
+
+        // the objective is scaled uniformly, so the original objective can be recovered
+        let xs = generate_random_binary_points(p.num_x(), 20, &mut prng);
+        for x in &xs {
+            assert!((p.eval_usize(x) - factor * p_normal.eval_usize(x)).abs() < 1e-8);
+        }
+
+        // a QUBO with no coefficients is left alone
+        let mut p_zero = Qubo::new(CsMat::zero((3, 3)));
+        assert!((p_zero.normalize() - 1.0).abs() < 1e-10);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json_round_trip() {
+        let mut prng = make_test_prng();
+        let mut p = Qubo::make_random_qubo(30, &mut prng, 0.2);
+        p.offset = 2.5;
+
+        let json = serde_json::to_string(&p).unwrap();
+        let p_read: Qubo = serde_json::from_str(&json).unwrap();
+
+        // the matrix, linear term and offset are identical, so the objective is identical
+        assert_eq!(p.q, p_read.q);
+        assert_eq!(p.c, p_read.c);
+        assert_eq!(p.offset.to_bits(), p_read.offset.to_bits());
+
+        let xs = generate_random_binary_points(p.num_x(), 20, &mut prng);
+        for x in &xs {
+            assert_eq!(p.eval_usize(x).to_bits(), p_read.eval_usize(x).to_bits());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_bincode_round_trip() {
+        let mut prng = make_test_prng();
+        let mut p = Qubo::make_random_qubo(30, &mut prng, 0.2);
+        p.offset = 2.5;
+
+        let config = bincode::config::standard();
+        let bytes = bincode::serde::encode_to_vec(&p, config).unwrap();
+        let (p_read, num_read): (Qubo, usize) =
+            bincode::serde::decode_from_slice(&bytes, config).unwrap();
+
+        // every byte is read back, and the matrix, linear term and offset are identical
+        assert_eq!(num_read, bytes.len());
+        assert_eq!(p.q, p_read.q);
+        assert_eq!(p.c, p_read.c);
+        assert_eq!(p.offset.to_bits(), p_read.offset.to_bits());
+
+        let xs = generate_random_binary_points(p.num_x(), 20, &mut prng);
+        for x in &xs {
+            assert_eq!(p.eval_usize(x).to_bits(), p_read.eval_usize(x).to_bits());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_bad_triplets() {
+        let json = r#"{"num_x":2,"rows":[0,3],"cols":[0,1],"values":[1.0,2.0],"c":[0.0,0.0]}"#;
+        assert!(serde_json::from_str::<Qubo>(json).is_err());
+
+        let json = r#"{"num_x":2,"rows":[0],"cols":[0],"values":[1.0],"c":[0.0]}"#;
+        assert!(serde_json::from_str::<Qubo>(json).is_err());
+    }
+
+    #[test]
+    fn test_flip_delta() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(15, &mut prng, 0.4);
+
+        // the matrix is not symmetric, so the delta is taken from its symmetric form, in both storage orders
+        let p_sym = p.make_symmetric();
+        let p_csc = Qubo::new_with_c(p_sym.q.to_csc(), p_sym.c.clone());
+        assert!(!p.is_symmetric());
+
+        let xs = generate_random_binary_points(p.num_x(), 10, &mut prng);
+        for x in &xs {
+            let obj = p.eval_binary(x);
+            for i in 0..p.num_x() {
+                let mut x_flip = x.clone();
+                x_flip[i] = 1 - x_flip[i];
+                let expected = p.eval_binary(&x_flip) - obj;
+
+                assert!((p_sym.flip_delta(x, i) - expected).abs() < 1e-10);
+                assert!((p_csc.flip_delta(x, i) - expected).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fix_and_reduce() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.4);
+        let fixed = HashMap::from([(0, 1), (3, 0), (4, 1), (11, 1)]);
+
+        let p_reduced = p.fix_and_reduce(&fixed);
+        assert_eq!(p_reduced.num_x(), 8);
+
+        // expand a point of the reduced problem to the original problem
+        let free = (0..p.num_x())
+            .filter(|i| !fixed.contains_key(i))
+            .collect::<Vec<usize>>();
+        let expand = |y: &Array1<usize>| {
+            let mut x = Array1::zeros(p.num_x());
+            for (&i, &value) in &fixed {
+                x[i] = value;
+            }
+            for (k, &i) in free.iter().enumerate() {
+                x[i] = y[k];
+            }
+            x
+        };
+
+        // the objectives agree at every point, so the optimal objectives agree
+        let mut opt_reduced = f64::INFINITY;
+        let mut opt_original = f64::INFINITY;
+        for k in 0..(1usize << p_reduced.num_x()) {
+            let y = Array1::from_shape_fn(p_reduced.num_x(), |i| (k >> i) & 1);
+            let obj_reduced = p_reduced.eval_usize(&y);
+            let obj_original = p.eval_usize(&expand(&y));
+
+            assert!((obj_reduced - obj_original).abs() < 1e-10);
+            opt_reduced = opt_reduced.min(obj_reduced);
+            opt_original = opt_original.min(obj_original);
+        }
+        assert!((opt_reduced - opt_original).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_induced_subproblem() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.4);
+
+        // with the complement fixed, this is the same as fix_and_reduce
+        let fixed = HashMap::from([(0, 1), (3, 0), (4, 1), (11, 1)]);
+        let background = fixed
+            .iter()
+            .map(|(&i, &x_i)| (i, x_i as f64))
+            .collect::<HashMap<usize, f64>>();
+        let vars = (0..p.num_x())
+            .rev()
+            .filter(|i| !fixed.contains_key(i))
+            .collect::<Vec<usize>>();
+
+        let p_reduced = p.fix_and_reduce(&fixed);
+        let (p_sub, members) = p.induced_subproblem(&vars, &background);
+        assert_eq!(members, vec![1, 2, 5, 6, 7, 8, 9, 10]);
+        assert!((p_sub.offset - p_reduced.offset).abs() < 1e-10);
+        assert!((&p_sub.c - &p_reduced.c).iter().all(|d| d.abs() < 1e-10));
+        assert!((&p_sub.q - &p_reduced.q)
+            .data()
+            .iter()
+            .all(|d| d.abs() < 1e-10));
+
+        // a subset with a fractional background, where the variables that are not given are zero
+        let background = HashMap::from([(0, 0.5), (2, 1.0), (5, 0.25)]);
+        let (p_sub, members) = p.induced_subproblem(&[6, 1, 1, 9], &background);
+        assert_eq!(members, vec![1, 6, 9]);
+
+        for k in 0..(1usize << members.len()) {
+            let y = Array1::from_shape_fn(members.len(), |i| ((k >> i) & 1) as f64);
+            let mut x = Array1::<f64>::zeros(p.num_x());
+            for (&i, &x_i) in &background {
+                x[i] = x_i;
+            }
+            for (l, &i) in members.iter().enumerate() {
+                x[i] = y[l];
+            }
+            assert!((p_sub.eval(&y) - p.eval(&x)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_add_equality_penalty() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(4, &mut prng, 0.5);
+
+        // 2 x_0 - x_1 + 3 x_3 - x_0 = 2, with x_0 repeated
+        let coeffs = [(0, 2.0), (1, -1.0), (3, 3.0), (0, -1.0)];
+        let mut p_penalty = p.clone();
+        p_penalty.add_equality_penalty(&coeffs, 2.0, 5.0);
+
+        for k in 0..16usize {
+            let x = Array1::from_shape_fn(4, |i| (k >> i) & 1);
+            let lhs = coeffs
+                .iter()
+                .map(|&(i, a_i)| a_i * x[i] as f64)
+                .sum::<f64>();
+            let penalty = p_penalty.eval_usize(&x) - p.eval_usize(&x);
+
+            assert!((penalty - 5.0 * (lhs - 2.0).powi(2)).abs() < 1e-10);
+            assert_eq!(penalty.abs() < 1e-10, (lhs - 2.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_add_one_hot_and_at_most_one() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(4, &mut prng, 0.5);
+        let vars = [0, 2, 3];
+
+        let mut p_one_hot = p.clone();
+        p_one_hot.add_one_hot(&vars, 3.0);
+
+        let mut p_at_most_one = p.clone();
+        p_at_most_one.add_at_most_one(&vars, 3.0);
+
+        for k in 0..16usize {
+            let x = Array1::from_shape_fn(4, |i| (k >> i) & 1);
+            let active = vars.iter().filter(|&&i| x[i] == 1).count();
+            let one_hot_penalty = p_one_hot.eval_usize(&x) - p.eval_usize(&x);
+            let at_most_one_penalty = p_at_most_one.eval_usize(&x) - p.eval_usize(&x);
+
+            // feasible assignments have no penalty, and infeasible ones have at least the weight
+            if active == 1 {
+                assert!(one_hot_penalty.abs() < 1e-10);
+            } else {
+                assert!(one_hot_penalty >= 3.0 - 1e-10);
+            }
+
+            if active <= 1 {
+                assert!(at_most_one_penalty.abs() < 1e-10);
+            } else {
+                assert!(at_most_one_penalty >= 3.0 - 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nnz_and_density() {
+        let p = Qubo::from_vec(
+            vec![0, 1, 2],
+            vec![1, 0, 2],
+            vec![1.0, 2.0, 3.0],
+            vec![0.0; 4],
+            4,
+        );
+        assert_eq!(p.nnz(), 3);
+        assert!((p.density() - 3.0 / 16.0).abs() < 1e-12);
+
+        let empty = Qubo::new(CsMat::<f64>::zero((0, 0)));
+        assert_eq!(empty.nnz(), 0);
+        assert!(empty.density().abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_to_maxcut() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(5, &mut prng, 0.6);
+        let (graph, offset) = p.to_maxcut();
+        assert_eq!(graph.num_nodes, 6);
+
+        // every cut maps to the objective of the point it encodes
+        for k in 0..(1usize << 6) {
+            let sides = Array1::from_shape_fn(6, |i| (k >> i) & 1);
+            let cut = graph
+                .edges
+                .iter()
+                .filter(|&&(u, v, _)| sides[u] != sides[v])
+                .map(|&(_, _, weight)| weight)
+                .sum::<f64>();
+
+            // complement the cut if the auxiliary node is on the one side
+            let x = Array1::from_shape_fn(5, |i| sides[i] ^ sides[5]);
+            assert!((p.eval_usize(&x) - (offset - cut)).abs() < 1e-10);
+        }
+
+        // the max-cut QUBO of a graph maps back to the same cut weights
+        let mut triangle = Graph::new(3);
+        triangle
+            .add_edge(0, 1, 1.0)
+            .add_edge(1, 2, 2.0)
+            .add_edge(0, 2, 3.0);
+        let (graph, offset) = max_cut_qubo(&triangle).to_maxcut();
+        assert!(offset.abs() < 1e-10);
+        assert!(graph.edges.iter().all(|&(u, v, _)| u != 3 && v != 3));
+        for &(u, v, weight) in &graph.edges {
+            let expected = triangle
+                .edges
+                .iter()
+                .find(|&&(a, b, _)| (a, b) == (u, v) || (b, a) == (u, v))
+                .unwrap()
+                .2;
+            assert!((weight - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_direct_sum() {
+        let mut prng = make_test_prng();
+        let p_1 = Qubo::make_random_qubo(3, &mut prng, 0.8);
+        let p_2 = Qubo::make_random_qubo(4, &mut prng, 0.8);
+        let p = p_1.direct_sum(&p_2);
+        assert_eq!(p.num_x(), 7);
+
+        for k in 0..(1usize << 7) {
+            let x = Array1::from_shape_fn(7, |i| (k >> i) & 1);
+            let x_1 = x.slice(ndarray::s![..3]).to_owned();
+            let x_2 = x.slice(ndarray::s![3..]).to_owned();
+
+            let obj_parts = p_1.eval_usize(&x_1) + p_2.eval_usize(&x_2);
+            assert!((p.eval_usize(&x) - obj_parts).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_reorder_rcm() {
+        // a path graph with its variables shuffled, so that the bandwidth is large
+        let order = [7, 2, 9, 0, 5, 3, 8, 1, 6, 4];
+        let mut q = TriMat::<f64>::new((10, 10));
+        for pair in order.windows(2) {
+            q.add_triplet(pair[0], pair[1], 1.0);
+            q.add_triplet(pair[1], pair[0], 1.0);
+        }
+        let c = Array1::from_shape_fn(10, |i| i as f64 - 4.5);
+        let p = Qubo::new_with_c(q.to_csr(), c);
+
+        let (p_rcm, perm) = p.reorder_rcm();
+
+        // the permutation visits every variable once
+        let mut sorted_perm = perm.clone();
+        sorted_perm.sort_unstable();
+        assert_eq!(sorted_perm, (0..10).collect::<Vec<usize>>());
+
+        // a path can always be ordered to have a bandwidth of one
+        let bandwidth = |p: &Qubo| p.q.iter().map(|(_, (i, j))| i.abs_diff(j)).max().unwrap();
+        assert!(bandwidth(&p) > 1);
+        assert_eq!(bandwidth(&p_rcm), 1);
 
-        assert_eq!(obj, 1.0);
+        // mapping a solution back gives the same objective
+        let mut prng = make_test_prng();
+        for y in &generate_random_binary_points(10, 20, &mut prng) {
+            let x = crate::utils::unpermute(y, &perm);
+            assert!((p_rcm.eval_usize(y) - p.eval_usize(&x)).abs() < 1e-10);
+        }
     }
 
     #[test]
-    fn test_qubo_eval_grad() {
-        let q = CsMat::<f64>::eye(3);
-        let c = Array1::<f64>::zeros(3);
-        let p = Qubo::new_with_c(q, c);
-        let x_0 = Array1::from_vec(vec![1.0, 1.0, 1.0]);
-        let grad = p.eval_grad(&x_0);
+    fn test_variable_degrees() {
+        // x_0 - x_1 is stored in both orders, x_1 - x_2 only once, x_2 - x_3 cancels, and the diagonal is ignored
+        let p = Qubo::from_vec(
+            vec![0, 1, 1, 2, 3, 3],
+            vec![1, 0, 2, 3, 2, 3],
+            vec![1.0, 2.0, -1.0, 3.0, -3.0, 5.0],
+            vec![0.0; 4],
+            4,
+        );
 
-        assert_eq!(grad, Array1::from_vec(vec![1.0, 1.0, 1.0]));
+        assert_eq!(p.variable_degrees(), Array1::from_vec(vec![1, 2, 1, 0]));
     }
 
     #[test]
-    fn test_qubo_eval_grad_non_symmetric() {
-        // Create a QUBO from a list of tuples:
-        let x = vec![0];
-        let y = vec![2];
-        let q = vec![1.0];
-        let c = vec![0.0, 0.0, 0.0];
-        let num_x = 3;
-
-        // actually create the QUBO
-        let p = Qubo::from_vec(x, y, q, c, num_x);
-        let x_0 = Array1::from_vec(vec![1.0, 1.0, 1.0]);
-        let grad = p.eval_grad(&x_0);
+    fn test_negate() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(3, &mut prng, 0.8);
+        let p_neg = p.negate();
 
-        assert_eq!(grad, Array1::from_vec(vec![0.5, 0.0, 0.5]));
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| (k >> i) & 1);
+            assert!((p.eval_usize(&x) + p_neg.eval_usize(&x)).abs() < 1e-10);
+        }
     }
 
     #[test]
-    fn test_make_symmetric_from_symmetric() {
-        let q = CsMat::<f64>::eye(3);
-        let c = Array1::<f64>::zeros(3);
-        let p = Qubo::new_with_c(q, c);
+    fn test_offset() {
+        let mut prng = make_test_prng();
+        let mut p = Qubo::make_random_qubo(6, &mut prng, 0.5);
+        let p_zero = p.clone();
+        p.offset = 1.75;
+
+        let p_neg = p.negate();
+        let mut p_scaled = p.clone();
+        p_scaled.scale(-2.0);
+        let p_sum = p.direct_sum(&p);
         let p_sym = p.make_symmetric();
 
-        assert_eq!(p_sym.q.nnz(), 3);
-        assert_eq!(p_sym.q, CsMat::<f64>::eye(3));
+        for k in 0..(1usize << p.num_x()) {
+            let x = Array1::from_shape_fn(p.num_x(), |i| (k >> i) & 1);
+            let obj = p.eval_usize(&x);
+
+            // the offset is part of the objective in both evaluations
+            assert!((obj - p_zero.eval_usize(&x) - 1.75).abs() < 1e-10);
+            assert!((p.eval_binary(&x) - obj).abs() < 1e-10);
+
+            // and it is carried through the transforms
+            assert!((p_neg.eval_usize(&x) + obj).abs() < 1e-10);
+            assert!((p_scaled.eval_usize(&x) + 2.0 * obj).abs() < 1e-10);
+            assert!((p_sym.eval_usize(&x) - obj).abs() < 1e-10);
+
+            let x_sum = x.iter().chain(x.iter()).copied().collect::<Array1<usize>>();
+            assert!((p_sum.eval_usize(&x_sum) - 2.0 * obj).abs() < 1e-10);
+        }
+
+        // with every variable fixed, the reduced QUBO is only the offset, which is the objective at the fixed point
+        let x = Array1::from_vec(vec![1, 0, 1, 1, 0, 1]);
+        let fixed = (0..p.num_x())
+            .map(|i| (i, x[i]))
+            .collect::<HashMap<usize, usize>>();
+        let p_fixed = p.fix_and_reduce(&fixed);
+        assert_eq!(p_fixed.num_x(), 0);
+        assert!((p_fixed.offset - p.eval_usize(&x)).abs() < 1e-10);
+
+        // the trivial bounds are shifted by the offset
+        let (lower_bound, upper_bound) = p.trivial_bounds();
+        let (lower_zero, upper_zero) = p_zero.trivial_bounds();
+        assert!((lower_bound - lower_zero - 1.75).abs() < 1e-10);
+        assert!((upper_bound - upper_zero - 1.75).abs() < 1e-10);
     }
 
     #[test]
-    fn test_make_symmetric_from_non_symmetric() {
-        // Create a QUBO from a list of tuples:
-        let x = vec![0, 0];
-        let y = vec![0, 2];
-        let q = vec![1.0, 1.0];
-        let c = vec![0.0, 0.0, 0.0];
-        let num_x = 3;
-        let p = Qubo::from_vec(x, y, q, c, num_x);
+    fn test_complement_variables() {
+        // a non-symmetric QUBO, so that both Q_ij and Q_ji are accounted for
+        let p = Qubo::from_vec(
+            vec![0, 0, 1, 2, 2],
+            vec![0, 1, 2, 0, 2],
+            vec![2.0, -3.0, 1.5, 4.0, -1.0],
+            vec![1.0, -2.0, 0.5],
+            3,
+        );
+        let p_complement = p.complement_variables();
 
-        // make a symmetric QUBO from this QUBO
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| (k >> i) & 1);
+            let y = x.mapv(|x_i| 1 - x_i);
+            assert!((p.eval_usize(&x) - p_complement.eval_usize(&y)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_marginal() {
+        let mut prng = make_test_prng();
+        let mut p = Qubo::make_random_qubo(15, &mut prng, 0.4);
+        p.offset = -3.0;
+        assert!(!p.is_symmetric());
         let p_sym = p.make_symmetric();
 
-        assert_eq!(p_sym.q.nnz(), 3);
-        assert_eq!(p_sym.q.get(0, 0), Some(&1.0f64));
-        assert_eq!(p_sym.q.get(0, 2), Some(&0.5f64));
-        assert_eq!(p_sym.q.get(2, 0), Some(&0.5f64));
+        let xs = generate_random_binary_points(p.num_x(), 10, &mut prng);
+        for x in &xs {
+            // the contributions of the variables sum to the objective without the offset
+            let total = (0..p.num_x()).map(|i| p.marginal(x, i)).sum::<f64>();
+            assert!((total - (p.eval_usize(x) - p.offset)).abs() < 1e-10);
+
+            for i in 0..p.num_x() {
+                if x[i] == 0 {
+                    assert!(p.marginal(x, i).abs() < 1e-10);
+                } else {
+                    // the marginal only counts half of the interactions that the flip removes
+                    let (diagonal, _) = p.diagonal_and_coupling(x, i);
+                    let linear = p.c[i] + 0.5 * diagonal;
+                    let removed = -p_sym.flip_delta(x, i);
+                    assert!((2.0 * p.marginal(x, i) - linear - removed).abs() < 1e-10);
+                }
+            }
+        }
     }
 
     #[test]
-    fn test_make_symmetric_from_non_symmetric_off_diagonal() {
-        // Create a QUBO from a list of tuples:
-        let x = vec![0, 0, 2];
-        let y = vec![0, 2, 0];
-        let q = vec![1.0, 1.5, 0.5];
-        let c = vec![0.0, 0.0, 0.0];
-        let num_x = 3;
-        let p = Qubo::from_vec(x, y, q, c, num_x);
-
-        // make a symmetric QUBO from this QUBO
+    fn test_neighbors() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(15, &mut prng, 0.4);
         let p_sym = p.make_symmetric();
 
-        assert_eq!(p_sym.q.nnz(), 3);
-        assert_eq!(p_sym.q.get(0, 0), Some(&1.0f64));
-        assert_eq!(p_sym.q.get(0, 2), Some(&1.0f64));
-        assert_eq!(p_sym.q.get(2, 0), Some(&1.0f64));
+        let xs = generate_random_binary_points(p.num_x(), 10, &mut prng);
+        for x in &xs {
+            let neighbors = p.neighbors(x).collect::<Vec<_>>();
+            assert_eq!(neighbors.len(), p.num_x());
+
+            for (i, delta) in neighbors {
+                assert!((delta - p_sym.flip_delta(x, i)).abs() < 1e-10);
+            }
+        }
     }
 
     #[test]
-    fn read_write_consistency() {
-        // make a qubo and write it to a file
-        let mut prng = crate::tests::make_test_prng();
-        let p = Qubo::make_random_qubo(10, &mut prng, 0.1);
-        Qubo::write_qubo(&p, "test.qubo");
+    fn test_trivial_bounds() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.4);
+        let (lower_bound, upper_bound) = p.trivial_bounds();
 
-        // now read it back in
-        let q = Qubo::read_qubo("test.qubo");
+        // every binary point is within the bounds
+        for k in 0..(1usize << p.num_x()) {
+            let obj = p.eval_usize(&Array1::from_shape_fn(p.num_x(), |i| (k >> i) & 1));
+            assert!(lower_bound <= obj + 1e-10);
+            assert!(obj <= upper_bound + 1e-10);
+        }
 
-        // check that the two are the same
-        assert_eq!(p.q, q.q);
-        assert_eq!(p.c, q.c);
-        assert_eq!(p.num_x(), q.num_x());
-        assert_eq!(p.q.nnz(), q.q.nnz())
+        // with only a diagonal and linear term the bounds are exact, so the diagonal is only counted once
+        let p = Qubo::new_with_c(CsMat::eye(3), Array1::from_vec(vec![-1.0, 0.0, 1.0]));
+        let (lower_bound, upper_bound) = p.trivial_bounds();
+        assert!((lower_bound + 0.5).abs() < 1e-10);
+        assert!((upper_bound - 2.0).abs() < 1e-10);
     }
 
     #[test]
-    fn large_scale_write_qubo() {
-        // make a large qubo instance and write it to file
-        let mut prng = crate::tests::make_test_prng();
-        let p = Qubo::make_random_qubo(1000, &mut prng, 0.01);
-        Qubo::write_qubo(&p, "test_large.qubo");
+    fn test_make_random_qubo_with_dist() {
+        // the default generator is the uniform distribution on [-0.5, 0.5]
+        let p = Qubo::make_random_qubo(20, &mut make_test_prng(), 0.3);
+        let p_dist = Qubo::make_random_qubo_with_dist(
+            20,
+            &mut make_test_prng(),
+            0.3,
+            CoeffDist::Uniform(0.5),
+        );
+        assert_eq!(p.q, p_dist.q);
+        assert_eq!(p.c, p_dist.c);
 
-        // read it back in
-        let q = Qubo::read_qubo("test_large.qubo");
+        let p = Qubo::make_random_qubo_with_dist(
+            20,
+            &mut make_test_prng(),
+            0.3,
+            CoeffDist::Uniform(3.0),
+        );
+        assert!(p.q.data().iter().chain(p.c.iter()).all(|v| v.abs() <= 3.0));
 
-        // check that the two are the same
-        assert_eq!(p.q, q.q);
-        assert_eq!(p.c, q.c);
-        assert_eq!(p.num_x(), q.num_x());
-        assert_eq!(p.q.nnz(), q.q.nnz())
+        let p =
+            Qubo::make_random_qubo_with_dist(20, &mut make_test_prng(), 0.3, CoeffDist::Integer(2));
+        assert!(p
+            .q
+            .data()
+            .iter()
+            .chain(p.c.iter())
+            .all(|v| v.abs() <= 2.0 && (v - v.round()).abs() < 1e-15));
+
+        // the sample mean and variance of the normal distribution should be close to 0 and 1
+        let p =
+            Qubo::make_random_qubo_with_dist(200, &mut make_test_prng(), 0.5, CoeffDist::Normal);
+        let values = p.q.data();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        assert!(mean.abs() < 0.05);
+        assert!((var - 1.0).abs() < 0.05);
     }
 
     #[test]
-    fn test_is_symmetric_on_symmetric() {
-        let q = CsMat::<f64>::eye(3);
-        let c = Array1::<f64>::zeros(3);
-        let p = Qubo::new_with_c(q, c);
-        assert_eq!(p.is_symmetric(), true);
+    fn test_is_convex() {
+        let p = Qubo::new(CsMat::eye(3));
+        assert!(p.is_convex());
+
+        // this hessian has the eigenvalues -1 and 3
+        let p = Qubo::from_vec(
+            vec![0, 0, 1, 1],
+            vec![0, 1, 0, 1],
+            vec![1.0, 2.0, 2.0, 1.0],
+            vec![0.0; 2],
+            2,
+        );
+        assert!(!p.is_convex());
+        assert!(p.convex_symmetric_form().is_convex());
+
+        // only the symmetric part of the hessian matters, so this upper triangular hessian is also not convex
+        let p = Qubo::from_vec(
+            vec![0, 0, 1],
+            vec![0, 1, 1],
+            vec![1.0, 4.0, 1.0],
+            vec![0.0; 2],
+            2,
+        );
+        assert!(!p.is_convex());
     }
 
     #[test]
-    fn test_is_symmetric_on_not_symmetric() {
-        // Create a QUBO from a list of tuples:
-        let x = vec![0, 0];
-        let y = vec![0, 2];
-        let q = vec![1.0, 1.0];
-        let c = vec![0.0, 0.0, 0.0];
-        let num_x = 3;
-        let p = Qubo::from_vec(x, y, q, c, num_x);
+    fn test_condition_estimate() {
+        // the eigenvalues are 1 and 4
+        let p = Qubo::from_vec(vec![0, 1], vec![0, 1], vec![1.0, 4.0], vec![0.0; 2], 2);
+        assert!((p.condition_estimate() - 4.0).abs() < 1e-8);
+        assert!((Qubo::new(CsMat::eye(3)).condition_estimate() - 1.0).abs() < 1e-8);
 
-        // make a symmetric QUBO from this QUBO
-        let p_sym = p.make_symmetric();
+        // this hessian has the eigenvalues -1 and 3, so the estimate is negative
+        let p = Qubo::from_vec(
+            vec![0, 0, 1, 1],
+            vec![0, 1, 0, 1],
+            vec![1.0, 2.0, 2.0, 1.0],
+            vec![0.0; 2],
+            2,
+        );
+        assert!((p.condition_estimate() + 3.0).abs() < 1e-8);
 
-        assert_eq!(p_sym.is_symmetric(), true);
-        assert_eq!(p.is_symmetric(), false);
+        // on a random QUBO, the estimate is close to the ratio of the dense eigenvalues
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(20, &mut prng, 0.3).make_symmetric();
+        let eigs = p.hess_eigenvalues();
+        let min_eig = eigs.iter().fold(f64::INFINITY, |acc, &eig| acc.min(eig));
+        let radius = eigs.iter().fold(0.0, |acc: f64, &eig| acc.max(eig.abs()));
+        assert!(
+            (p.condition_estimate() - radius / min_eig).abs() < 1e-3 * (radius / min_eig).abs()
+        );
+
+        let p_convex = p.make_diag_transform(1.0 - min_eig);
+        assert!(p_convex.condition_estimate() > 0.0);
     }
 
     #[test]
@@ -770,4 +3913,490 @@ mod tests {
             assert!((obj - obj_convex).abs() < 1e-5);
         }
     }
+
+    #[test]
+    fn test_convexify() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(20, &mut prng, 0.3);
+        let (p_convex, shift) = p.convexify();
+
+        // the shift is the smallest that makes the hessian convex
+        let min_eig = p_convex
+            .hess_eigenvalues()
+            .iter()
+            .fold(f64::INFINITY, |acc, &eig| acc.min(eig));
+        assert!(shift > 0.0);
+        assert!(p_convex.is_convex());
+        assert!(min_eig.abs() < 1e-8);
+
+        // the objective is unchanged at binary points
+        for x in &generate_random_binary_points(p.num_x(), 50, &mut prng) {
+            assert!((p.eval_usize(x) - p_convex.eval_usize(x)).abs() < 1e-8);
+        }
+
+        // a convex QUBO is not shifted, up to the rounding error of the eigenvalues
+        let (p_same, shift) = p_convex.convexify();
+        assert!(shift <= 1e-10);
+        assert!(p_same.approx_eq(&p_convex, 1e-12));
+    }
+
+    #[test]
+    fn test_read_qplib() {
+        let qplib = "TEST_0001 # problem name
+QBN # problem type
+minimize # objective sense
+3 # number of variables
+3 # number of quadratic terms in objective
+1 1 2.0
+2 1 -1.0
+3 2 4.0
+0.5 # default value for linear coefficients in objective
+1 # number of non-default linear coefficients in objective
+3 -2.0
+1.5 # objective constant
+";
+        let path = std::env::temp_dir().join("hercules_test_read.qplib");
+        std::fs::write(&path, qplib).unwrap();
+        let p = Qubo::read_qplib(&path).unwrap();
+
+        // the lower triangle is mirrored and the indices are shifted to be 0-based
+        let x = vec![0, 1, 1, 2, 2];
+        let y = vec![0, 0, 1, 1, 2];
+        let q = vec![2.0, -2.0, 0.0, 8.0, 0.0];
+        let mut target = Qubo::from_vec(x, y, q, vec![0.5, 0.5, -2.0], 3).make_symmetric();
+        target.offset = 1.5;
+
+        assert_eq!(p.num_x(), 3);
+        assert!(p.is_symmetric());
+        assert_eq!(p.q.get(0, 1), Some(&-1.0));
+        assert_eq!(p.q.get(1, 0), Some(&-1.0));
+        assert_eq!(p.c, target.c);
+        assert!((p.offset - 1.5).abs() < 1e-10);
+
+        // check every binary point has the same objective as the hand written QUBO
+        for k in 0..8 {
+            let x = Array1::from_vec(vec![k & 1, (k >> 1) & 1, (k >> 2) & 1]);
+            assert!((p.eval_usize(&x) - target.eval_usize(&x)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_read_qplib_constrained() {
+        let qplib = "TEST_0002\nQBL\nminimize\n2\n1 # number of constraints\n0\n0.0\n0\n0.0\n";
+        let path = std::env::temp_dir().join("hercules_test_constrained.qplib");
+        std::fs::write(&path, qplib).unwrap();
+
+        assert!(matches!(
+            Qubo::read_qplib(&path),
+            Err(QuboError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_dimacs_maxcut() {
+        let dimacs = "c a triangle, with a heavier edge
+p edge 3 3
+e 1 2
+e 2 3 2.5
+c comments can be anywhere
+e 1 3
+";
+        let path = std::env::temp_dir().join("hercules_test_maxcut.mc");
+        std::fs::write(&path, dimacs).unwrap();
+        let p = Qubo::from_dimacs_maxcut(&path).unwrap();
+
+        let mut graph = Graph::new(3);
+        graph
+            .add_edge(0, 1, 1.0)
+            .add_edge(1, 2, 2.5)
+            .add_edge(0, 2, 1.0);
+        let target = max_cut_qubo(&graph);
+
+        assert_eq!(p.q, target.q);
+        assert_eq!(p.c, target.c);
+    }
+
+    #[test]
+    fn test_from_wcnf() {
+        let wcnf = "c a tiny instance, with a hard clause and a repeated variable
+p wcnf 3 6 20
+20 1 2 0
+3 -1 0
+2 -2 3 0
+1 -3 0
+4 1 -1 0
+5 -2 -2 0
+";
+        let path = std::env::temp_dir().join("hercules_test.wcnf");
+        std::fs::write(&path, wcnf).unwrap();
+        let p = Qubo::from_wcnf(&path).unwrap();
+        assert_eq!(p.num_x(), 3);
+
+        // violating the hard clause costs one more than all the soft clauses
+        let clauses: [(f64, &[i64]); 6] = [
+            (16.0, &[1, 2]),
+            (3.0, &[-1]),
+            (2.0, &[-2, 3]),
+            (1.0, &[-3]),
+            (4.0, &[1, -1]),
+            (5.0, &[-2, -2]),
+        ];
+        let violated_weight = |x: &Array1<usize>| {
+            clauses
+                .iter()
+                .filter(|(_, literals)| {
+                    !literals.iter().any(|&l| {
+                        let x_i = x[l.unsigned_abs() as usize - 1];
+                        (l > 0) == (x_i == 1)
+                    })
+                })
+                .map(|(weight, _)| weight)
+                .sum::<f64>()
+        };
+
+        // the objective is the violated weight at every point, so the optimum matches brute force
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| (k >> i) & 1);
+            assert!((p.eval_usize(&x) - violated_weight(&x)).abs() < 1e-10);
+        }
+        let (x_opt, obj_opt) = brute_force(&p);
+        assert_eq!(x_opt, Array1::from_vec(vec![1, 0, 0]));
+        assert!((obj_opt - 3.0).abs() < 1e-10);
+        assert!((violated_weight(&x_opt) - obj_opt).abs() < 1e-10);
+
+        // the newer format has no problem line and marks hard clauses with h
+        std::fs::write(
+            &path,
+            "h 1 2 0\n3 -1 0\n2 -2 3 0\n1 -3 0\n4 1 -1 0\n5 -2 -2 0\n",
+        )
+        .unwrap();
+        let p_new = Qubo::from_wcnf(&path).unwrap();
+        assert_eq!(p_new.q, p.q);
+        assert_eq!(p_new.c, p.c);
+        assert!((p_new.offset - p.offset).abs() < 1e-10);
+
+        // clauses of three literals, and clauses without the terminating zero are rejected
+        std::fs::write(&path, "p wcnf 3 1\n1 1 2 3 0\n").unwrap();
+        assert!(matches!(
+            Qubo::from_wcnf(&path),
+            Err(QuboError::Unsupported(_))
+        ));
+        std::fs::write(&path, "p wcnf 3 1\n1 1 2\n").unwrap();
+        assert!(matches!(Qubo::from_wcnf(&path), Err(QuboError::Parse(_))));
+    }
+
+    #[test]
+    fn test_from_matrix_market() {
+        let mtx = "%%MatrixMarket matrix coordinate real symmetric
+% the lower triangle of a 3 by 3 matrix
+3 3 4
+1 1 2.0
+2 1 -1.0
+3 2 4.0
+3 3 1.5
+";
+        let q_path = std::env::temp_dir().join("hercules_test_read.mtx");
+        std::fs::write(&q_path, mtx).unwrap();
+        let c_path = std::env::temp_dir().join("hercules_test_read_c.txt");
+        std::fs::write(&c_path, "0.5\n-1.0\n2.0\n").unwrap();
+
+        let p = Qubo::from_matrix_market(&q_path, Some(&c_path)).unwrap();
+        let target = Qubo::from_vec(
+            vec![0, 0, 1, 1, 2, 2],
+            vec![0, 1, 0, 2, 1, 2],
+            vec![2.0, -1.0, -1.0, 4.0, 4.0, 1.5],
+            vec![0.5, -1.0, 2.0],
+            3,
+        );
+
+        assert_eq!(p.q, target.q);
+        assert_eq!(p.c, target.c);
+
+        // without a linear term file, the linear term is zero
+        let p = Qubo::from_matrix_market(&q_path, None).unwrap();
+        assert_eq!(p.c, Array1::<f64>::zeros(3));
+    }
+
+    #[test]
+    fn test_from_matrix_market_errors() {
+        let path = std::env::temp_dir().join("hercules_test_array.mtx");
+        std::fs::write(
+            &path,
+            "%%MatrixMarket matrix array real general\n2 2\n1.0\n0.0\n0.0\n1.0\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            Qubo::from_matrix_market(&path, None),
+            Err(QuboError::Unsupported(_))
+        ));
+
+        let path = std::env::temp_dir().join("hercules_test_count.mtx");
+        std::fs::write(
+            &path,
+            "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1.0\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            Qubo::from_matrix_market(&path, None),
+            Err(QuboError::Parse(_))
+        ));
+
+        let c_path = std::env::temp_dir().join("hercules_test_count_c.txt");
+        std::fs::write(
+            &path,
+            "%%MatrixMarket matrix coordinate pattern general\n2 2 1\n1 2\n",
+        )
+        .unwrap();
+        std::fs::write(&c_path, "1.0\n").unwrap();
+        assert!(matches!(
+            Qubo::from_matrix_market(&path, Some(&c_path)),
+            Err(QuboError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_dimacs_maxcut_errors() {
+        let path = std::env::temp_dir().join("hercules_test_maxcut_count.mc");
+        std::fs::write(&path, "p edge 3 2\ne 1 2\n").unwrap();
+        assert!(matches!(
+            Qubo::from_dimacs_maxcut(&path),
+            Err(QuboError::Parse(_))
+        ));
+
+        let path = std::env::temp_dir().join("hercules_test_maxcut_index.mc");
+        std::fs::write(&path, "p edge 3 1\ne 1 4\n").unwrap();
+        assert!(matches!(
+            Qubo::from_dimacs_maxcut(&path),
+            Err(QuboError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_orlib_bqp() {
+        // two problems, the first with the line breaks in unusual places
+        let data = "2\n3 4\n1 1 2\n1 2 -3 2 3\n4\n3 3 -1\n2 1\n1 2 5\n";
+        let path = std::env::temp_dir().join("hercules_test_orlib.txt");
+        std::fs::write(&path, data).unwrap();
+
+        let problems = Qubo::from_orlib_bqp_all(&path).unwrap();
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].num_x(), 3);
+        assert_eq!(problems[1].num_x(), 2);
+
+        // the QUBO is the negative of the objective x^T Q x, with the listed pairs mirrored
+        let p = &problems[0];
+        for k in 0..8usize {
+            let x = Array1::from_shape_fn(3, |i| ((k >> i) & 1) as f64);
+            let objective =
+                2.0 * x[0] - 1.0 * x[2] + 2.0 * (-3.0 * x[0] * x[1] + 4.0 * x[1] * x[2]);
+            assert!((p.eval(&x) + objective).abs() < 1e-10);
+        }
+
+        // the single problem reader returns the first problem
+        let p_first = Qubo::from_orlib_bqp(&path).unwrap();
+        assert_eq!(p_first.q, problems[0].q);
+        assert_eq!(p_first.c, problems[0].c);
+
+        // a missing problem, and an out of bounds index
+        std::fs::write(&path, "2\n1 1\n1 1 1\n").unwrap();
+        assert!(matches!(
+            Qubo::from_orlib_bqp_all(&path),
+            Err(QuboError::Parse(_))
+        ));
+        std::fs::write(&path, "1\n2 1\n1 3 1\n").unwrap();
+        assert!(matches!(
+            Qubo::from_orlib_bqp(&path),
+            Err(QuboError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_sparse_round_trip() {
+        let p = make_solver_qubo();
+
+        let mut buffer = Vec::new();
+        p.write_sparse(&mut buffer).unwrap();
+        let p_read = Qubo::read_sparse(buffer.as_slice()).unwrap();
+
+        assert_eq!(p.num_x(), p_read.num_x());
+
+        // the diagonal is moved into the linear term, so compare the objective at binary points
+        let mut prng = make_test_prng();
+        let xs = generate_random_binary_points(p.num_x(), 50, &mut prng);
+
+        for x in &xs {
+            assert!((p.eval_usize(x) - p_read.eval_usize(x)).abs() < 1e-8);
+        }
+
+        // writing the read QUBO should give the same file
+        let mut buffer_read = Vec::new();
+        p_read.write_sparse(&mut buffer_read).unwrap();
+        let p_reread = Qubo::read_sparse(buffer_read.as_slice()).unwrap();
+
+        assert_eq!(p_read.q, p_reread.q);
+        assert_eq!(p_read.c, p_reread.c);
+    }
+
+    #[test]
+    fn test_bin_round_trip() {
+        let mut p = make_solver_qubo();
+        p.offset = -1.25;
+
+        // both storage orders are kept as they are
+        for p in [p.clone(), Qubo::new_with_c(p.q.to_csc(), p.c.clone())] {
+            let mut buffer = Vec::new();
+            p.write_bin(&mut buffer).unwrap();
+            let p_read = Qubo::read_bin(buffer.as_slice()).unwrap();
+
+            assert_eq!(p.q, p_read.q);
+            assert_eq!(p.q.is_csc(), p_read.q.is_csc());
+            assert_eq!(p.c, p_read.c);
+            assert_eq!(p.offset.to_bits(), p_read.offset.to_bits());
+        }
+
+        let mut buffer = Vec::new();
+        p.write_bin(&mut buffer).unwrap();
+
+        // a different magic, a newer version, a truncated file, and a broken matrix are all rejected
+        let mut bad_magic = buffer.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(
+            Qubo::read_bin(bad_magic.as_slice()),
+            Err(QuboError::Parse(_))
+        ));
+
+        let mut bad_version = buffer.clone();
+        bad_version[8] = 2;
+        assert!(matches!(
+            Qubo::read_bin(bad_version.as_slice()),
+            Err(QuboError::Unsupported(_))
+        ));
+
+        assert!(matches!(
+            Qubo::read_bin(&buffer[..buffer.len() - 1]),
+            Err(QuboError::Io(_))
+        ));
+
+        // the first index pointer comes right after the header, and must be zero
+        let mut bad_matrix = buffer;
+        bad_matrix[29] = 1;
+        assert!(matches!(
+            Qubo::read_bin(bad_matrix.as_slice()),
+            Err(QuboError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_sparse_bad_count() {
+        let sparse = "2 3\n0 0 1.0\n0 1 -2.0\n";
+        assert!(matches!(
+            Qubo::read_sparse(sparse.as_bytes()),
+            Err(QuboError::Parse(_))
+        ));
+
+        // a header that does not match the file fails without allocating for the number of variables it claims
+        let sparse = "1000000000000000000 5\n0 1 -2.0\n";
+        assert!(matches!(
+            Qubo::read_sparse(sparse.as_bytes()),
+            Err(QuboError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_eval_binary() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+        let xs = generate_random_binary_points(p.num_x(), 50, &mut prng);
+
+        for x in &xs {
+            assert!((p.eval_binary(x) - p.eval_usize(x)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_eval_many() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+        let xs = generate_random_binary_points(p.num_x(), 50, &mut prng);
+
+        let objs = p.eval_many(&xs);
+        assert_eq!(objs.len(), xs.len());
+        for (x, obj) in xs.iter().zip(objs.iter()) {
+            assert!((p.eval_binary(x) - obj).abs() < 1e-10);
+        }
+
+        assert!(p.eval_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_ising_round_trip() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(8, &mut prng, 0.5);
+        let ising = p.to_ising();
+        let p_round = Qubo::from_ising(&ising);
+
+        // check every binary assignment
+        for k in 0..(1usize << p.num_x()) {
+            let x = Array1::from_shape_fn(p.num_x(), |i| ((k >> i) & 1) as f64);
+            let s = 2.0 * &x - 1.0;
+
+            let obj = p.eval(&x);
+            assert!((obj - ising.energy(&s)).abs() < 1e-10);
+            assert!((obj - p_round.eval(&x)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_builder_accumulates() {
+        let mut builder = QuboBuilder::new();
+        builder.set_size(4);
+        builder.add_quadratic(0, 0, 1.0).add_quadratic(0, 0, 2.0);
+        builder.add_quadratic(0, 2, 1.0).add_quadratic(2, 0, 3.0);
+        builder.add_linear(1, -1.0).add_linear(1, -0.5);
+
+        let p = builder.build();
+
+        assert_eq!(p.num_x(), 4);
+        assert_eq!(p.q.get(0, 0), Some(&3.0));
+        assert_eq!(p.q.get(0, 2), Some(&2.0));
+        assert_eq!(p.q.get(2, 0), Some(&2.0));
+        assert!((p.c[1] + 1.5).abs() < 1e-12);
+        assert!(p.is_symmetric());
+    }
+
+    #[test]
+    fn test_builder_without_symmetrize() {
+        let mut builder = QuboBuilder::new();
+        builder.set_symmetrize(false);
+        builder.add_quadratic(0, 2, 1.0);
+        builder.add_linear(1, 1.0);
+
+        let p = builder.build();
+
+        // the size is inferred from the largest index
+        assert_eq!(p.num_x(), 3);
+        assert_eq!(p.q.get(0, 2), Some(&1.0));
+        assert_eq!(p.q.get(2, 0), None);
+        assert!(!p.is_symmetric());
+    }
+
+    #[test]
+    fn test_builder_matches_from_vec() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(10, &mut prng, 0.3);
+
+        let mut builder = QuboBuilder::new();
+        for (&value, (i, j)) in &p.q {
+            builder.add_quadratic(i, j, value);
+        }
+        for (i, &value) in p.c.iter().enumerate() {
+            builder.add_linear(i, value);
+        }
+        let p_built = builder.build();
+
+        let xs = generate_random_binary_points(p.num_x(), 20, &mut prng);
+        for x in &xs {
+            assert!((p.eval_usize(x) - p_built.eval_usize(x)).abs() < 1e-10);
+        }
+    }
 }
@@ -0,0 +1,69 @@
+//! This module contains functions for the continuous relaxation of a QUBO, where the binary variables are relaxed to
+//! the interval [0, 1]
+//!
+//! The optimal objective of the relaxation is a lower bound on the QUBO when the Hessian is positive semidefinite, and
+//! the relaxed solution is a natural target for rounding heuristics.
+
+use crate::qubo::Qubo;
+use crate::subproblemsolvers::clarabel_qp::solve_relaxed_qp;
+use ndarray::Array1;
+use std::collections::HashMap;
+
+/// Solves the continuous relaxation of the QUBO over $[0, 1]^n$ with the given variables fixed, and returns the
+/// relaxed optimal point and its objective.
+///
+/// The relaxation is solved with Clarabel, so the QUBO must be convex, see `Qubo::convex_symmetric_form` to get an
+/// equivalent convex QUBO.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::relaxation::solve_relaxation;
+/// use smolprng::{PRNG, JsfLarge};
+/// use std::collections::HashMap;
+///
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(50, &mut prng, 0.1).convex_symmetric_form();
+///
+/// // solve the relaxation with x_0 fixed to 1
+/// let fixed = HashMap::from([(0, 1)]);
+/// let (x_relaxed, lower_bound) = solve_relaxation(&p, &fixed);
+/// ```
+pub fn solve_relaxation(qubo: &Qubo, fixed: &HashMap<usize, usize>) -> (Array1<f64>, f64) {
+    let (objective, x) = solve_relaxed_qp(qubo, fixed);
+    (x, objective)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::qubo::Qubo;
+    use crate::relaxation::solve_relaxation;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_solve_relaxation() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(10, &mut prng, 0.5).convex_symmetric_form();
+        let fixed = HashMap::from([(2, 1), (5, 0)]);
+
+        let (x, objective) = solve_relaxation(&p, &fixed);
+
+        // the relaxed solution is in the box, respects the fixings, and has the reported objective
+        assert!(x.iter().all(|&x_i| (-1E-6..=1.0 + 1E-6).contains(&x_i)));
+        assert!((x[2] - 1.0).abs() < 1E-10);
+        assert!(x[5].abs() < 1E-10);
+        assert!((p.eval(&x) - objective).abs() < 1E-10);
+
+        // the relaxation is a lower bound on every binary point that agrees with the fixings
+        for k in 0..(1usize << p.num_x()) {
+            let x_bin = Array1::from_shape_fn(p.num_x(), |i| (k >> i) & 1);
+            if x_bin[2] == 1 && x_bin[5] == 0 {
+                assert!(objective <= p.eval_usize(&x_bin) + 1E-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! This module contains repair functions, that project a solution of a penalty encoded QUBO onto the points that
+//! satisfy the constraints that the penalties were encoding
+//!
+//! Currently the following repairs are implemented:
+//! - One-hot groups, where exactly one variable of each group is active
+
+use crate::qubo::Qubo;
+use ndarray::Array1;
+
+/// Repairs x so that exactly one variable of each group is active, by moving to the nearest one-hot point of each
+/// group in turn.
+///
+/// If some variables of a group are active, then one of them is kept and the rest are cleared. If none are active,
+/// then one variable of the group is activated. In both cases the variable that gives the lowest objective is picked,
+/// so the highest scoring active variable is kept. The repair can increase the objective, as the penalty encoded
+/// minimum might not be feasible, and the groups are repaired one at a time rather than jointly.
+///
+/// The groups are assumed to not overlap, otherwise repairing a later group can break an earlier one.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use hercules::repair::enforce_one_hot;
+/// use ndarray::Array1;
+/// use sprs::CsMat;
+///
+/// let p = Qubo::new(CsMat::<f64>::eye(4));
+/// let groups = vec![vec![0, 1], vec![2, 3]];
+///
+/// // the first group has two active variables and the second has none
+/// let mut x = Array1::from_vec(vec![1, 1, 0, 0]);
+/// enforce_one_hot(&p, &mut x, &groups);
+/// ```
+pub fn enforce_one_hot(qubo: &Qubo, x: &mut Array1<usize>, groups: &[Vec<usize>]) {
+    for group in groups {
+        if group.is_empty() {
+            continue;
+        }
+
+        // keeping one of the active variables is the nearest feasible point, if there are any
+        let active = group
+            .iter()
+            .copied()
+            .filter(|&i| x[i] == 1)
+            .collect::<Vec<usize>>();
+        let candidates = if active.is_empty() { group } else { &active };
+
+        // clear the group, then pick the candidate that gives the lowest objective
+        for &i in group {
+            x[i] = 0;
+        }
+
+        let mut best = candidates[0];
+        let mut best_obj = f64::INFINITY;
+        for &i in candidates {
+            x[i] = 1;
+            let obj = qubo.eval_usize(x);
+            if obj < best_obj {
+                best = i;
+                best_obj = obj;
+            }
+            x[i] = 0;
+        }
+
+        x[best] = 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::qubo::Qubo;
+    use crate::repair::enforce_one_hot;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_enforce_one_hot() {
+        // the linear term prefers x_1 over x_0, and x_3 over x_2
+        let p = Qubo::from_vec(vec![], vec![], vec![], vec![-1.0, -2.0, 1.0, 0.5], 4);
+        let groups = vec![vec![0, 1], vec![2, 3]];
+
+        let mut x = Array1::from_vec(vec![1, 1, 0, 0]);
+        enforce_one_hot(&p, &mut x, &groups);
+        assert_eq!(x, Array1::from_vec(vec![0, 1, 0, 1]));
+
+        // an active variable is kept, even if activating another one would be better
+        let mut x = Array1::from_vec(vec![0, 0, 1, 0]);
+        enforce_one_hot(&p, &mut x, &groups);
+        assert_eq!(x, Array1::from_vec(vec![0, 1, 1, 0]));
+    }
+
+    #[test]
+    fn test_enforce_one_hot_random() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.5);
+        let groups = vec![vec![0, 1, 2, 3], vec![4, 5, 6], vec![7, 8, 9, 10, 11]];
+
+        for k in 0..50usize {
+            let x_0 = Array1::from_shape_fn(12, |i| ((k * 7919) >> i) & 1);
+            let mut x = x_0.clone();
+            enforce_one_hot(&p, &mut x, &groups);
+
+            for group in &groups {
+                let active = group.iter().filter(|&&i| x[i] == 1).collect::<Vec<_>>();
+                assert_eq!(active.len(), 1);
+
+                // the kept variable was active before, unless none of the group was
+                if group.iter().any(|&i| x_0[i] == 1) {
+                    assert_eq!(x_0[*active[0]], 1);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! # Simulated Annealing
+//!
+//! This module contains a simulated annealing heuristic for QUBO problems, for large instances where solving the
+//! problem exactly is not tractable. The temperature can follow either a geometric or a linear cooling schedule.
+
+use crate::qubo::Qubo;
+use ndarray::Array1;
+use smolprng::{Algorithm, PRNG};
+
+/// The type of cooling used to update the temperature over the iterations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cooling {
+    /// The temperature is multiplied by a constant factor each iteration
+    Geometric,
+    /// The temperature is decreased by a constant amount each iteration
+    Linear,
+}
+
+/// The temperature schedule of the simulated annealing heuristic
+#[derive(Clone, Copy, Debug)]
+pub struct AnnealingSchedule {
+    /// The type of cooling
+    pub cooling: Cooling,
+    /// The temperature at the first iteration
+    pub start_temp: f64,
+    /// The temperature at the last iteration
+    pub end_temp: f64,
+    /// The number of iterations, where each iteration proposes a single bit flip
+    pub num_iters: usize,
+}
+
+impl AnnealingSchedule {
+    /// Creates a geometric cooling schedule from `start_temp` to `end_temp` over `num_iters` iterations
+    pub const fn geometric(start_temp: f64, end_temp: f64, num_iters: usize) -> Self {
+        Self {
+            cooling: Cooling::Geometric,
+            start_temp,
+            end_temp,
+            num_iters,
+        }
+    }
+
+    /// Creates a linear cooling schedule from `start_temp` to `end_temp` over `num_iters` iterations
+    pub const fn linear(start_temp: f64, end_temp: f64, num_iters: usize) -> Self {
+        Self {
+            cooling: Cooling::Linear,
+            start_temp,
+            end_temp,
+            num_iters,
+        }
+    }
+
+    /// Computes the temperature at a given iteration of the schedule
+    pub fn temperature(&self, iter: usize) -> f64 {
+        if self.num_iters <= 1 {
+            return self.start_temp;
+        }
+
+        // fraction of the way thru the schedule, in [0, 1]
+        let t = iter as f64 / (self.num_iters - 1) as f64;
+
+        match self.cooling {
+            Cooling::Geometric => self.start_temp * (self.end_temp / self.start_temp).powf(t),
+            Cooling::Linear => self.start_temp + t * (self.end_temp - self.start_temp),
+        }
+    }
+}
+
+/// Runs simulated annealing starting from x_0, and returns the best solution seen over all iterations.
+///
+/// Each iteration proposes flipping a single random variable, which is accepted if it improves the objective, or
+/// otherwise with probability $\exp(-\Delta / T)$. The change in objective of a flip is computed incrementally, by
+/// keeping the gradient of the symmetric form of the QUBO up to date, so each iteration is only proportional to the
+/// nonzeros in the flipped row.
+///
+/// If `history` is given, then the objective of the best solution so far is appended to it after every iteration, for
+/// plotting the convergence of a schedule.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+/// use hercules::simulated_annealing::{simulated_annealing, AnnealingSchedule};
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // generate a random point inside with x in {0, 1}^10
+/// let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+///
+/// // anneal from a temperature of 10 down to 0.01 over 1000 iterations
+/// let schedule = AnnealingSchedule::geometric(10.0, 0.01, 1000);
+/// let x_sol = simulated_annealing(&p, x_0, schedule, &mut prng, None);
+/// ```
+pub fn simulated_annealing<T: Algorithm>(
+    qubo: &Qubo,
+    x_0: Array1<usize>,
+    schedule: AnnealingSchedule,
+    prng: &mut PRNG<T>,
+    mut history: Option<&mut Vec<f64>>,
+) -> Array1<usize> {
+    let num_x = qubo.num_x();
+    if num_x == 0 {
+        return x_0;
+    }
+
+    // with the symmetric form S, the change from flipping x_i is delta_i * (Sx + c)_i + 0.5 * S_ii
+    let q_sym = qubo.make_symmetric();
+    let q_diag = q_sym.q.diag().to_dense();
+    let mut grad = q_sym.eval_grad_usize(&x_0);
+
+    let mut x = x_0;
+    let mut obj = qubo.eval_usize(&x);
+    let mut best_x = x.clone();
+    let mut best_obj = obj;
+
+    for iter in 0..schedule.num_iters {
+        let temp = schedule.temperature(iter);
+
+        // the max value that sparse matrices are addressable is usize::MAX
+        let i = prng.gen_u64() as usize % num_x;
+        let direction = 1.0 - 2.0 * x[i] as f64;
+        let delta = direction * grad[i] + 0.5 * q_diag[i];
+
+        if metropolis(delta, temp, prng) {
+            x[i] = 1 - x[i];
+            obj += delta;
+
+            // the gradient only changes by the flipped row of the symmetric Hessian
+            if let Some(row) = q_sym.q.outer_view(i) {
+                for (j, &value) in row.iter() {
+                    grad[j] += direction * value;
+                }
+            }
+
+            if obj < best_obj {
+                best_obj = obj;
+                best_x.clone_from(&x);
+            }
+        }
+
+        if let Some(history) = history.as_mut() {
+            history.push(best_obj);
+        }
+    }
+
+    best_x
+}
+
+/// The Metropolis criterion, which decides if a change of `delta` in the objective is accepted at temperature `temp`
+///
+/// A change that does not make the objective worse is always accepted, and otherwise it is accepted with probability
+/// $\exp(-\Delta / T)$, so no worse change is accepted at a temperature of zero.
+pub fn metropolis<T: Algorithm>(delta: f64, temp: f64, prng: &mut PRNG<T>) -> bool {
+    delta <= 0.0 || prng.gen_f64() < (-delta / temp).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::initial_points;
+    use crate::local_search::simple_local_search;
+    use crate::simulated_annealing::*;
+    use crate::tests::{make_solver_qubo, make_test_prng};
+
+    #[test]
+    fn test_schedule_endpoints() {
+        let geometric = AnnealingSchedule::geometric(10.0, 0.1, 100);
+        let linear = AnnealingSchedule::linear(10.0, 0.1, 100);
+
+        assert!((geometric.temperature(0) - 10.0).abs() < 1e-10);
+        assert!((geometric.temperature(99) - 0.1).abs() < 1e-10);
+        assert!((linear.temperature(0) - 10.0).abs() < 1e-10);
+        assert!((linear.temperature(99) - 0.1).abs() < 1e-10);
+
+        // a geometric schedule spends more of the iterations at low temperatures
+        assert!(geometric.temperature(50) < linear.temperature(50));
+    }
+
+    #[test]
+    fn test_simulated_annealing() {
+        let p = make_solver_qubo();
+        let mut prng = make_test_prng();
+
+        let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+
+        for schedule in [
+            AnnealingSchedule::geometric(1.0, 0.001, 10_000),
+            AnnealingSchedule::linear(1.0, 0.001, 10_000),
+        ] {
+            let mut history = Vec::new();
+            let x_sa =
+                simulated_annealing(&p, x_0.clone(), schedule, &mut prng, Some(&mut history));
+            let x_ls = simple_local_search(&p, &x_sa, 1000);
+
+            // the best point seen is never worse than the start, and should already be a local minimum
+            assert!(p.eval_usize(&x_sa) <= p.eval_usize(&x_0));
+            assert!((p.eval_usize(&x_sa) - p.eval_usize(&x_ls)).abs() < 1e-10);
+
+            // the recorded best objective never increases, and ends at the returned solution
+            assert_eq!(history.len(), schedule.num_iters);
+            assert!(history.windows(2).all(|w| w[1] <= w[0]));
+            assert!((history[history.len() - 1] - p.eval_usize(&x_sa)).abs() < 1e-10);
+        }
+    }
+}
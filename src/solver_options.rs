@@ -1,31 +1,115 @@
-use crate::branch_stratagy::BranchStrategy;
+use crate::branch_stratagy::{BranchDirection, BranchSelector, BranchStrategy};
 use crate::branch_subproblem::SubProblemSelection;
 use crate::heuristic_stratagy::HeuristicSelection;
+use crate::node_selection::NodeSelectionStrategy;
+use ndarray::Array1;
 use std::collections::HashMap;
 
+/// A function that is called with the new best solution and its objective, each time the incumbent improves
+pub type IncumbentCallback = Box<dyn FnMut(&Array1<usize>, f64) + Send + Sync>;
+
+/// A custom branching rule, which is used in place of the branch strategy when it is set
+pub type BoxedBranchSelector = Box<dyn BranchSelector + Send + Sync>;
+
+/// A policy for restarting the B&B search, where the open nodes are discarded and the search starts again from the
+/// root node, while the incumbent and the pseudo costs are kept
+///
+/// Restarts let the search recover from poor branching decisions made early on, when the pseudo costs knew little
+/// about the problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Restart after `unit` times the terms of the Luby sequence 1, 1, 2, 1, 1, 2, 4, ... of visited nodes, so the
+    /// search is still complete as the runs between restarts grow without bound
+    Luby { unit: usize },
+}
+
+impl RestartPolicy {
+    /// The number of nodes that are visited after `restarts` restarts before the next one
+    pub fn threshold(self, restarts: usize) -> usize {
+        match self {
+            Self::Luby { unit } => unit.max(1).saturating_mul(luby(restarts + 1)),
+        }
+    }
+}
+
+/// The i-th term of the Luby sequence, counting from one
+///
+/// If $i = 2^k - 1$ then the term is $2^{k - 1}$, and otherwise the sequence repeats itself, so the term is the same as
+/// the term $i - 2^{k - 1} + 1$ for the smallest k with $i < 2^k - 1$.
+pub const fn luby(mut i: usize) -> usize {
+    loop {
+        // the smallest k such that i <= 2^k - 1
+        let mut k = 1;
+        while (1usize << k) - 1 < i {
+            k += 1;
+        }
+
+        if i == (1 << k) - 1 {
+            return 1 << (k - 1);
+        }
+        i -= (1 << (k - 1)) - 1;
+    }
+}
+
 /// Options for the B&B solver for run time
 pub struct SolverOptions {
     pub fixed_variables: HashMap<usize, usize>,
+    pub initial_solution: Option<Array1<usize>>,
     pub branch_strategy: BranchStrategy,
+    pub branch_selector: Option<BoxedBranchSelector>,
+    pub branch_direction: BranchDirection,
+    pub strong_branching_candidates: Option<usize>,
+    pub reliability_threshold: usize,
+    pub node_selection: NodeSelectionStrategy,
     pub sub_problem_solver: SubProblemSelection,
     pub heuristic: HeuristicSelection,
     pub max_time: f64,
+    pub max_nodes: Option<usize>,
+    pub max_depth: Option<usize>,
+    pub gap_tolerance: f64,
+    pub relative_gap_tolerance: f64,
     pub seed: usize,
     pub verbose: usize,
     pub threads: usize,
+    pub record_tree: bool,
+    pub on_incumbent: Option<IncumbentCallback>,
+    pub restart_policy: Option<RestartPolicy>,
+    pub dive_frequency: Option<usize>,
+    pub reduced_cost_fixing: bool,
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SolverOptions {
     pub fn new() -> Self {
         Self {
             fixed_variables: HashMap::new(),
+            initial_solution: None,
             branch_strategy: BranchStrategy::MostViolated,
+            branch_selector: None,
+            branch_direction: BranchDirection::TowardRelaxation,
+            strong_branching_candidates: None,
+            reliability_threshold: 4,
+            node_selection: NodeSelectionStrategy::BestBound,
             sub_problem_solver: SubProblemSelection::ClarabelQP,
             heuristic: HeuristicSelection::LocalSearch,
             max_time: 100.0,
+            max_nodes: None,
+            max_depth: None,
+            gap_tolerance: 0.0,
+            relative_gap_tolerance: 0.0,
             seed: 0,
             verbose: 1,
             threads: 1,
+            record_tree: false,
+            on_incumbent: None,
+            restart_policy: None,
+            dive_frequency: None,
+            reduced_cost_fixing: false,
         }
     }
 
@@ -44,21 +128,35 @@ impl SolverOptions {
                 "MostEdges" => self.branch_strategy = BranchStrategy::MostEdges,
                 "LargestEdges" => self.branch_strategy = BranchStrategy::LargestEdges,
                 "MostFixed" => self.branch_strategy = BranchStrategy::MostFixed,
-                "FullStrongBranching" => self.branch_strategy = BranchStrategy::FullStrongBranching,
+                "FullStrongBranch" => self.branch_strategy = BranchStrategy::FullStrongBranch,
                 "PartialStrongBranching" => {
                     self.branch_strategy = BranchStrategy::PartialStrongBranching;
                 }
                 "RoundRobin" => self.branch_strategy = BranchStrategy::RoundRobin,
+                "PseudoCost" => self.branch_strategy = BranchStrategy::PseudoCost,
+                "Reliability" => self.branch_strategy = BranchStrategy::Reliability,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn set_node_selection_strategy(&mut self, strategy: Option<String>) {
+        if let Some(s) = strategy {
+            match s.as_str() {
+                "DepthFirst" => self.node_selection = NodeSelectionStrategy::DepthFirst,
+                "BestBound" => self.node_selection = NodeSelectionStrategy::BestBound,
+                "BreadthFirst" => self.node_selection = NodeSelectionStrategy::BreadthFirst,
                 _ => {}
             }
         }
     }
 
     pub fn set_sub_problem_strategy(&mut self, strategy: Option<String>) {
-        // currently only one strategy is implemented but the structure is left for extension
-        #[allow(clippy::redundant_pattern_matching)]
-        if let Some(_) = strategy {
-            self.sub_problem_solver = SubProblemSelection::ClarabelQP;
+        if let Some(s) = strategy {
+            match s.as_str() {
+                "ClarabelLP" => self.sub_problem_solver = SubProblemSelection::ClarabelLP,
+                _ => self.sub_problem_solver = SubProblemSelection::ClarabelQP,
+            }
         }
     }
 
@@ -74,3 +172,18 @@ impl SolverOptions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::solver_options::{luby, RestartPolicy};
+
+    #[test]
+    fn test_luby_sequence() {
+        let terms = (1..=15).map(luby).collect::<Vec<usize>>();
+        assert_eq!(terms, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+
+        let policy = RestartPolicy::Luby { unit: 10 };
+        assert_eq!(policy.threshold(0), 10);
+        assert_eq!(policy.threshold(6), 40);
+    }
+}
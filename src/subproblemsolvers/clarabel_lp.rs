@@ -1,52 +1,200 @@
+use std::collections::HashMap;
 use clarabel::algebra::CscMatrix;
-use clarabel::solver::DefaultSettings;
+use clarabel::solver::{DefaultSettings, DefaultSolver, IPSolver, NonnegativeConeT};
 use ndarray::Array1;
-use sprs::CsMat;
+use sprs::{CsMat, TriMat};
 use crate::branch_node::QuboBBNode;
 use crate::branch_subproblem::SubProblemSolver;
 use crate::branchbound::BBSolver;
 use crate::qubo::Qubo;
 use crate::branch_subproblem::SubProblemResult;
 
+/// Solves the McCormick LP relaxation of each node, which is built from the QUBO of the solver so nothing is stored
 #[derive(Clone)]
-pub struct ClarabelLPSolver {
-    pub q: CscMatrix,
-    pub c: Array1<f64>,
-}
+pub struct ClarabelLPSolver;
 
 impl ClarabelLPSolver {
     pub fn make_cb_form(p0: &CsMat<f64>) -> CscMatrix {
         let (t, y, u) = p0.to_csc().into_raw_storage();
         CscMatrix::new(p0.rows(), p0.cols(), t, y, u)
     }
+}
+
+impl SubProblemSolver for ClarabelLPSolver {
+    fn solve_lower_bound(&self, bbsolver: &BBSolver, node: &QuboBBNode) -> SubProblemResult {
+        let fixed_variables = node
+            .fixed_variables
+            .iter()
+            .map(|(&i, &x_i)| (i, x_i as f64))
+            .collect::<HashMap<usize, f64>>();
+
+        solve_mccormick_lp(&bbsolver.qubo, &fixed_variables)
+    }
+}
+
+/// Solves the McCormick linearization of the QUBO over $[0, 1]^n$ with the given variables held at fixed values, and
+/// returns the lower bound and the relaxed solution in the original space
+///
+/// Over binary points $x_i^2 = x_i$, so the diagonal of Q moves into the linear term, and every product $x_i x_j$ of
+/// the free variables is replaced by a variable $y_{ij}$ with the McCormick envelope of the product over $[0, 1]^2$
+///
+/// $$ \max(0, x_i + x_j - 1) \leq y_{ij} \leq \min(x_i, x_j) $$
+///
+/// Only the side of the envelope that the objective pushes $y_{ij}$ against is needed, which is the lower side for a
+/// positive coefficient and the upper side for a negative one. The bound is often weaker than the QP relaxation, but
+/// it is a linear program and does not need the QUBO to be convex.
+///
+/// # Panics
+///
+/// Will panic if a fixed variable is not a variable of the QUBO.
+pub fn solve_mccormick_lp(
+    qubo: &Qubo,
+    fixed_variables: &HashMap<usize, f64>,
+) -> (f64, Array1<f64>) {
+    // project out the fixed variables
+    let free = (0..qubo.num_x())
+        .filter(|i| !fixed_variables.contains_key(i))
+        .collect::<Vec<usize>>();
+    let (sub_qubo, members) = qubo.induced_subproblem(&free, fixed_variables);
+
+    // the fixed variables are part of the solution whether or not anything is left to solve
+    let mut x = Array1::<f64>::zeros(qubo.num_x());
+    for (&i, &x_i) in fixed_variables {
+        x[i] = x_i;
+    }
+
+    let num_free = members.len();
+    if num_free == 0 {
+        return (sub_qubo.offset, x);
+    }
+
+    // the coefficient of x_i x_j in the objective is the (i, j) entry of the symmetric form, for i < j
+    let sub_sym = sub_qubo.make_symmetric();
+    let edges = sub_sym
+        .quadratic_terms()
+        .filter(|&(i, j, value)| i < j && value != 0.0)
+        .collect::<Vec<(usize, usize, f64)>>();
+
+    // the costs of the x variables, followed by the costs of the y variables
+    let num_vars = num_free + edges.len();
+    let mut cost = Array1::<f64>::zeros(num_vars);
+    for k in 0..num_free {
+        cost[k] = sub_sym.c[k] + 0.5 * sub_sym.q.get(k, k).unwrap_or(&0.0);
+    }
+
+    // generate the constraint matrix, where each row is a constraint of the form a^T z <= b
+    let num_rows = 2 * num_free + 2 * edges.len();
+    let mut a = TriMat::new((num_rows, num_vars));
+    let mut b = Array1::<f64>::zeros(num_rows);
+
+    // add the box constraints of the x variables
+    for k in 0..num_free {
+        a.add_triplet(2 * k, k, 1.0);
+        a.add_triplet(2 * k + 1, k, -1.0);
+        b[2 * k] = 1.0;
+    }
 
-    pub fn new(qubo: &Qubo) -> Self {
-        let q_new = Self::make_cb_form(&(qubo.q));
-        Self {
-            q: q_new,
-            c: qubo.c.clone(),
+    // add the side of the McCormick envelope that bounds each product
+    for (e, &(i, j, value)) in edges.iter().enumerate() {
+        let y = num_free + e;
+        let row = 2 * num_free + 2 * e;
+        cost[y] = value;
+
+        if value > 0.0 {
+            // y >= 0 and y >= x_i + x_j - 1
+            a.add_triplet(row, y, -1.0);
+            a.add_triplet(row + 1, i, 1.0);
+            a.add_triplet(row + 1, j, 1.0);
+            a.add_triplet(row + 1, y, -1.0);
+            b[row + 1] = 1.0;
+        } else {
+            // y <= x_i and y <= x_j
+            a.add_triplet(row, y, 1.0);
+            a.add_triplet(row, i, -1.0);
+            a.add_triplet(row + 1, y, 1.0);
+            a.add_triplet(row + 1, j, -1.0);
         }
     }
+
+    let settings = DefaultSettings {
+        verbose: false,
+        ..Default::default()
+    };
+    let cones = [NonnegativeConeT(num_rows)];
+
+    // set up the solver with no quadratic term, as this is a linear program
+    let mut solver = DefaultSolver::new(
+        &CscMatrix::zeros((num_vars, num_vars)),
+        cost.as_slice().unwrap(), // unwrap is safe because Array1 is stored in contiguous memory
+        &ClarabelLPSolver::make_cb_form(&a.to_csc()),
+        b.as_slice().unwrap(),
+        &cones,
+        settings,
+    );
+
+    solver.solve();
+
+    // map out the free variables
+    for (k, &i) in members.iter().enumerate() {
+        x[i] = solver.solution.x[k];
+    }
+
+    let lower_bound = sub_qubo.offset
+        + cost
+            .iter()
+            .zip(solver.solution.x.iter())
+            .map(|(cost_k, z_k)| cost_k * z_k)
+            .sum::<f64>();
+
+    (lower_bound, x)
 }
-impl SubProblemSolver for ClarabelLPSolver {
 
+#[cfg(test)]
+mod tests {
+    use crate::exact::brute_force;
+    use crate::qubo::Qubo;
+    use crate::subproblemsolvers::clarabel_lp::solve_mccormick_lp;
+    use crate::tests::make_test_prng;
+    use std::collections::HashMap;
 
-    fn solve_lower_bound(&self, bbsolver: &BBSolver, node: &QuboBBNode) -> SubProblemResult {
+    #[test]
+    fn test_mccormick_lp_bound() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+        let (_, opt) = brute_force(&p);
 
+        // the relaxation is a lower bound of the optimum, and the solution is in the unit box
+        let (bound, x) = solve_mccormick_lp(&p, &HashMap::new());
+        assert!(bound <= opt + 1E-6);
+        assert!(x.iter().all(|&x_i| (-1E-6..=1.0 + 1E-6).contains(&x_i)));
+    }
 
-        // this solves the glover's reformulation of the problem, e.g. the QUBO is transformed into a linear problem
+    #[test]
+    fn test_mccormick_lp_fixed() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(8, &mut prng, 0.5);
 
-        // settings.presolve_enable = true;
-        //
-        // // first find the number of edges that we are dealing with in this qubo
-        // let edges = &bbsolver.qubo.q.iter().filter(|(_, (i,j))| i < j).collect::<Vec<_>>();
-        // let edge_weights = Array1::from(edges.iter().map(|&v| v.0));
-        // let num_aux_vars = edge_weights.count();
-        //
-        // let num_unfixed = bbsolver.qubo.num_x() - node.fixed_variables.len();
-        //
-        // let P = CscMatrix::zeros((num_unfixed + num_aux_vars, num_unfixed + num_aux_vars));
+        // with every variable fixed, the bound is the objective at the fixed point
+        let x_fixed = ndarray::Array1::from_shape_fn(p.num_x(), |i| i % 2);
+        let fixed = (0..p.num_x())
+            .map(|i| (i, x_fixed[i] as f64))
+            .collect::<HashMap<usize, f64>>();
+        let (bound, x) = solve_mccormick_lp(&p, &fixed);
+        assert!((bound - p.eval_usize(&x_fixed)).abs() < 1E-10);
+        assert_eq!(x, x_fixed.mapv(|x_i| x_i as f64));
 
-        !unimplemented!("This function is not implemented yet")
+        // with all but one variable fixed, the relaxation is exact
+        let mut fixed = fixed;
+        fixed.remove(&3);
+        let (bound, _) = solve_mccormick_lp(&p, &fixed);
+        let best = [0, 1]
+            .iter()
+            .map(|&x_3| {
+                let mut x = x_fixed.clone();
+                x[3] = x_3;
+                p.eval_usize(&x)
+            })
+            .fold(f64::INFINITY, f64::min);
+        assert!((bound - best).abs() < 1E-6);
     }
 }
@@ -19,69 +19,7 @@ impl SubProblemSolver for ClarabelQPSolver {
 
     fn solve_lower_bound(&self, bbsolver: &BBSolver, node: &QuboBBNode) -> SubProblemResult {
         // solve QP associated with the node
-        // generate default settings
-        let settings = DefaultSettings {
-            verbose: false,
-            ..Default::default()
-        };
-
-        // find projected subproblem
-        let (sub_qubo, unfixed_map, _constant) =
-            make_sub_problem(&bbsolver.qubo, node.fixed_variables.clone());
-
-        // let min_eig = trial_sub_qubo.hess_eigenvalues();
-        // let min_eig = min_eig.iter().fold(f64::INFINITY, |acc, &x| x.min(acc));
-        // let sub_qubo = trial_sub_qubo.make_diag_transform(0.0001 - min_eig);
-
-        // generate the constraint matrix
-        let A_size = 2 * sub_qubo.num_x();
-        let mut A = TriMat::new((A_size, sub_qubo.num_x()));
-        let mut b = Array1::<f64>::zeros(A_size);
-
-        // add the inequality constraints
-        for (index, i) in (0..sub_qubo.num_x()).enumerate() {
-            let offset = index * 2;
-            A.add_triplet(offset, i, 1.0);
-            A.add_triplet(offset + 1, i, -1.0);
-            b[offset] = 1.0;
-            b[offset + 1] = 0.0;
-        }
-
-        // convert the matrix to CSC format and then Clarabel format
-        let A_csc = A.to_csc();
-        let A_clara = Self::make_cb_form(&A_csc);
-
-        // generate the cones for the solver
-        let cones = [NonnegativeConeT(2 * sub_qubo.num_x())];
-
-        // set up the solver with the matrices
-        let mut solver = DefaultSolver::new(
-            &Self::make_cb_form(&sub_qubo.q),
-            sub_qubo.c.as_slice().unwrap(), // unwrap is safe because Array1 is stored in contiguous memory
-            &A_clara,
-            b.as_slice().unwrap(),
-            &cones,
-            settings,
-        );
-
-        // solve the optimization problem
-        solver.solve();
-
-        // convert the solution back to the original space
-        let mut x = Array1::<f64>::zeros(bbsolver.qubo.num_x());
-
-        // map out the unfixed variables
-        for (&original, &new) in &unfixed_map {
-            x[original] = solver.solution.x[new];
-        }
-
-        // map out the fixed variables
-        for (&i, &val) in &node.fixed_variables {
-            x[i] = val as f64;
-        }
-
-        let obj = bbsolver.qubo.eval(&x);
-        (obj, x)
+        solve_relaxed_qp(&bbsolver.qubo, &node.fixed_variables)
     }
 }
 
@@ -99,6 +37,103 @@ impl ClarabelQPSolver {
     }
 }
 
+/// Solves the continuous relaxation of the QUBO over $[0, 1]^n$ with the given variables fixed, and returns the
+/// objective and the relaxed solution in the original space
+///
+/// # Panics
+///
+/// Will panic if a fixed variable is not a variable of the QUBO.
+pub fn solve_relaxed_qp(
+    qubo: &Qubo,
+    fixed_variables: &HashMap<usize, usize>,
+) -> (f64, Array1<f64>) {
+    let (obj, x, _) = solve_relaxed_qp_with_duals(qubo, fixed_variables);
+    (obj, x)
+}
+
+/// Solves the continuous relaxation like `solve_relaxed_qp`, and also returns the reduced cost of each variable, taken
+/// from the duals of the bound constraints
+///
+/// The reduced cost of a free variable is the dual of $x_i \geq 0$ minus the dual of $x_i \leq 1$, which is the
+/// gradient of the relaxation at the relaxed solution. It is positive if the variable is held at zero, and negative
+/// if it is held at one, and its size is a lower bound on the increase of the objective when the variable is moved to
+/// its other bound. The reduced cost of a fixed variable is zero.
+///
+/// # Panics
+///
+/// Will panic if a fixed variable is not a variable of the QUBO.
+pub fn solve_relaxed_qp_with_duals(
+    qubo: &Qubo,
+    fixed_variables: &HashMap<usize, usize>,
+) -> (f64, Array1<f64>, Array1<f64>) {
+    // generate default settings
+    let settings = DefaultSettings {
+        verbose: false,
+        ..Default::default()
+    };
+
+    // find projected subproblem
+    let (sub_qubo, unfixed_map, _constant) = make_sub_problem(qubo, fixed_variables.clone());
+
+    // let min_eig = trial_sub_qubo.hess_eigenvalues();
+    // let min_eig = min_eig.iter().fold(f64::INFINITY, |acc, &x| x.min(acc));
+    // let sub_qubo = trial_sub_qubo.make_diag_transform(0.0001 - min_eig);
+
+    // generate the constraint matrix
+    let A_size = 2 * sub_qubo.num_x();
+    let mut A = TriMat::new((A_size, sub_qubo.num_x()));
+    let mut b = Array1::<f64>::zeros(A_size);
+
+    // add the inequality constraints
+    for (index, i) in (0..sub_qubo.num_x()).enumerate() {
+        let offset = index * 2;
+        A.add_triplet(offset, i, 1.0);
+        A.add_triplet(offset + 1, i, -1.0);
+        b[offset] = 1.0;
+        b[offset + 1] = 0.0;
+    }
+
+    // convert the matrix to CSC format and then Clarabel format
+    let A_csc = A.to_csc();
+    let A_clara = ClarabelQPSolver::make_cb_form(&A_csc);
+
+    // generate the cones for the solver
+    let cones = [NonnegativeConeT(2 * sub_qubo.num_x())];
+
+    // set up the solver with the matrices
+    let mut solver = DefaultSolver::new(
+        &ClarabelQPSolver::make_cb_form(&sub_qubo.q),
+        sub_qubo.c.as_slice().unwrap(), // unwrap is safe because Array1 is stored in contiguous memory
+        &A_clara,
+        b.as_slice().unwrap(),
+        &cones,
+        settings,
+    );
+
+    // solve the optimization problem, this is a cold start at every node, as the solve of Clarabel 0.9 always computes
+    // its own initial point and has no way to take the solution of the parent node as a starting guess
+    solver.solve();
+
+    // convert the solution back to the original space
+    let mut x = Array1::<f64>::zeros(qubo.num_x());
+
+    let mut reduced_costs = Array1::<f64>::zeros(qubo.num_x());
+
+    // map out the unfixed variables, the bound constraints of each variable are the rows x <= 1 and -x <= 0
+    for (&original, &new) in &unfixed_map {
+        x[original] = solver.solution.x[new];
+        reduced_costs[original] = solver.solution.z[2 * new + 1] - solver.solution.z[2 * new];
+    }
+
+    // map out the fixed variables
+    for (&i, &val) in fixed_variables {
+        x[i] = val as f64;
+    }
+
+    let obj = qubo.eval(&x);
+    (obj, x, reduced_costs)
+}
+
 fn make_sub_problem(
     qubo: &Qubo,
     fixed_vars: HashMap<usize, usize>,
@@ -169,7 +204,7 @@ fn make_sub_problem(
 
 #[cfg(test)]
 mod tests {
-    use crate::subproblemsolvers::clarabel_qp::ClarabelQPSolver;
+    use crate::subproblemsolvers::clarabel_qp::{solve_relaxed_qp_with_duals, ClarabelQPSolver};
     use crate::qubo::Qubo;
     use crate::tests::make_solver_qubo;
     use ndarray::Array1;
@@ -193,6 +228,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_reduced_costs() {
+        // the reduced costs of the free variables are the gradient of the relaxation at the relaxed solution
+        let p = make_solver_qubo().convex_symmetric_form();
+        let fixed_variables = HashMap::from([(0, 1), (1, 0)]);
+
+        let (obj, x, reduced_costs) = solve_relaxed_qp_with_duals(&p, &fixed_variables);
+        let grad = p.eval_grad(&x);
+
+        assert!((obj - p.eval(&x)).abs() < 1E-10);
+        for i in 0..p.num_x() {
+            if fixed_variables.contains_key(&i) {
+                assert!(reduced_costs[i].abs() < 1E-10);
+            } else {
+                assert!((reduced_costs[i] - grad[i]).abs() < 1E-5);
+            }
+        }
+    }
+
     #[test]
     fn test_generate_sub_problem_1() {
         // the idea of this test is, given a QUBO & some fixed variables, generate an equivalent problem
@@ -0,0 +1,158 @@
+//! # Tabu Search
+//!
+//! This module contains a tabu search heuristic for QUBO problems. At each iteration the best single bit flip is
+//! taken, even if it makes the objective worse, and recently flipped variables are made tabu so that the search does
+//! not immediately cycle back to the previous local minimum.
+
+use crate::qubo::Qubo;
+use ndarray::Array1;
+
+/// Runs tabu search starting from x_0 for `max_iters` iterations, and returns the best solution seen.
+///
+/// After a variable is flipped it can not be flipped again for `tenure` iterations, unless flipping it would give a
+/// better objective than the best solution found so far (the aspiration criterion). The change in objective of every
+/// flip is computed incrementally, by keeping the gradient of the symmetric form of the QUBO up to date.
+///
+/// If `history` is given, then the objective of the best solution so far is appended to it after every iteration, for
+/// plotting the convergence of the search.
+///
+/// Example:
+/// ``` rust
+/// use hercules::qubo::Qubo;
+/// use smolprng::{PRNG, JsfLarge};
+/// use hercules::initial_points;
+/// use hercules::tabu::tabu_search;
+///
+/// // generate a random QUBO
+/// let mut prng = PRNG {
+///   generator: JsfLarge::default(),
+/// };
+/// let p = Qubo::make_random_qubo(10, &mut prng, 0.5);
+///
+/// // generate a random point inside with x in {0, 1}^10
+/// let x_0 = initial_points::generate_random_binary_point(p.num_x(), &mut prng, 0.5);
+///
+/// // perform a tabu search with a tenure of 3 for 100 iterations
+/// let x_sol = tabu_search(&p, x_0.clone(), 3, 100, None);
+///
+/// // record the best objective of each iteration
+/// let mut history = Vec::new();
+/// let x_sol = tabu_search(&p, x_0, 3, 100, Some(&mut history));
+/// ```
+pub fn tabu_search(
+    qubo: &Qubo,
+    x_0: Array1<usize>,
+    tenure: usize,
+    max_iters: usize,
+    mut history: Option<&mut Vec<f64>>,
+) -> Array1<usize> {
+    let num_x = qubo.num_x();
+
+    // with the symmetric form S, the change from flipping x_i is delta_i * (Sx + c)_i + 0.5 * S_ii
+    let q_sym = qubo.make_symmetric();
+    let q_diag = q_sym.q.diag().to_dense();
+    let mut grad = q_sym.eval_grad_usize(&x_0);
+
+    // the first iteration that each variable is allowed to be flipped
+    let mut tabu_until = vec![0usize; num_x];
+
+    let mut x = x_0;
+    let mut obj = qubo.eval_usize(&x);
+    let mut best_x = x.clone();
+    let mut best_obj = obj;
+
+    for iter in 0..max_iters {
+        // find the best flip that is either not tabu, or that satisfies the aspiration criterion
+        let mut best_move = None;
+        let mut best_delta = f64::INFINITY;
+
+        for i in 0..num_x {
+            let delta = (1.0 - 2.0 * x[i] as f64) * grad[i] + 0.5 * q_diag[i];
+            let is_tabu = tabu_until[i] > iter;
+            let aspiration = obj + delta < best_obj;
+
+            if (!is_tabu || aspiration) && delta < best_delta {
+                best_move = Some(i);
+                best_delta = delta;
+            }
+        }
+
+        // if every variable is tabu there is nothing to do
+        let Some(i) = best_move else {
+            break;
+        };
+
+        let direction = 1.0 - 2.0 * x[i] as f64;
+        x[i] = 1 - x[i];
+        obj += best_delta;
+        tabu_until[i] = iter + tenure + 1;
+
+        // the gradient only changes by the flipped row of the symmetric Hessian
+        if let Some(row) = q_sym.q.outer_view(i) {
+            for (j, &value) in row.iter() {
+                grad[j] += direction * value;
+            }
+        }
+
+        if obj < best_obj {
+            best_obj = obj;
+            best_x.clone_from(&x);
+        }
+
+        if let Some(history) = history.as_mut() {
+            history.push(best_obj);
+        }
+    }
+
+    best_x
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::qubo::Qubo;
+    use crate::tabu::tabu_search;
+    use crate::tests::make_test_prng;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_tabu_search_optimal() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(12, &mut prng, 0.5);
+
+        // find the optimal solution by enumeration
+        let num_x = p.num_x();
+        let opt = (0..(1usize << num_x))
+            .map(|k| p.eval_usize(&Array1::from_shape_fn(num_x, |i| (k >> i) & 1)))
+            .fold(f64::INFINITY, f64::min);
+
+        let x_0 = Array1::zeros(num_x);
+        let x_tabu = tabu_search(&p, x_0, 3, 200, None);
+
+        assert!((p.eval_usize(&x_tabu) - opt).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_tabu_search_improves() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(100, &mut prng, 0.1);
+
+        let x_0 = Array1::ones(p.num_x());
+        let x_tabu = tabu_search(&p, x_0.clone(), 10, 500, None);
+
+        assert!(p.eval_usize(&x_tabu) <= p.eval_usize(&x_0));
+    }
+
+    #[test]
+    fn test_tabu_search_history() {
+        let mut prng = make_test_prng();
+        let p = Qubo::make_random_qubo(50, &mut prng, 0.1);
+
+        let mut history = Vec::new();
+        let x_tabu = tabu_search(&p, Array1::zeros(p.num_x()), 5, 100, Some(&mut history));
+
+        // the best objective so far never increases, and ends at the returned solution
+        assert_eq!(history.len(), 100);
+        assert!(history.windows(2).all(|w| w[1] <= w[0]));
+        assert!((history[99] - p.eval_usize(&x_tabu)).abs() < 1e-10);
+    }
+}
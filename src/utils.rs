@@ -103,6 +103,30 @@ pub fn calculate_hamming_distance(x_0: &Array1<usize>, x_1: &Array1<usize>) -> u
     distance
 }
 
+/// Returns the indices of the bits that are different between two points, in increasing order.
+/// The number of indices is the hamming distance of the two points.
+///
+/// Example:
+/// ``` rust
+/// use hercules::utils;
+/// use ndarray::Array1;
+///
+/// let x_0 = Array1::from_vec(vec![1, 0, 1, 1]);
+/// let x_1 = Array1::from_vec(vec![0, 0, 1, 0]);
+///
+/// // the points differ in the first and last bits
+/// let diff = utils::solution_diff(&x_0, &x_1);
+/// assert_eq!(diff, vec![0, 3]);
+/// ```
+pub fn solution_diff(x_0: &Array1<usize>, x_1: &Array1<usize>) -> Vec<usize> {
+    x_0.iter()
+        .zip(x_1.iter())
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(i, _)| i)
+        .collect()
+}
+
 /// Given a point, x, determine if it is fractional e.g. not just 0.0f64 or 1.0f64
 ///
 /// Example:
@@ -187,6 +211,26 @@ pub fn get_best_point(qubo: &Qubo, points: &Vec<Array1<usize>>) -> Array1<usize>
     best_point
 }
 
+/// Maps a solution of a reordered QUBO back to the original variable order, where `perm[k]` is the original index of
+/// the k-th variable of the reordered QUBO, as returned by `Qubo::reorder_rcm`.
+///
+/// Example:
+/// ``` rust
+/// use hercules::utils;
+/// use ndarray::Array1;
+///
+/// let y = Array1::from_vec(vec![1, 0, 0]);
+/// let x = utils::unpermute(&y, &[2, 0, 1]);
+/// assert_eq!(x, Array1::from_vec(vec![0, 0, 1]));
+/// ```
+pub fn unpermute(y: &Array1<usize>, perm: &[usize]) -> Array1<usize> {
+    let mut x = Array1::zeros(y.len());
+    for (k, &i) in perm.iter().enumerate() {
+        x[i] = y[k];
+    }
+    x
+}
+
 /// Given a floating point vector, round it to the nearest 0 or 1.
 pub fn rounded_vector(x: &Array1<f64>) -> Array1<usize> {
     let mut rounded_solution = Array1::zeros(x.len());
@@ -227,6 +271,17 @@ mod tests {
         assert_eq!(x_1, target);
     }
 
+    #[test]
+    fn test_solution_diff() {
+        let x_0 = Array1::from_vec(vec![1, 0, 1, 0, 1]);
+        let x_1 = Array1::from_vec(vec![1, 1, 0, 0, 1]);
+        let diff = solution_diff(&x_0, &x_1);
+
+        assert_eq!(diff, vec![1, 2]);
+        assert_eq!(diff.len(), calculate_hamming_distance(&x_0, &x_1));
+        assert!(solution_diff(&x_0, &x_0).is_empty());
+    }
+
     #[test]
     fn test_round_vector() {
         let point = Array1::from_vec(vec![0.1, 0.9, 0.51, 0.6, 0.4]);